@@ -0,0 +1,229 @@
+//! Axis-aligned bounding boxes
+//!
+//! `Aabb` is used by the 3D collision code; `Aabb2` is its 2D counterpart for the
+//! sprite-based rpg world. Both treat their boundary as inside, so touching boxes
+//! intersect and points on a face are contained.
+
+use raylib::prelude::{Vector2, Vector3};
+
+/// 3D axis-aligned bounding box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vector3,
+    pub max: Vector3,
+}
+
+impl Aabb {
+    pub fn new(min: Vector3, max: Vector3) -> Self {
+        Self { min, max }
+    }
+
+    /// Box of the given full size centered on `center`
+    pub fn from_center_size(center: Vector3, size: Vector3) -> Self {
+        let half = size / 2.0;
+        Self {
+            min: center - half,
+            max: center + half,
+        }
+    }
+
+    pub fn center(&self) -> Vector3 {
+        (self.min + self.max) / 2.0
+    }
+
+    pub fn size(&self) -> Vector3 {
+        self.max - self.min
+    }
+
+    pub fn half_extents(&self) -> Vector3 {
+        self.size() / 2.0
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x
+            && self.max.x >= other.min.x
+            && self.min.y <= other.max.y
+            && self.max.y >= other.min.y
+            && self.min.z <= other.max.z
+            && self.max.z >= other.min.z
+    }
+
+    pub fn contains_point(&self, point: Vector3) -> bool {
+        point.x >= self.min.x
+            && point.x <= self.max.x
+            && point.y >= self.min.y
+            && point.y <= self.max.y
+            && point.z >= self.min.z
+            && point.z <= self.max.z
+    }
+
+    /// Grow the box by `amount` on every side
+    pub fn expand(&self, amount: f32) -> Self {
+        let margin = Vector3::new(amount, amount, amount);
+        Self {
+            min: self.min - margin,
+            max: self.max + margin,
+        }
+    }
+
+    /// Point inside (or on) the box nearest to `point`
+    pub fn closest_point(&self, point: Vector3) -> Vector3 {
+        Vector3::new(
+            point.x.clamp(self.min.x, self.max.x),
+            point.y.clamp(self.min.y, self.max.y),
+            point.z.clamp(self.min.z, self.max.z),
+        )
+    }
+
+    /// Outward normal of the face nearest to `point` (usually a point on the surface)
+    pub fn face_normal(&self, point: Vector3) -> Vector3 {
+        let faces = [
+            ((point.x - self.min.x).abs(), Vector3::new(-1.0, 0.0, 0.0)),
+            ((point.x - self.max.x).abs(), Vector3::new(1.0, 0.0, 0.0)),
+            ((point.y - self.min.y).abs(), Vector3::new(0.0, -1.0, 0.0)),
+            ((point.y - self.max.y).abs(), Vector3::new(0.0, 1.0, 0.0)),
+            ((point.z - self.min.z).abs(), Vector3::new(0.0, 0.0, -1.0)),
+            ((point.z - self.max.z).abs(), Vector3::new(0.0, 0.0, 1.0)),
+        ];
+        faces
+            .into_iter()
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, normal)| normal)
+            .unwrap_or(Vector3::up())
+    }
+
+    /// Distance along the ray to where it enters the box (slab test)
+    ///
+    /// `direction` doesn't need to be normalized; the result is in multiples of it.
+    /// Returns `Some(0.0)` when the origin is already inside.
+    pub fn ray_intersection(&self, origin: Vector3, direction: Vector3) -> Option<f32> {
+        let mut t_min = 0.0_f32;
+        let mut t_max = f32::INFINITY;
+
+        for (o, d, min, max) in [
+            (origin.x, direction.x, self.min.x, self.max.x),
+            (origin.y, direction.y, self.min.y, self.max.y),
+            (origin.z, direction.z, self.min.z, self.max.z),
+        ] {
+            if d.abs() < f32::EPSILON {
+                // Parallel to this slab: must already be between its planes
+                if o < min || o > max {
+                    return None;
+                }
+                continue;
+            }
+            let (t1, t2) = ((min - o) / d, (max - o) / d);
+            t_min = t_min.max(t1.min(t2));
+            t_max = t_max.min(t1.max(t2));
+            if t_min > t_max {
+                return None;
+            }
+        }
+        Some(t_min)
+    }
+}
+
+/// 2D axis-aligned bounding box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb2 {
+    pub min: Vector2,
+    pub max: Vector2,
+}
+
+impl Aabb2 {
+    pub fn new(min: Vector2, max: Vector2) -> Self {
+        Self { min, max }
+    }
+
+    /// Box of the given full size centered on `center`
+    pub fn from_center_size(center: Vector2, size: Vector2) -> Self {
+        let half = size / 2.0;
+        Self {
+            min: center - half,
+            max: center + half,
+        }
+    }
+
+    pub fn center(&self) -> Vector2 {
+        (self.min + self.max) / 2.0
+    }
+
+    pub fn size(&self) -> Vector2 {
+        self.max - self.min
+    }
+
+    pub fn intersects(&self, other: &Aabb2) -> bool {
+        self.min.x <= other.max.x
+            && self.max.x >= other.min.x
+            && self.min.y <= other.max.y
+            && self.max.y >= other.min.y
+    }
+
+    pub fn contains_point(&self, point: Vector2) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x && point.y >= self.min.y && point.y <= self.max.y
+    }
+
+    /// Grow the box by `amount` on every side
+    pub fn expand(&self, amount: f32) -> Self {
+        let margin = Vector2::new(amount, amount);
+        Self {
+            min: self.min - margin,
+            max: self.max + margin,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aabb_intersection() {
+        let a = Aabb::from_center_size(Vector3::zero(), Vector3::new(2.0, 2.0, 2.0));
+
+        let overlapping = Aabb::from_center_size(Vector3::new(1.5, 0.0, 0.0), Vector3::new(2.0, 2.0, 2.0));
+        let touching = Aabb::from_center_size(Vector3::new(2.0, 0.0, 0.0), Vector3::new(2.0, 2.0, 2.0));
+        let disjoint = Aabb::from_center_size(Vector3::new(0.0, 0.0, 2.5), Vector3::new(2.0, 2.0, 2.0));
+
+        assert!(a.intersects(&overlapping));
+        assert!(overlapping.intersects(&a));
+        assert!(a.intersects(&touching));
+        assert!(!a.intersects(&disjoint));
+        assert!(a.expand(0.5).intersects(&disjoint));
+        assert_eq!(a.center(), Vector3::zero());
+    }
+
+    #[test]
+    fn test_aabb_contains_point_on_boundary() {
+        let a = Aabb::new(Vector3::new(-1.0, 0.0, -1.0), Vector3::new(1.0, 2.0, 1.0));
+
+        assert!(a.contains_point(Vector3::new(0.0, 1.0, 0.0)));
+        assert!(a.contains_point(Vector3::new(1.0, 2.0, -1.0)));
+        assert!(a.contains_point(Vector3::new(-1.0, 0.5, 0.0)));
+        assert!(!a.contains_point(Vector3::new(1.001, 1.0, 0.0)));
+        assert_eq!(a.closest_point(Vector3::new(5.0, -3.0, 0.5)), Vector3::new(1.0, 0.0, 0.5));
+    }
+
+    #[test]
+    fn test_aabb_ray_intersection() {
+        let a = Aabb::from_center_size(Vector3::new(0.0, 0.0, -5.0), Vector3::new(2.0, 2.0, 2.0));
+
+        assert_eq!(a.ray_intersection(Vector3::zero(), Vector3::new(0.0, 0.0, -1.0)), Some(4.0));
+        assert_eq!(a.ray_intersection(Vector3::zero(), Vector3::new(0.0, 0.0, 1.0)), None);
+        assert_eq!(a.ray_intersection(Vector3::new(3.0, 0.0, 0.0), Vector3::new(0.0, 0.0, -1.0)), None);
+        assert_eq!(a.ray_intersection(Vector3::new(0.0, 0.0, -5.0), Vector3::new(1.0, 0.0, 0.0)), Some(0.0));
+    }
+
+    #[test]
+    fn test_aabb2_intersection_and_containment() {
+        let a = Aabb2::from_center_size(Vector2::new(0.0, 0.0), Vector2::new(4.0, 2.0));
+
+        assert!(a.intersects(&Aabb2::new(Vector2::new(1.0, 0.0), Vector2::new(3.0, 3.0))));
+        assert!(a.intersects(&Aabb2::new(Vector2::new(2.0, 1.0), Vector2::new(3.0, 3.0))));
+        assert!(!a.intersects(&Aabb2::new(Vector2::new(2.1, 0.0), Vector2::new(3.0, 3.0))));
+
+        assert!(a.contains_point(Vector2::new(2.0, -1.0)));
+        assert!(!a.contains_point(Vector2::new(0.0, 1.01)));
+        assert_eq!(a.size(), Vector2::new(4.0, 2.0));
+    }
+}
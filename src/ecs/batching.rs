@@ -0,0 +1,426 @@
+//! Render batching for repeated primitive shapes
+//!
+//! Entities that share the same shape, size, color and level of detail (or the
+//! same model file) are grouped so they can be drawn from one GPU mesh instead of re-submitting the
+//! geometry per entity. With an instancing shader loaded (see
+//! `resources/shaders/instancing.vs`), a whole batch is a single
+//! `DrawMeshInstanced` call.
+
+use raylib::prelude::*;
+use std::collections::HashMap;
+use std::ffi::CString;
+use super::components::{RenderShape, Renderable, Transform};
+use super::entity::{EntityId, World};
+
+/// Groups smaller than this are drawn through the regular per-entity path
+pub const MIN_BATCH_SIZE: usize = 2;
+
+/// Hashable identity of a shape (floats stored as raw bits)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BatchKey {
+    Cube { size: [u32; 3], color: [u8; 4] },
+    /// `segments` is the LOD tier's ring/slice count
+    Sphere { radius: u32, segments: i32, color: [u8; 4] },
+    Cylinder { radius: u32, height: u32, segments: i32, color: [u8; 4] },
+    Model { path: String },
+}
+
+impl BatchKey {
+    /// Build a key for a shape tessellated with `segments` (ignored by cubes and
+    /// models), or None if the shape can't be batched
+    pub fn from_shape(shape: &RenderShape, segments: i32) -> Option<Self> {
+        match shape {
+            RenderShape::Cube { size, color } => Some(BatchKey::Cube {
+                size: [size.x.to_bits(), size.y.to_bits(), size.z.to_bits()],
+                color: *color,
+            }),
+            RenderShape::Sphere { radius, color } => Some(BatchKey::Sphere {
+                radius: radius.to_bits(),
+                segments,
+                color: *color,
+            }),
+            RenderShape::Cylinder { radius, height, color } => Some(BatchKey::Cylinder {
+                radius: radius.to_bits(),
+                height: height.to_bits(),
+                segments,
+                color: *color,
+            }),
+            RenderShape::Model { path } => Some(BatchKey::Model { path: path.clone() }),
+        }
+    }
+
+    /// Shape color; models use their own materials and report white
+    pub fn color(&self) -> Color {
+        let c = match self {
+            BatchKey::Cube { color, .. }
+            | BatchKey::Sphere { color, .. }
+            | BatchKey::Cylinder { color, .. } => color,
+            BatchKey::Model { .. } => return Color::WHITE,
+        };
+        Color::new(c[0], c[1], c[2], c[3])
+    }
+}
+
+/// A group of entities drawn with the same mesh
+#[derive(Debug, Clone)]
+pub struct RenderBatch {
+    pub key: BatchKey,
+    pub entities: Vec<EntityId>,
+    pub positions: Vec<Vector3>,
+}
+
+impl RenderBatch {
+    /// Model file shared by the batch, for `BatchKey::Model`
+    pub fn model_path(&self) -> Option<&str> {
+        match &self.key {
+            BatchKey::Model { path } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Per-instance model matrices for `DrawMeshInstanced`
+    pub fn transforms(&self) -> Vec<Matrix> {
+        self.positions
+            .iter()
+            .map(|position| Matrix::translate(position.x, position.y, position.z))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+}
+
+/// Whether an entity can be drawn through a batch
+/// Rotated entities still go through the rlgl matrix path, transparent shapes
+/// need back-to-front drawing, and batched models are drawn unscaled
+pub fn is_batchable(transform: &Transform, renderable: &Renderable) -> bool {
+    let has_rotation = transform.rotation.x.abs() > 0.001
+        || transform.rotation.y.abs() > 0.001
+        || transform.rotation.z.abs() > 0.001;
+    let opaque = match &renderable.shape {
+        RenderShape::Cube { color, .. }
+        | RenderShape::Sphere { color, .. }
+        | RenderShape::Cylinder { color, .. } => color[3] == 255,
+        RenderShape::Model { .. } => transform.scale == Vector3::one(),
+    };
+
+    renderable.visible && !has_rotation && opaque && BatchKey::from_shape(&renderable.shape, 0).is_some()
+}
+
+/// Group batchable entities by shape, color and the LOD segment count
+/// `lod_segments` picks for their position
+/// Only groups with at least MIN_BATCH_SIZE entities are returned
+pub fn group_batches(world: &World, lod_segments: impl Fn(Vector3) -> i32) -> Vec<RenderBatch> {
+    let mut groups: HashMap<BatchKey, RenderBatch> = HashMap::new();
+
+    for entity in world.enabled_entities() {
+        let (Some(transform), Some(renderable)) = (&entity.transform, &entity.renderable) else {
+            continue;
+        };
+        // Tinted entities need their own color, so they are drawn individually
+        if !is_batchable(transform, renderable) || entity.tint.is_some() {
+            continue;
+        }
+        let Some(key) = BatchKey::from_shape(&renderable.shape, lod_segments(transform.position)) else {
+            continue;
+        };
+
+        let batch = groups.entry(key.clone()).or_insert_with(|| RenderBatch {
+            key,
+            entities: Vec::new(),
+            positions: Vec::new(),
+        });
+        batch.entities.push(entity.id);
+        batch.positions.push(transform.position);
+    }
+
+    groups
+        .into_values()
+        .filter(|batch| batch.len() >= MIN_BATCH_SIZE)
+        .collect()
+}
+
+/// GPU meshes and materials shared by every entity in a batch
+pub struct BatchMeshCache {
+    meshes: HashMap<BatchKey, (raylib::ffi::Mesh, raylib::ffi::Material)>,
+    /// Shader reading per-instance transforms, required for instanced drawing
+    instance_shader: Option<raylib::ffi::Shader>,
+}
+
+impl BatchMeshCache {
+    pub fn new() -> Self {
+        Self {
+            meshes: HashMap::new(),
+            instance_shader: None,
+        }
+    }
+
+    /// Shader used for `DrawMeshInstanced`
+    ///
+    /// raylib reads the instance transform attribute location from
+    /// `locs[SHADER_LOC_MATRIX_MODEL]`, so that must point at the shader's
+    /// per-instance matrix attribute. The cache takes ownership and unloads the
+    /// shader in `cleanup`.
+    pub fn set_instance_shader(&mut self, shader: raylib::ffi::Shader) {
+        if let Some(old) = self.instance_shader.replace(shader) {
+            unsafe {
+                raylib::ffi::UnloadShader(old);
+            }
+        }
+    }
+
+    /// Load an instancing shader and use it for `DrawMeshInstanced`
+    ///
+    /// The vertex shader must take the per-instance model matrix as a `mat4
+    /// instanceTransform` attribute. Returns false, leaving batches drawn a mesh
+    /// at a time, if the shader fails to load or has no such attribute.
+    pub fn load_instance_shader(&mut self, vs_path: &str, fs_path: &str) -> bool {
+        let (Ok(vs_path), Ok(fs_path)) = (CString::new(vs_path), CString::new(fs_path)) else {
+            return false;
+        };
+        let attribute = CString::new("instanceTransform").unwrap();
+        unsafe {
+            let shader = raylib::ffi::LoadShader(vs_path.as_ptr(), fs_path.as_ptr());
+            if !raylib::ffi::IsShaderValid(shader) {
+                return false;
+            }
+            // A shader that failed to compile falls back to raylib's default one,
+            // which has no instance attribute
+            let location = raylib::ffi::GetShaderLocationAttrib(shader, attribute.as_ptr());
+            if location < 0 {
+                raylib::ffi::UnloadShader(shader);
+                return false;
+            }
+            *shader.locs.add(ShaderLocationIndex::SHADER_LOC_MATRIX_MODEL as usize) = location;
+            self.set_instance_shader(shader);
+        }
+        true
+    }
+
+    /// Get (or generate and upload) the mesh for a primitive batch key
+    /// Model batches draw the model's own meshes and return None
+    pub fn get(&mut self, key: &BatchKey) -> Option<(raylib::ffi::Mesh, raylib::ffi::Material)> {
+        if let BatchKey::Model { .. } = key {
+            return None;
+        }
+        Some(*self.meshes.entry(key.clone()).or_insert_with(|| unsafe {
+            let mesh = match *key {
+                BatchKey::Cube { size, .. } => raylib::ffi::GenMeshCube(
+                    f32::from_bits(size[0]),
+                    f32::from_bits(size[1]),
+                    f32::from_bits(size[2]),
+                ),
+                BatchKey::Sphere { radius, segments, .. } => {
+                    raylib::ffi::GenMeshSphere(f32::from_bits(radius), segments, segments)
+                }
+                BatchKey::Cylinder { radius, height, segments, .. } => raylib::ffi::GenMeshCylinder(
+                    f32::from_bits(radius),
+                    f32::from_bits(height),
+                    segments,
+                ),
+                BatchKey::Model { .. } => unreachable!("model batches have no generated mesh"),
+            };
+
+            let material = raylib::ffi::LoadMaterialDefault();
+            if !material.maps.is_null() {
+                (*material.maps).color = key.color().into();
+            }
+
+            (mesh, material)
+        }))
+    }
+
+    /// Draw every entity in a primitive batch from the shared mesh
+    /// Returns the number of draw calls issued
+    pub fn draw(&mut self, batch: &RenderBatch, instanced: bool) -> usize {
+        match self.get(&batch.key) {
+            Some((mesh, material)) => self.draw_instances(mesh, material, &batch.transforms(), instanced),
+            None => 0,
+        }
+    }
+
+    /// Draw one mesh at each transform, in a single instanced call when
+    /// `instanced` is set and an instance shader is available
+    /// Returns the number of draw calls issued
+    pub fn draw_instances(
+        &self,
+        mesh: raylib::ffi::Mesh,
+        mut material: raylib::ffi::Material,
+        transforms: &[Matrix],
+        instanced: bool,
+    ) -> usize {
+        match self.instance_shader {
+            Some(shader) if instanced => {
+                material.shader = shader;
+                let transforms: Vec<raylib::ffi::Matrix> = transforms.iter().map(|&m| m.into()).collect();
+                unsafe {
+                    raylib::ffi::DrawMeshInstanced(mesh, material, transforms.as_ptr(), transforms.len() as i32);
+                }
+                1
+            }
+            _ => {
+                for &transform in transforms {
+                    unsafe {
+                        raylib::ffi::DrawMesh(mesh, material, transform.into());
+                    }
+                }
+                transforms.len()
+            }
+        }
+    }
+
+    pub fn cleanup(&mut self) {
+        for (_, (mesh, material)) in self.meshes.drain() {
+            unsafe {
+                raylib::ffi::UnloadMesh(mesh);
+                raylib::ffi::UnloadMaterial(material);
+            }
+        }
+        if let Some(shader) = self.instance_shader.take() {
+            unsafe {
+                raylib::ffi::UnloadShader(shader);
+            }
+        }
+    }
+}
+
+impl Drop for BatchMeshCache {
+    fn drop(&mut self) {
+        self.cleanup();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawn_cube(world: &mut World, x: f32, size: f32, color: Color) -> EntityId {
+        world
+            .spawn()
+            .with_transform(Transform::new(Vector3::new(x, 0.0, 0.0)))
+            .with_renderable(Renderable::cube(Vector3::new(size, size, size), color))
+            .build()
+    }
+
+    #[test]
+    fn test_identical_shapes_share_batch() {
+        let mut world = World::new();
+        let a = spawn_cube(&mut world, 0.0, 1.0, Color::RED);
+        let b = spawn_cube(&mut world, 2.0, 1.0, Color::RED);
+        let c = spawn_cube(&mut world, 4.0, 1.0, Color::RED);
+
+        let batches = group_batches(&world, |_| 16);
+        assert_eq!(batches.len(), 1);
+
+        let mut ids = batches[0].entities.clone();
+        ids.sort();
+        assert_eq!(ids, vec![a, b, c]);
+    }
+
+    #[test]
+    fn test_different_shapes_split_batches() {
+        let mut world = World::new();
+        spawn_cube(&mut world, 0.0, 1.0, Color::RED);
+        spawn_cube(&mut world, 1.0, 1.0, Color::RED);
+        spawn_cube(&mut world, 2.0, 1.0, Color::BLUE);
+        spawn_cube(&mut world, 3.0, 1.0, Color::BLUE);
+        spawn_cube(&mut world, 4.0, 2.0, Color::BLUE);
+        world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_renderable(Renderable::sphere(1.0, Color::RED))
+            .build();
+
+        let batches = group_batches(&world, |_| 16);
+        // Red 1.0 cubes and blue 1.0 cubes; the lone 2.0 cube and the sphere stay unbatched
+        assert_eq!(batches.len(), 2);
+        for batch in &batches {
+            assert_eq!(batch.len(), 2);
+        }
+        assert_ne!(batches[0].key, batches[1].key);
+    }
+
+    #[test]
+    fn test_rotated_and_hidden_not_batched() {
+        let mut world = World::new();
+        spawn_cube(&mut world, 0.0, 1.0, Color::RED);
+        world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()).with_rotation(Vector3::new(0.0, 1.0, 0.0)))
+            .with_renderable(Renderable::cube(Vector3::one(), Color::RED))
+            .build();
+        let hidden = spawn_cube(&mut world, 2.0, 1.0, Color::RED);
+        world.get_entity_mut(hidden).unwrap().renderable.as_mut().unwrap().visible = false;
+
+        assert!(group_batches(&world, |_| 16).is_empty());
+    }
+
+    #[test]
+    fn test_hundred_identical_cubes_form_one_instanced_batch() {
+        let mut world = World::new();
+        for i in 0..100 {
+            spawn_cube(&mut world, i as f32 * 2.0, 1.0, Color::BROWN);
+        }
+        // Transparent crates need sorting, so they stay on the per-entity path
+        spawn_cube(&mut world, -2.0, 1.0, Color::new(200, 100, 50, 128));
+        spawn_cube(&mut world, -4.0, 1.0, Color::new(200, 100, 50, 128));
+
+        let batches = group_batches(&world, |_| 16);
+        assert_eq!(batches.len(), 1);
+        let transforms = batches[0].transforms();
+        assert_eq!(transforms.len(), 100);
+        let first = batches[0].positions[0];
+        assert_eq!(transforms[0], Matrix::translate(first.x, first.y, first.z));
+    }
+
+    #[test]
+    fn test_same_model_path_batches() {
+        let mut world = World::new();
+        let model = |path: &str| Renderable { shape: RenderShape::Model { path: path.to_string() }, visible: true };
+        for x in 0..3 {
+            world
+                .spawn()
+                .with_transform(Transform::new(Vector3::new(x as f32, 0.0, 0.0)))
+                .with_renderable(model("assets/barrel.glb"))
+                .build();
+        }
+        world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_renderable(model("assets/crate.glb"))
+            .build();
+
+        let batches = group_batches(&world, |_| 16);
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].len(), 3);
+        assert_eq!(batches[0].model_path(), Some("assets/barrel.glb"));
+    }
+
+    #[test]
+    fn test_spheres_batch_by_lod_tier() {
+        let mut world = World::new();
+        for x in [0.0, 1.0, 50.0, 51.0] {
+            world
+                .spawn()
+                .with_transform(Transform::new(Vector3::new(x, 0.0, 0.0)))
+                .with_renderable(Renderable::sphere(0.5, Color::RED))
+                .build();
+        }
+
+        let batches = group_batches(&world, |position| if position.x < 10.0 { 24 } else { 8 });
+        let mut tiers: Vec<(i32, usize)> = batches
+            .iter()
+            .map(|batch| match batch.key {
+                BatchKey::Sphere { segments, .. } => (segments, batch.len()),
+                _ => panic!("expected a sphere batch, got {:?}", batch.key),
+            })
+            .collect();
+        tiers.sort();
+        assert_eq!(tiers, vec![(8, 2), (24, 2)]);
+    }
+}
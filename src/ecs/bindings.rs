@@ -0,0 +1,237 @@
+//! Rebindable keyboard controls
+//!
+//! `KeyBindings` maps each `InputAction` to a `KeyboardKey`. It serializes as a
+//! map of action to raylib key name (`"KEY_W"`), so a controls config can be saved
+//! next to the other settings. Key state is read through `KeyState`, which the
+//! raylib handle implements and tests can fake.
+
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// Something the player can do with a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum InputAction {
+    MoveForward,
+    MoveBack,
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Descend,
+    Sprint,
+    ToggleInventory,
+    ToggleCharacterSheet,
+    ToggleBoundingBoxes,
+    ToggleMap,
+    ToggleFrustumCulling,
+    SkillSlot1,
+    SkillSlot2,
+    SkillSlot3,
+    SkillSlot4,
+    SkillSlot5,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 17] = [
+        InputAction::MoveForward,
+        InputAction::MoveBack,
+        InputAction::MoveLeft,
+        InputAction::MoveRight,
+        InputAction::Jump,
+        InputAction::Descend,
+        InputAction::Sprint,
+        InputAction::ToggleInventory,
+        InputAction::ToggleCharacterSheet,
+        InputAction::ToggleBoundingBoxes,
+        InputAction::ToggleMap,
+        InputAction::ToggleFrustumCulling,
+        InputAction::SkillSlot1,
+        InputAction::SkillSlot2,
+        InputAction::SkillSlot3,
+        InputAction::SkillSlot4,
+        InputAction::SkillSlot5,
+    ];
+
+    /// Hotbar actions, in slot order (hotkey 1 first)
+    pub const SKILL_SLOTS: [InputAction; 5] = [
+        InputAction::SkillSlot1,
+        InputAction::SkillSlot2,
+        InputAction::SkillSlot3,
+        InputAction::SkillSlot4,
+        InputAction::SkillSlot5,
+    ];
+}
+
+/// Keys that can be bound (and therefore saved and loaded by name)
+const BINDABLE_KEYS: &[KeyboardKey] = {
+    use KeyboardKey::*;
+    &[
+        KEY_A, KEY_B, KEY_C, KEY_D, KEY_E, KEY_F, KEY_G, KEY_H, KEY_I, KEY_J, KEY_K, KEY_L, KEY_M,
+        KEY_N, KEY_O, KEY_P, KEY_Q, KEY_R, KEY_S, KEY_T, KEY_U, KEY_V, KEY_W, KEY_X, KEY_Y, KEY_Z,
+        KEY_ZERO, KEY_ONE, KEY_TWO, KEY_THREE, KEY_FOUR, KEY_FIVE, KEY_SIX, KEY_SEVEN, KEY_EIGHT, KEY_NINE,
+        KEY_F1, KEY_F2, KEY_F3, KEY_F4, KEY_F5, KEY_F6, KEY_F7, KEY_F8, KEY_F9, KEY_F10, KEY_F11, KEY_F12,
+        KEY_UP, KEY_DOWN, KEY_LEFT, KEY_RIGHT,
+        KEY_SPACE, KEY_ENTER, KEY_TAB, KEY_BACKSPACE, KEY_ESCAPE, KEY_GRAVE,
+        KEY_LEFT_SHIFT, KEY_RIGHT_SHIFT, KEY_LEFT_CONTROL, KEY_RIGHT_CONTROL, KEY_LEFT_ALT, KEY_RIGHT_ALT,
+        KEY_COMMA, KEY_PERIOD, KEY_SLASH, KEY_SEMICOLON, KEY_APOSTROPHE, KEY_MINUS, KEY_EQUAL,
+        KEY_LEFT_BRACKET, KEY_RIGHT_BRACKET, KEY_BACKSLASH,
+    ]
+};
+
+/// raylib name of a key, e.g. `"KEY_W"`
+pub fn key_name(key: KeyboardKey) -> String {
+    format!("{:?}", key)
+}
+
+/// Bindable key with the given raylib name
+pub fn key_from_name(name: &str) -> Option<KeyboardKey> {
+    BINDABLE_KEYS.iter().copied().find(|&key| key_name(key) == name)
+}
+
+/// Source of keyboard state
+pub trait KeyState {
+    fn is_key_down(&self, key: KeyboardKey) -> bool;
+    fn is_key_pressed(&self, key: KeyboardKey) -> bool;
+}
+
+impl KeyState for RaylibHandle {
+    fn is_key_down(&self, key: KeyboardKey) -> bool {
+        RaylibHandle::is_key_down(self, key)
+    }
+
+    fn is_key_pressed(&self, key: KeyboardKey) -> bool {
+        RaylibHandle::is_key_pressed(self, key)
+    }
+}
+
+/// Fixed set of held keys, where every held key also counts as just pressed
+impl KeyState for HashSet<KeyboardKey> {
+    fn is_key_down(&self, key: KeyboardKey) -> bool {
+        self.contains(&key)
+    }
+
+    fn is_key_pressed(&self, key: KeyboardKey) -> bool {
+        self.contains(&key)
+    }
+}
+
+/// Key assigned to each action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "BTreeMap<InputAction, String>", into = "BTreeMap<InputAction, String>")]
+pub struct KeyBindings {
+    keys: BTreeMap<InputAction, KeyboardKey>,
+}
+
+impl Default for KeyBindings {
+    /// WASD movement, Space/Shift up and down, Ctrl sprint, the UI toggles and 1-5 for skills
+    fn default() -> Self {
+        use KeyboardKey::*;
+        let keys = [
+            (InputAction::MoveForward, KEY_W),
+            (InputAction::MoveBack, KEY_S),
+            (InputAction::MoveLeft, KEY_A),
+            (InputAction::MoveRight, KEY_D),
+            (InputAction::Jump, KEY_SPACE),
+            (InputAction::Descend, KEY_LEFT_SHIFT),
+            (InputAction::Sprint, KEY_LEFT_CONTROL),
+            (InputAction::ToggleInventory, KEY_I),
+            (InputAction::ToggleCharacterSheet, KEY_C),
+            (InputAction::ToggleBoundingBoxes, KEY_B),
+            (InputAction::ToggleMap, KEY_M),
+            (InputAction::ToggleFrustumCulling, KEY_F),
+            (InputAction::SkillSlot1, KEY_ONE),
+            (InputAction::SkillSlot2, KEY_TWO),
+            (InputAction::SkillSlot3, KEY_THREE),
+            (InputAction::SkillSlot4, KEY_FOUR),
+            (InputAction::SkillSlot5, KEY_FIVE),
+        ];
+        Self { keys: keys.into_iter().collect() }
+    }
+}
+
+impl KeyBindings {
+    /// Key bound to an action
+    pub fn key(&self, action: InputAction) -> KeyboardKey {
+        self.keys.get(&action).copied().unwrap_or(KeyboardKey::KEY_NULL)
+    }
+
+    /// Bind an action to a different key
+    /// Other actions on the same key keep it, so one key can drive several actions
+    pub fn rebind(&mut self, action: InputAction, key: KeyboardKey) {
+        self.keys.insert(action, key);
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize key bindings: {}", e))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Failed to parse key bindings: {}", e))
+    }
+}
+
+impl TryFrom<BTreeMap<InputAction, String>> for KeyBindings {
+    type Error = String;
+
+    /// Actions missing from the map keep their default key
+    fn try_from(names: BTreeMap<InputAction, String>) -> Result<Self, Self::Error> {
+        let mut bindings = Self::default();
+        for (action, name) in names {
+            let key = key_from_name(&name).ok_or_else(|| format!("Unknown key '{}' for {:?}", name, action))?;
+            bindings.rebind(action, key);
+        }
+        Ok(bindings)
+    }
+}
+
+impl From<KeyBindings> for BTreeMap<InputAction, String> {
+    fn from(bindings: KeyBindings) -> Self {
+        bindings.keys.into_iter().map(|(action, key)| (action, key_name(key))).collect()
+    }
+}
+
+/// Whether the key bound to `action` is held
+pub fn is_action_down(bindings: &KeyBindings, action: InputAction, keys: &impl KeyState) -> bool {
+    keys.is_key_down(bindings.key(action))
+}
+
+/// Whether the key bound to `action` went down this frame
+pub fn is_action_pressed(bindings: &KeyBindings, action: InputAction, keys: &impl KeyState) -> bool {
+    keys.is_key_pressed(bindings.key(action))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rebind_forward_to_up_arrow() {
+        let mut bindings = KeyBindings::default();
+        let w_held: HashSet<KeyboardKey> = [KeyboardKey::KEY_W].into_iter().collect();
+        let up_held: HashSet<KeyboardKey> = [KeyboardKey::KEY_UP].into_iter().collect();
+        assert!(is_action_down(&bindings, InputAction::MoveForward, &w_held));
+        assert!(!is_action_down(&bindings, InputAction::MoveForward, &up_held));
+
+        bindings.rebind(InputAction::MoveForward, KeyboardKey::KEY_UP);
+        assert!(is_action_down(&bindings, InputAction::MoveForward, &up_held));
+        assert!(!is_action_down(&bindings, InputAction::MoveForward, &w_held));
+        assert!(!is_action_down(&bindings, InputAction::MoveBack, &up_held));
+    }
+
+    #[test]
+    fn test_bindings_round_trip_through_json() {
+        let mut bindings = KeyBindings::default();
+        bindings.rebind(InputAction::Jump, KeyboardKey::KEY_E);
+
+        let json = bindings.to_json().unwrap();
+        assert!(json.contains("\"Jump\": \"KEY_E\""));
+        assert_eq!(KeyBindings::from_json(&json).unwrap(), bindings);
+
+        // Partial configs fill in defaults; unknown key names are rejected
+        let partial = KeyBindings::from_json(r#"{ "Sprint": "KEY_Q" }"#).unwrap();
+        assert_eq!(partial.key(InputAction::Sprint), KeyboardKey::KEY_Q);
+        assert_eq!(partial.key(InputAction::MoveForward), KeyboardKey::KEY_W);
+        assert!(KeyBindings::from_json(r#"{ "Sprint": "KEY_BANANA" }"#).is_err());
+        assert!(InputAction::ALL.iter().all(|&action| bindings.key(action) != KeyboardKey::KEY_NULL));
+    }
+}
@@ -0,0 +1,256 @@
+//! Floating combat text
+//!
+//! `CombatTextSystem` keeps a pool of `FloatingText` numbers that rise and fade
+//! above whatever was hit or healed. It notices changes to any entity's `Health`
+//! by itself, so projectiles and enemy attacks show up without extra wiring, and
+//! hits whose target carries a `CriticalHit` tag are shown as crits. Damage
+//! applied elsewhere (rpg `Stats`) can be pushed with `spawn_damage` and `spawn_heal`.
+
+use raylib::prelude::*;
+use std::collections::HashMap;
+use super::entity::{EntityId, World};
+use super::examples::CriticalHit;
+use super::System;
+
+/// A number (or word) drifting up from a point in the world
+#[derive(Debug, Clone, PartialEq)]
+pub struct FloatingText {
+    pub world_pos: Vector3,
+    pub text: String,
+    pub color: Color,
+    /// Seconds left before it disappears
+    pub lifetime: f32,
+    /// Lifetime it started with, for fading
+    pub duration: f32,
+    /// World units per second
+    pub rise_speed: f32,
+    pub font_size: i32,
+}
+
+impl FloatingText {
+    pub fn new(world_pos: Vector3, text: &str, color: Color) -> Self {
+        Self {
+            world_pos,
+            text: text.to_string(),
+            color,
+            lifetime: 1.0,
+            duration: 1.0,
+            rise_speed: 1.5,
+            font_size: 20,
+        }
+    }
+
+    pub fn with_lifetime(mut self, seconds: f32) -> Self {
+        self.lifetime = seconds;
+        self.duration = seconds;
+        self
+    }
+
+    pub fn with_font_size(mut self, font_size: i32) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
+    /// Opacity from 1.0 when spawned to 0.0 when it expires
+    pub fn alpha(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return 0.0;
+        }
+        (self.lifetime / self.duration).clamp(0.0, 1.0)
+    }
+
+    /// `color` faded by `alpha`
+    pub fn faded_color(&self) -> Color {
+        let alpha = (self.color.a as f32 * self.alpha()).round() as u8;
+        Color::new(self.color.r, self.color.g, self.color.b, alpha)
+    }
+}
+
+/// System that spawns, ages and draws floating combat text
+pub struct CombatTextSystem {
+    texts: Vec<FloatingText>,
+    /// Oldest texts are dropped once the pool is full
+    pub max_texts: usize,
+    /// Height above an entity's position that its numbers start at
+    pub spawn_height: f32,
+    /// Health of each entity as of the last update
+    last_health: HashMap<EntityId, f32>,
+}
+
+impl CombatTextSystem {
+    pub const CRIT_COLOR: Color = Color::YELLOW;
+    pub const DAMAGE_COLOR: Color = Color::WHITE;
+    pub const HEAL_COLOR: Color = Color::GREEN;
+
+    pub fn new() -> Self {
+        Self {
+            texts: Vec::new(),
+            max_texts: 64,
+            spawn_height: 1.5,
+            last_health: HashMap::new(),
+        }
+    }
+
+    pub fn texts(&self) -> &[FloatingText] {
+        &self.texts
+    }
+
+    /// Add a text to the pool
+    pub fn push(&mut self, text: FloatingText) {
+        if self.texts.len() >= self.max_texts && !self.texts.is_empty() {
+            self.texts.remove(0);
+        }
+        self.texts.push(text);
+    }
+
+    /// Damage number at `position`; crits are larger and yellow
+    pub fn spawn_damage(&mut self, position: Vector3, amount: f32, crit: bool) {
+        let text = format!("{}", amount.round() as i32);
+        let position = position + Vector3::new(0.0, self.spawn_height, 0.0);
+        if crit {
+            self.push(FloatingText::new(position, &format!("{}!", text), Self::CRIT_COLOR).with_font_size(30));
+        } else {
+            self.push(FloatingText::new(position, &text, Self::DAMAGE_COLOR));
+        }
+    }
+
+    /// Healing number at `position`
+    pub fn spawn_heal(&mut self, position: Vector3, amount: f32) {
+        let text = format!("+{}", amount.round() as i32);
+        let position = position + Vector3::new(0.0, self.spawn_height, 0.0);
+        self.push(FloatingText::new(position, &text, Self::HEAL_COLOR));
+    }
+
+    /// Age, raise and expire the texts
+    pub fn advance(&mut self, delta_time: f32) {
+        for text in &mut self.texts {
+            text.lifetime -= delta_time;
+            text.world_pos.y += text.rise_speed * delta_time;
+        }
+        self.texts.retain(|text| text.lifetime > 0.0);
+    }
+
+    /// Draw every text in front of the camera at its projected screen position
+    pub fn render(&self, d: &mut RaylibDrawHandle, camera: Camera3D) {
+        let forward = camera.target - camera.position;
+        for text in &self.texts {
+            if forward.dot(text.world_pos - camera.position) <= 0.0 {
+                continue;
+            }
+            let screen = d.get_world_to_screen(text.world_pos, camera);
+            let width = d.measure_text(&text.text, text.font_size);
+            d.draw_text(
+                &text.text,
+                screen.x as i32 - width / 2,
+                screen.y as i32 - text.font_size / 2,
+                text.font_size,
+                text.faded_color(),
+            );
+        }
+    }
+}
+
+impl Default for CombatTextSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl System for CombatTextSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let mut changes = Vec::new();
+        let mut seen = HashMap::new();
+        for entity in world.entities() {
+            if let (Some(health), Some(transform)) = (&entity.health, &entity.transform) {
+                if let Some(&previous) = self.last_health.get(&entity.id) {
+                    let change = health.current - previous;
+                    if change != 0.0 {
+                        changes.push((entity.id, transform.position, change));
+                    }
+                }
+                seen.insert(entity.id, health.current);
+            }
+        }
+        self.last_health = seen;
+
+        for (id, position, change) in changes {
+            if change < 0.0 {
+                let crit = world.remove_component::<CriticalHit>(id).is_some();
+                self.spawn_damage(position, -change, crit);
+            } else {
+                self.spawn_heal(position, change);
+            }
+        }
+        self.advance(delta_time);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::components::{Health, Transform};
+
+    #[test]
+    fn test_floating_text_fades_and_expires() {
+        let mut system = CombatTextSystem::new();
+        system.push(FloatingText::new(Vector3::zero(), "12", Color::WHITE).with_lifetime(1.0));
+
+        system.advance(0.5);
+        let text = &system.texts()[0];
+        assert!(text.alpha() < 1.0 && text.alpha() > 0.0);
+        assert!(text.faded_color().a < 255);
+        assert_eq!(text.world_pos.y, 0.75);
+
+        system.advance(0.6);
+        assert!(system.texts().is_empty());
+    }
+
+    #[test]
+    fn test_health_changes_spawn_numbers() {
+        let mut world = World::new();
+        let target = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::new(2.0, 0.0, 0.0)))
+            .with_health(Health::new(100.0))
+            .build();
+        let mut system = CombatTextSystem::new();
+
+        // The first update only records health
+        system.update(&mut world, 0.016);
+        assert!(system.texts().is_empty());
+
+        world.get_entity_mut(target).unwrap().health.as_mut().unwrap().current = 75.0;
+        system.update(&mut world, 0.016);
+        assert_eq!(system.texts().len(), 1);
+        assert_eq!(system.texts()[0].text, "25");
+        assert_eq!(system.texts()[0].color, CombatTextSystem::DAMAGE_COLOR);
+
+        system.spawn_damage(Vector3::zero(), 40.0, true);
+        let crit = &system.texts()[1];
+        assert_eq!((crit.text.as_str(), crit.color), ("40!", CombatTextSystem::CRIT_COLOR));
+        assert!(crit.font_size > system.texts()[0].font_size);
+    }
+
+    #[test]
+    fn test_tagged_hits_show_as_crits() {
+        let mut world = World::new();
+        let target = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_health(Health::new(100.0))
+            .build();
+        let mut system = CombatTextSystem::new();
+        system.update(&mut world, 0.016);
+
+        world.get_entity_mut(target).unwrap().health.as_mut().unwrap().current = 70.0;
+        world.insert_component(target, CriticalHit);
+        system.update(&mut world, 0.016);
+        assert_eq!(system.texts()[0].text, "30!");
+        assert!(world.get_component::<CriticalHit>(target).is_none());
+
+        // The tag is spent, so the next hit is an ordinary one
+        world.get_entity_mut(target).unwrap().health.as_mut().unwrap().current = 60.0;
+        system.update(&mut world, 0.016);
+        assert_eq!(system.texts()[1].text, "10");
+    }
+}
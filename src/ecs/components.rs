@@ -1,7 +1,9 @@
 use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+use super::aabb::Aabb;
 
 /// Position component for 3D entities
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Transform {
     pub position: Vector3,
     pub rotation: Vector3,
@@ -35,10 +37,30 @@ impl Transform {
         self.rotation = rotation;
         self
     }
+
+    /// The Euler rotation as a quaternion
+    ///
+    /// Uses the same X, then Y, then Z rotation order as the renderer, so vectors
+    /// rotated by it line up with what's drawn.
+    pub fn rotation_quaternion(&self) -> Quaternion {
+        Quaternion::from_axis_angle(Vector3::new(1.0, 0.0, 0.0), self.rotation.x)
+            * Quaternion::from_axis_angle(Vector3::new(0.0, 1.0, 0.0), self.rotation.y)
+            * Quaternion::from_axis_angle(Vector3::new(0.0, 0.0, 1.0), self.rotation.z)
+    }
+
+    /// The local up axis in world space
+    ///
+    /// Uses the same X, then Y, then Z rotation order as the renderer.
+    pub fn up(&self) -> Vector3 {
+        let (sx, cx) = self.rotation.x.sin_cos();
+        let (sy, cy) = self.rotation.y.sin_cos();
+        let (sz, cz) = self.rotation.z.sin_cos();
+        Vector3::new(-sz * cy, cz * cx - sz * sy * sx, cz * sx + sz * sy * cx)
+    }
 }
 
 /// Render component - what to draw
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RenderShape {
     Cube { size: Vector3, color: [u8; 4] },
     Sphere { radius: f32, color: [u8; 4] },
@@ -47,7 +69,7 @@ pub enum RenderShape {
 }
 
 /// Model component for 3D models with textures
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Model {
     pub model_path: String,
     pub texture_path: Option<String>,
@@ -81,7 +103,7 @@ impl Model {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Renderable {
     pub shape: RenderShape,
     pub visible: bool,
@@ -107,10 +129,46 @@ impl Renderable {
             visible: true,
         }
     }
+
+    pub fn cylinder(radius: f32, height: f32, color: Color) -> Self {
+        Self {
+            shape: RenderShape::Cylinder {
+                radius,
+                height,
+                color: [color.r, color.g, color.b, color.a],
+            },
+            visible: true,
+        }
+    }
+
+    /// Renderable matching a collider shape (capsules draw as cylinders)
+    pub fn from_collider_shape(shape: &ColliderShape, color: Color) -> Self {
+        match *shape {
+            ColliderShape::Box { size } => Self::cube(size, color),
+            ColliderShape::Sphere { radius } => Self::sphere(radius, color),
+            ColliderShape::Capsule { radius, height } => Self::cylinder(radius, height, color),
+        }
+    }
+
+    /// Radius of a sphere around the entity position enclosing the drawn shape
+    ///
+    /// Uses the largest `scale` axis, but never less than 1 since unrotated shapes
+    /// are drawn unscaled. Models have unknown bounds and return `None`.
+    pub fn bounding_radius(&self, scale: Vector3) -> Option<f32> {
+        let scale = scale.x.abs().max(scale.y.abs()).max(scale.z.abs()).max(1.0);
+        let radius = match &self.shape {
+            RenderShape::Cube { size, .. } => size.length() / 2.0,
+            RenderShape::Sphere { radius, .. } => *radius,
+            // Drawn upwards from its base at the entity position
+            RenderShape::Cylinder { radius, height, .. } => (radius * radius + height * height).sqrt(),
+            RenderShape::Model { .. } => return None,
+        };
+        Some(radius * scale)
+    }
 }
 
 /// Velocity component for moving entities
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Velocity {
     pub linear: Vector3,
     pub angular: Vector3,
@@ -126,36 +184,92 @@ impl Default for Velocity {
 }
 
 /// Tag for player entity
+/// Named `PlayerTag` so it doesn't clash with `rpg::Player`
 #[derive(Debug, Clone, Copy)]
-pub struct Player;
+pub struct PlayerTag;
 
 /// Tag for enemy entities
+/// Named `EnemyTag` so it doesn't clash with `rpg::Enemy`
 #[derive(Debug, Clone, Copy)]
-pub struct Enemy;
+pub struct EnemyTag;
+
+/// Tag for the entity picked by clicking on it (see `SelectionSystem`)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Selected;
 
 /// Health component
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Health {
     pub current: f32,
     pub max: f32,
+    /// Seconds of invincibility granted after each hit (0 disables i-frames)
+    pub invulnerability: f32,
+    /// Time left in the current invincibility window
+    pub invulnerable_remaining: f32,
 }
 
 impl Health {
     pub fn new(max: f32) -> Self {
-        Self { current: max, max }
+        Self {
+            current: max,
+            max,
+            invulnerability: 0.0,
+            invulnerable_remaining: 0.0,
+        }
+    }
+
+    pub fn with_invulnerability(mut self, seconds: f32) -> Self {
+        self.invulnerability = seconds;
+        self
     }
 
     pub fn is_alive(&self) -> bool {
         self.current > 0.0
     }
+
+    pub fn is_invulnerable(&self) -> bool {
+        self.invulnerable_remaining > 0.0
+    }
+
+    /// Apply damage unless inside an i-frame window, then start a new window
+    /// Returns whether the damage landed
+    pub fn take_damage(&mut self, amount: f32) -> bool {
+        if self.is_invulnerable() {
+            return false;
+        }
+        self.current = (self.current - amount).max(0.0);
+        self.invulnerable_remaining = self.invulnerability;
+        true
+    }
+
+    /// Count down the invincibility window
+    pub fn tick_invulnerability(&mut self, delta_time: f32) {
+        self.invulnerable_remaining = (self.invulnerable_remaining - delta_time).max(0.0);
+    }
 }
 
 /// Name/label component
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Name(pub String);
 
+/// Stable identifier for level entities whose state outlives a reload
+/// (chests, unique enemies and items); see `level::WorldState`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PersistentId(pub String);
+
+/// Lootable container such as a chest
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Container {
+    pub is_open: bool,
+}
+
+/// raylib's built-in near clip distance (RL_CULL_DISTANCE_NEAR)
+pub const DEFAULT_NEAR_CLIP: f32 = 0.01;
+/// raylib's built-in far clip distance (RL_CULL_DISTANCE_FAR)
+pub const DEFAULT_FAR_CLIP: f32 = 1000.0;
+
 /// Camera component for first-person view
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Camera {
     /// Field of view in degrees
     pub fov: f32,
@@ -169,11 +283,136 @@ pub struct Camera {
     pub pitch: f32,
     /// Mouse sensitivity for first-person controls
     pub mouse_sensitivity: f32,
+    /// Maximum look rotation per frame in degrees (per axis), guards against frame hitches
+    pub max_look_per_frame: f32,
+    /// When set, scale look input by `delta_time * reference_fps` for setups that report
+    /// per-frame deltas that don't accumulate between frames
+    pub look_reference_fps: Option<f32>,
+    /// Head-bob vertical amplitude in world units (0 disables head-bob)
+    pub bob_amplitude: f32,
+    /// Head-bob phase advance in radians per world unit travelled
+    pub bob_frequency: f32,
+    /// Extra field of view in degrees while sprinting
+    pub sprint_fov_delta: f32,
+    /// How quickly bob and FOV effects blend in/out (per second)
+    pub effect_smoothing: f32,
+    /// Current head-bob phase in radians (runtime state)
+    pub bob_phase: f32,
+    /// Current vertical head-bob offset (runtime state)
+    pub bob_offset: f32,
+    /// Current FOV offset from sprinting (runtime state)
+    pub fov_offset: f32,
+    /// Field of view to zoom to (e.g. while aiming); `None` returns to `fov`
+    pub zoom_fov: Option<f32>,
+    /// How quickly the zoom blends in/out (per second)
+    pub zoom_smoothing: f32,
+    /// Current FOV offset from zooming (runtime state)
+    pub zoom_offset: f32,
+    /// Active screen shake, if any
+    pub shake: Option<CameraShake>,
+    /// Higher priority cameras win when picking the main view
+    pub priority: i32,
+    /// Inactive cameras are skipped when picking the main view
+    pub active: bool,
+    /// Near clip plane distance
+    pub near: f32,
+    /// Far clip plane distance
+    pub far: f32,
+}
+
+/// Decaying screen shake layered on top of a camera (explosions, big hits)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CameraShake {
+    /// Peak positional offset in world units
+    pub magnitude: f32,
+    /// Total shake length in seconds
+    pub duration: f32,
+    /// Seconds left before the shake ends
+    pub remaining: f32,
+    /// Oscillation speed of the shake noise
+    pub frequency: f32,
+}
+
+impl CameraShake {
+    /// Degrees of yaw/pitch wobble per world unit of positional offset
+    pub const ROTATION_PER_UNIT: f32 = 8.0;
+
+    pub fn new(magnitude: f32, duration: f32) -> Self {
+        Self {
+            magnitude,
+            duration,
+            remaining: duration,
+            frequency: 25.0,
+        }
+    }
+
+    pub fn with_frequency(mut self, frequency: f32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.remaining > 0.0
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.remaining = (self.remaining - delta_time).max(0.0);
+    }
+
+    /// Current strength, easing out quadratically to zero at the end
+    pub fn intensity(&self) -> f32 {
+        Self::shake_intensity(self.magnitude, self.duration, self.remaining)
+    }
+
+    /// Positional offset for the current point in the shake
+    pub fn offset(&self) -> Vector3 {
+        Self::shake_offset(self.magnitude, self.duration, self.remaining, self.frequency)
+    }
+
+    /// (yaw, pitch) wobble in degrees for the current point in the shake
+    pub fn rotation_offset(&self) -> (f32, f32) {
+        let offset = self.offset();
+        (offset.x * Self::ROTATION_PER_UNIT, offset.y * Self::ROTATION_PER_UNIT)
+    }
+
+    pub fn shake_intensity(magnitude: f32, duration: f32, remaining: f32) -> f32 {
+        if duration <= 0.0 || remaining <= 0.0 {
+            return 0.0;
+        }
+        let t = (remaining / duration).min(1.0);
+        magnitude * t * t
+    }
+
+    /// Smooth pseudo-noise offset; each axis stays within +/- the current intensity
+    pub fn shake_offset(magnitude: f32, duration: f32, remaining: f32, frequency: f32) -> Vector3 {
+        let intensity = Self::shake_intensity(magnitude, duration, remaining);
+        if intensity == 0.0 {
+            return Vector3::zero();
+        }
+
+        let time = (duration - remaining) * frequency;
+        let noise = |phase: f32| ((time + phase).sin() + 0.5 * (time * 2.3 + phase * 1.7).sin()) / 1.5;
+
+        Vector3::new(noise(0.0), noise(11.3), noise(27.9)) * intensity
+    }
+}
+
+/// How a rigidbody takes part in the simulation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BodyType {
+    /// Moved by forces and collisions
+    Dynamic,
+    /// Moved by code through its velocity; pushes dynamic bodies but is never pushed back
+    Kinematic,
+    /// Never moves (infinite mass)
+    Static,
 }
 
 /// Rigidbody component for physics simulation
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Rigidbody {
+    /// Simulation mode (dynamic, kinematic or static)
+    pub body_type: BodyType,
     /// Mass of the object (0.0 = infinite mass/static)
     pub mass: f32,
     /// Whether gravity affects this object
@@ -184,6 +423,14 @@ pub struct Rigidbody {
     pub force: Vector3,
     /// Drag coefficient (air resistance) - 0.0 = no drag, higher = more drag
     pub drag: f32,
+    /// Spin in radians per second around each axis (added to `Transform::rotation`)
+    pub angular_velocity: Vector3,
+    /// Accumulated torque to apply this frame
+    pub torque: Vector3,
+    /// Resistance to changes in spin (moment of inertia, same for every axis)
+    pub inertia: f32,
+    /// Rotational counterpart of `drag`
+    pub angular_drag: f32,
     /// Is the object currently grounded/touching floor
     pub is_grounded: bool,
 }
@@ -191,26 +438,45 @@ pub struct Rigidbody {
 impl Default for Rigidbody {
     fn default() -> Self {
         Self {
+            body_type: BodyType::Dynamic,
             mass: 1.0,
             use_gravity: true,
             velocity: Vector3::zero(),
             force: Vector3::zero(),
             drag: 0.01,
+            angular_velocity: Vector3::zero(),
+            torque: Vector3::zero(),
+            inertia: 1.0,
+            angular_drag: 0.05,
             is_grounded: false,
         }
     }
 }
 
 impl Rigidbody {
+    /// Create a dynamic body (a mass of 0.0 creates a static body)
     pub fn new(mass: f32) -> Self {
         Self {
+            body_type: if mass == 0.0 { BodyType::Static } else { BodyType::Dynamic },
             mass,
             ..Default::default()
         }
     }
 
+    /// Body moved by code (e.g. platforms, doors) that pushes dynamic bodies
     pub fn kinematic() -> Self {
         Self {
+            body_type: BodyType::Kinematic,
+            mass: 0.0,
+            use_gravity: false,
+            ..Default::default()
+        }
+    }
+
+    /// Immovable body (walls, floors)
+    pub fn static_body() -> Self {
+        Self {
+            body_type: BodyType::Static,
             mass: 0.0,
             use_gravity: false,
             ..Default::default()
@@ -227,25 +493,100 @@ impl Rigidbody {
         self
     }
 
+    pub fn with_inertia(mut self, inertia: f32) -> Self {
+        self.inertia = inertia;
+        self
+    }
+
+    pub fn with_angular_drag(mut self, angular_drag: f32) -> Self {
+        self.angular_drag = angular_drag;
+        self
+    }
+
     pub fn add_force(&mut self, force: Vector3) {
         self.force = self.force + force;
     }
 
+    pub fn add_torque(&mut self, torque: Vector3) {
+        self.torque += torque;
+    }
+
     pub fn is_static(&self) -> bool {
-        self.mass == 0.0
+        self.body_type == BodyType::Static
+    }
+
+    pub fn is_kinematic(&self) -> bool {
+        self.body_type == BodyType::Kinematic
+    }
+
+    /// Whether collisions and forces can move this body
+    pub fn is_dynamic(&self) -> bool {
+        self.body_type == BodyType::Dynamic && self.mass > 0.0
+    }
+}
+
+/// Platform that moves between waypoints and carries entities standing on it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MovingPlatform {
+    /// Points to travel between (loops back to the first)
+    pub waypoints: Vec<Vector3>,
+    /// Travel speed in units per second
+    pub speed: f32,
+    /// Index of the waypoint currently being approached
+    pub current_waypoint: usize,
+    /// How far the platform moved last update (applied to riders)
+    pub frame_displacement: Vector3,
+}
+
+impl MovingPlatform {
+    pub fn new(waypoints: Vec<Vector3>, speed: f32) -> Self {
+        Self {
+            waypoints,
+            speed,
+            current_waypoint: 0,
+            frame_displacement: Vector3::zero(),
+        }
     }
 }
 
 /// Collider shapes for collision detection
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum ColliderShape {
     Box { size: Vector3 },
     Sphere { radius: f32 },
     Capsule { radius: f32, height: f32 },
 }
 
+impl ColliderShape {
+    /// Axis-aligned box enclosing the shape at `position`
+    pub fn bounds(&self, position: Vector3) -> Aabb {
+        let half = match *self {
+            ColliderShape::Box { size } => size / 2.0,
+            ColliderShape::Sphere { radius } => Vector3::new(radius, radius, radius),
+            ColliderShape::Capsule { radius, height } => Vector3::new(radius, (height / 2.0).max(radius), radius),
+        };
+        Aabb::new(position - half, position + half)
+    }
+
+    /// Like `bounds`, but with a capsule's segment along `up` instead of world up
+    pub fn oriented_bounds(&self, position: Vector3, up: Vector3) -> Aabb {
+        match *self {
+            ColliderShape::Capsule { radius, height } => {
+                let tip = up * (height / 2.0 - radius).max(0.0);
+                let margin = Vector3::new(radius, radius, radius);
+                let (a, b) = (position + tip, position - tip);
+                Aabb::new(
+                    Vector3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)) - margin,
+                    Vector3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)) + margin,
+                )
+            }
+            _ => self.bounds(position),
+        }
+    }
+}
+
 /// Collider component for collision detection
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Collider {
     pub shape: ColliderShape,
     /// Is this a trigger (no physics response, just detection)
@@ -254,8 +595,15 @@ pub struct Collider {
     pub restitution: f32,
     /// Friction coefficient (0.0 = no friction, 1.0 = high friction)
     pub friction: f32,
+    /// Layer bits this collider belongs to
+    pub collision_layer: u32,
+    /// Layer bits this collider collides with
+    pub collision_mask: u32,
 }
 
+/// Layer colliders start on
+pub const DEFAULT_COLLISION_LAYER: u32 = 1;
+
 impl Collider {
     pub fn box_collider(size: Vector3) -> Self {
         Self {
@@ -263,6 +611,8 @@ impl Collider {
             is_trigger: false,
             restitution: 0.0,
             friction: 0.5,
+            collision_layer: DEFAULT_COLLISION_LAYER,
+            collision_mask: u32::MAX,
         }
     }
 
@@ -272,6 +622,8 @@ impl Collider {
             is_trigger: false,
             restitution: 0.0,
             friction: 0.5,
+            collision_layer: DEFAULT_COLLISION_LAYER,
+            collision_mask: u32::MAX,
         }
     }
 
@@ -281,6 +633,19 @@ impl Collider {
             is_trigger: false,
             restitution: 0.0,
             friction: 0.5,
+            collision_layer: DEFAULT_COLLISION_LAYER,
+            collision_mask: u32::MAX,
+        }
+    }
+
+    /// Collider matching a renderable's shape (cylinders approximated as capsules)
+    /// Models have no known bounds yet, so they get a unit box like their placeholder
+    pub fn from_renderable(renderable: &Renderable) -> Self {
+        match renderable.shape {
+            RenderShape::Cube { size, .. } => Self::box_collider(size),
+            RenderShape::Sphere { radius, .. } => Self::sphere_collider(radius),
+            RenderShape::Cylinder { radius, height, .. } => Self::capsule_collider(radius, height),
+            RenderShape::Model { .. } => Self::box_collider(Vector3::one()),
         }
     }
 
@@ -298,6 +663,23 @@ impl Collider {
         self.friction = friction;
         self
     }
+
+    /// Put the collider on these layer bits
+    pub fn on_layer(mut self, layer: u32) -> Self {
+        self.collision_layer = layer;
+        self
+    }
+
+    /// Only collide with colliders on these layer bits
+    pub fn collides_with(mut self, mask: u32) -> Self {
+        self.collision_mask = mask;
+        self
+    }
+
+    /// Whether each collider's mask accepts the other's layer
+    pub fn can_collide_with(&self, other: &Collider) -> bool {
+        (self.collision_layer & other.collision_mask) != 0 && (other.collision_layer & self.collision_mask) != 0
+    }
 }
 
 impl Default for Camera {
@@ -309,6 +691,23 @@ impl Default for Camera {
             yaw: 0.0,
             pitch: 0.0,
             mouse_sensitivity: 0.1,
+            max_look_per_frame: 30.0,
+            look_reference_fps: None,
+            bob_amplitude: 0.05,
+            bob_frequency: 2.0,
+            sprint_fov_delta: 10.0,
+            effect_smoothing: 8.0,
+            bob_phase: 0.0,
+            bob_offset: 0.0,
+            fov_offset: 0.0,
+            zoom_fov: None,
+            zoom_smoothing: 12.0,
+            zoom_offset: 0.0,
+            shake: None,
+            priority: 0,
+            active: true,
+            near: DEFAULT_NEAR_CLIP,
+            far: DEFAULT_FAR_CLIP,
         }
     }
 }
@@ -326,11 +725,174 @@ impl Camera {
         self
     }
 
+    pub fn with_max_look_per_frame(mut self, degrees: f32) -> Self {
+        self.max_look_per_frame = degrees;
+        self
+    }
+
+    pub fn with_look_reference_fps(mut self, fps: f32) -> Self {
+        self.look_reference_fps = Some(fps);
+        self
+    }
+
+    pub fn with_head_bob(mut self, amplitude: f32, frequency: f32) -> Self {
+        self.bob_amplitude = amplitude;
+        self.bob_frequency = frequency;
+        self
+    }
+
+    pub fn with_sprint_fov(mut self, fov_delta: f32) -> Self {
+        self.sprint_fov_delta = fov_delta;
+        self
+    }
+
+    pub fn with_zoom_smoothing(mut self, smoothing: f32) -> Self {
+        self.zoom_smoothing = smoothing;
+        self
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn with_clip_planes(mut self, near: f32, far: f32) -> Self {
+        self.near = near;
+        self.far = far;
+        self
+    }
+
+    pub fn with_active(mut self, active: bool) -> Self {
+        self.active = active;
+        self
+    }
+
+    /// Apply a mouse delta to yaw/pitch for this frame
+    pub fn apply_look(&mut self, delta_x: f32, delta_y: f32, delta_time: f32) {
+        let (yaw, pitch) = self.look_rotation(delta_x, delta_y, delta_time);
+        self.rotate(yaw, pitch);
+    }
+
+    /// Convert a mouse delta into (yaw, pitch) degrees for this frame
+    /// Non-finite deltas are ignored and each axis is clamped to max_look_per_frame
+    pub fn look_rotation(&self, delta_x: f32, delta_y: f32, delta_time: f32) -> (f32, f32) {
+        let scale = match self.look_reference_fps {
+            Some(fps) => self.mouse_sensitivity * delta_time * fps,
+            None => self.mouse_sensitivity,
+        };
+
+        let limit = self.max_look_per_frame.abs();
+        let convert = |delta: f32| {
+            let degrees = delta * scale;
+            if degrees.is_finite() { degrees.clamp(-limit, limit) } else { 0.0 }
+        };
+
+        (convert(delta_x), -convert(delta_y))
+    }
+
+    /// Rotate by yaw/pitch degrees
+    /// Yaw wraps into [0, 360) and pitch clamps to avoid gimbal lock
+    pub fn rotate(&mut self, yaw: f32, pitch: f32) {
+        if yaw.is_finite() {
+            self.yaw += yaw;
+        }
+        if pitch.is_finite() {
+            self.pitch += pitch;
+        }
+
+        self.yaw = Self::normalize_yaw(self.yaw);
+        self.pitch = Self::clamp_pitch(self.pitch);
+    }
+
+    /// Wrap any yaw angle into [0, 360)
+    pub fn normalize_yaw(yaw: f32) -> f32 {
+        if !yaw.is_finite() {
+            return 0.0;
+        }
+        let wrapped = yaw.rem_euclid(360.0);
+        // rem_euclid can round up to exactly 360.0 for tiny negative inputs
+        if wrapped >= 360.0 { 0.0 } else { wrapped }
+    }
+
+    /// Clamp pitch to +/-89 degrees
+    pub fn clamp_pitch(pitch: f32) -> f32 {
+        if !pitch.is_finite() {
+            return 0.0;
+        }
+        pitch.clamp(-89.0, 89.0)
+    }
+
+    /// Start a screen shake; a weaker shake never cuts short a stronger one
+    pub fn shake(&mut self, magnitude: f32, duration: f32) {
+        let incoming = CameraShake::new(magnitude, duration);
+        match self.shake {
+            Some(current) if current.intensity() >= incoming.intensity() => {}
+            _ => self.shake = Some(incoming),
+        }
+    }
+
+    /// Smoothly zoom to a field of view in degrees
+    pub fn set_zoom(&mut self, target_fov: f32) {
+        self.zoom_fov = Some(target_fov);
+    }
+
+    /// Smoothly return to the base field of view
+    pub fn clear_zoom(&mut self) {
+        self.zoom_fov = None;
+    }
+
+    /// Effective field of view including sprint and zoom effects
+    pub fn effective_fov(&self) -> f32 {
+        self.fov + self.fov_offset + self.zoom_offset
+    }
+
+    /// Advance head-bob, sprint FOV and zoom effects
+    /// The sprint FOV kick is suppressed while zoomed
+    pub fn update_effects(&mut self, horizontal_speed: f32, sprinting: bool, delta_time: f32) {
+        self.bob_phase = (self.bob_phase + horizontal_speed * self.bob_frequency * delta_time)
+            % std::f32::consts::TAU;
+
+        let target_bob = Self::head_bob_offset(self.bob_phase, horizontal_speed, self.bob_amplitude);
+        self.bob_offset = Self::smooth_towards(self.bob_offset, target_bob, self.effect_smoothing, delta_time);
+
+        let target_fov = if sprinting && self.zoom_fov.is_none() { self.sprint_fov_delta } else { 0.0 };
+        self.fov_offset = Self::smooth_towards(self.fov_offset, target_fov, self.effect_smoothing, delta_time);
+
+        let target_zoom = self.zoom_fov.map_or(0.0, |zoom| zoom - self.fov);
+        self.zoom_offset = Self::smooth_towards(self.zoom_offset, target_zoom, self.zoom_smoothing, delta_time);
+
+        if let Some(shake) = &mut self.shake {
+            shake.update(delta_time);
+            if !shake.is_active() {
+                self.shake = None;
+            }
+        }
+    }
+
+    /// Vertical head-bob offset for a phase; zero when standing still
+    pub fn head_bob_offset(phase: f32, horizontal_speed: f32, amplitude: f32) -> f32 {
+        if horizontal_speed < 0.01 {
+            return 0.0;
+        }
+        phase.sin() * amplitude
+    }
+
+    /// Frame-rate independent exponential approach from current to target
+    pub fn smooth_towards(current: f32, target: f32, rate: f32, delta_time: f32) -> f32 {
+        let t = 1.0 - (-rate * delta_time).exp();
+        current + (target - current) * t
+    }
+
     /// Convert to Raylib Camera3D using the entity's transform
     pub fn to_camera3d(&self, position: Vector3) -> Camera3D {
+        let (shake_offset, (shake_yaw, shake_pitch)) = match &self.shake {
+            Some(shake) => (shake.offset(), shake.rotation_offset()),
+            None => (Vector3::zero(), (0.0, 0.0)),
+        };
+
         // Calculate the forward direction from yaw and pitch
-        let pitch_rad = self.pitch.to_radians();
-        let yaw_rad = self.yaw.to_radians();
+        let pitch_rad = Self::clamp_pitch(self.pitch + shake_pitch).to_radians();
+        let yaw_rad = (self.yaw + shake_yaw).to_radians();
 
         let forward = Vector3::new(
             yaw_rad.cos() * pitch_rad.cos(),
@@ -338,8 +900,238 @@ impl Camera {
             yaw_rad.sin() * pitch_rad.cos(),
         );
 
-        let target = position + forward;
+        let eye = Vector3::new(position.x, position.y + self.bob_offset, position.z) + shake_offset;
+        let target = eye + forward;
+
+        Camera3D::perspective(eye, target, self.up, self.effective_fov())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        Camera3D::perspective(position, target, self.up, self.fov)
+    #[test]
+    fn test_yaw_normalization() {
+        assert!((Camera::normalize_yaw(725.0) - 5.0).abs() < 1e-4);
+        assert!((Camera::normalize_yaw(-400.0) - 320.0).abs() < 1e-4);
+        assert_eq!(Camera::normalize_yaw(360.0), 0.0);
+        assert_eq!(Camera::normalize_yaw(-1e-8), 0.0);
+        assert_eq!(Camera::normalize_yaw(f32::NAN), 0.0);
+
+        let mut camera = Camera::default();
+        camera.rotate(725.0, 0.0);
+        assert!((camera.yaw - 5.0).abs() < 1e-4);
+        camera.rotate(-405.0, 0.0);
+        assert!((camera.yaw - 320.0).abs() < 1e-4);
+        assert!(camera.yaw >= 0.0 && camera.yaw < 360.0);
+    }
+
+    #[test]
+    fn test_pitch_clamped_and_nan_ignored() {
+        let mut camera = Camera::default();
+        camera.rotate(0.0, 500.0);
+        assert_eq!(camera.pitch, 89.0);
+        camera.rotate(0.0, -1000.0);
+        assert_eq!(camera.pitch, -89.0);
+
+        camera.yaw = 90.0;
+        camera.apply_look(f32::NAN, f32::INFINITY, 1.0 / 60.0);
+        assert_eq!(camera.yaw, 90.0);
+        assert_eq!(camera.pitch, -89.0);
+    }
+
+    #[test]
+    fn test_look_spike_clamped() {
+        let camera = Camera::default().with_sensitivity(0.1).with_max_look_per_frame(20.0);
+
+        // Normal movement passes through unchanged
+        let (yaw, pitch) = camera.look_rotation(50.0, 30.0, 1.0 / 60.0);
+        assert!((yaw - 5.0).abs() < 1e-5);
+        assert!((pitch + 3.0).abs() < 1e-5);
+
+        // A hitch-sized spike is limited to the per-frame max
+        let (yaw, pitch) = camera.look_rotation(5000.0, -8000.0, 0.5);
+        assert_eq!(yaw, 20.0);
+        assert_eq!(pitch, 20.0);
+
+        let mut camera = camera;
+        camera.apply_look(-5000.0, 0.0, 0.5);
+        assert_eq!(camera.yaw, 340.0);
+    }
+
+    #[test]
+    fn test_look_reference_fps_scaling() {
+        let camera = Camera::default().with_sensitivity(0.1).with_look_reference_fps(60.0);
+
+        // At the reference rate the scale is 1.0
+        let (yaw, _) = camera.look_rotation(50.0, 0.0, 1.0 / 60.0);
+        assert!((yaw - 5.0).abs() < 1e-4);
+
+        // At half the frame rate, the same delta turns twice as far
+        let (yaw, _) = camera.look_rotation(50.0, 0.0, 1.0 / 30.0);
+        assert!((yaw - 10.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_head_bob_zero_at_standstill() {
+        for i in 0..8 {
+            let phase = i as f32 * 0.7;
+            assert_eq!(Camera::head_bob_offset(phase, 0.0, 0.1), 0.0);
+        }
+
+        let mut camera = Camera::default();
+        for _ in 0..60 {
+            camera.update_effects(0.0, false, 1.0 / 60.0);
+        }
+        assert_eq!(camera.bob_offset, 0.0);
+    }
+
+    #[test]
+    fn test_head_bob_periodic_while_moving() {
+        let tau = std::f32::consts::TAU;
+        let a = Camera::head_bob_offset(1.0, 5.0, 0.1);
+        let b = Camera::head_bob_offset(1.0 + tau, 5.0, 0.1);
+        assert!((a - b).abs() < 1e-5);
+
+        // Peaks at the amplitude a quarter cycle in
+        assert!((Camera::head_bob_offset(tau / 4.0, 5.0, 0.1) - 0.1).abs() < 1e-5);
+
+        // Walking produces both positive and negative offsets
+        let mut camera = Camera::default();
+        let (mut min, mut max) = (0.0f32, 0.0f32);
+        for _ in 0..120 {
+            camera.update_effects(5.0, false, 1.0 / 60.0);
+            min = min.min(camera.bob_offset);
+            max = max.max(camera.bob_offset);
+        }
+        assert!(min < 0.0 && max > 0.0);
+    }
+
+    #[test]
+    fn test_sprint_fov_lerp() {
+        assert_eq!(Camera::smooth_towards(60.0, 70.0, 8.0, 0.0), 60.0);
+        let halfway = Camera::smooth_towards(0.0, 10.0, 8.0, 0.1);
+        assert!(halfway > 0.0 && halfway < 10.0);
+
+        let mut camera = Camera::new(60.0).with_sprint_fov(10.0);
+        for _ in 0..120 {
+            camera.update_effects(8.0, true, 1.0 / 60.0);
+        }
+        assert!((camera.effective_fov() - 70.0).abs() < 0.01);
+
+        for _ in 0..120 {
+            camera.update_effects(0.0, false, 1.0 / 60.0);
+        }
+        assert!((camera.effective_fov() - 60.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_zoom_lerps_fov() {
+        let mut camera = Camera::new(60.0).with_sprint_fov(10.0);
+        camera.set_zoom(30.0);
+        camera.update_effects(8.0, true, 1.0 / 60.0);
+        let fov = camera.effective_fov();
+        assert!(fov > 30.0 && fov < 60.0, "zoom should blend in, got {}", fov);
+
+        for _ in 0..120 {
+            camera.update_effects(8.0, true, 1.0 / 60.0);
+        }
+        // Zoom wins over the sprint kick
+        assert!((camera.effective_fov() - 30.0).abs() < 0.01);
+        assert!((camera.to_camera3d(Vector3::zero()).fovy - 30.0).abs() < 0.01);
+
+        camera.clear_zoom();
+        for _ in 0..120 {
+            camera.update_effects(0.0, false, 1.0 / 60.0);
+        }
+        assert!((camera.effective_fov() - 60.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_collider_from_renderable() {
+        let cube = Renderable::cube(Vector3::new(1.0, 2.0, 3.0), Color::RED);
+        match Collider::from_renderable(&cube).shape {
+            ColliderShape::Box { size } => assert_eq!(size, Vector3::new(1.0, 2.0, 3.0)),
+            other => panic!("expected box, got {:?}", other),
+        }
+
+        let sphere = Renderable::sphere(0.75, Color::RED);
+        match Collider::from_renderable(&sphere).shape {
+            ColliderShape::Sphere { radius } => assert_eq!(radius, 0.75),
+            other => panic!("expected sphere, got {:?}", other),
+        }
+
+        let cylinder = Renderable::cylinder(0.5, 2.0, Color::RED);
+        match Collider::from_renderable(&cylinder).shape {
+            ColliderShape::Capsule { radius, height } => {
+                assert_eq!(radius, 0.5);
+                assert_eq!(height, 2.0);
+            }
+            other => panic!("expected capsule, got {:?}", other),
+        }
+
+        let model = Renderable {
+            shape: RenderShape::Model { path: "assets/crate.glb".to_string() },
+            visible: true,
+        };
+        match Collider::from_renderable(&model).shape {
+            ColliderShape::Box { size } => assert_eq!(size, Vector3::one()),
+            other => panic!("expected box, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_shake_offset_decays() {
+        let (magnitude, duration, frequency) = (0.5, 1.0, 25.0);
+
+        assert_eq!(CameraShake::shake_offset(magnitude, duration, 0.0, frequency), Vector3::zero());
+        assert_eq!(CameraShake::shake_offset(0.0, duration, 0.5, frequency), Vector3::zero());
+
+        let mut previous_bound = f32::MAX;
+        for step in 0..=10 {
+            let remaining = duration - step as f32 * 0.1;
+            let bound = CameraShake::shake_intensity(magnitude, duration, remaining);
+            let offset = CameraShake::shake_offset(magnitude, duration, remaining, frequency);
+            assert!(bound <= previous_bound);
+            assert!(offset.x.abs() <= bound + 1e-6 && offset.y.abs() <= bound + 1e-6 && offset.z.abs() <= bound + 1e-6);
+            previous_bound = bound;
+        }
+        assert!(previous_bound.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_camera_shake_lifecycle() {
+        let mut camera = Camera::default();
+        let position = Vector3::new(0.0, 2.0, 0.0);
+        let still = camera.to_camera3d(position);
+
+        camera.shake(0.3, 0.5);
+        for _ in 0..3 {
+            camera.update_effects(0.0, false, 0.05);
+        }
+        assert!(camera.shake.is_some());
+        assert_ne!(camera.to_camera3d(position).position, still.position);
+
+        for _ in 0..10 {
+            camera.update_effects(0.0, false, 0.05);
+        }
+        assert!(camera.shake.is_none());
+        assert_eq!(camera.to_camera3d(position).position, still.position);
+    }
+
+    mod tag_imports {
+        // The rpg and ecs globs are imported side by side; this only compiles
+        // because the ecs tags no longer share the rpg type names
+        use crate::ecs::components::*;
+        use crate::rpg::*;
+
+        #[test]
+        fn test_tags_coexist_with_rpg_types() {
+            let player = Player::new("Tester", PlayerClass::Warrior);
+            let _tags = (PlayerTag, EnemyTag);
+            assert_eq!(player.name, "Tester");
+            assert!(std::mem::size_of::<EnemyTag>() == 0);
+        }
     }
 }
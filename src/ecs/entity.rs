@@ -1,16 +1,89 @@
-use std::collections::HashMap;
+use raylib::prelude::{Color, Vector3};
+use serde::{Deserialize, Serialize};
+use std::any::{Any, TypeId};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use super::components::*;
 use super::examples::*;
 
 pub type EntityId = usize;
 
+/// Bitmask of the components an entity has, for cheap filtering
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ComponentMask(pub u64);
+
+impl ComponentMask {
+    pub const EMPTY: Self = Self(0);
+    pub const TRANSFORM: Self = Self(1 << 0);
+    pub const RENDERABLE: Self = Self(1 << 1);
+    pub const VELOCITY: Self = Self(1 << 2);
+    pub const HEALTH: Self = Self(1 << 3);
+    pub const NAME: Self = Self(1 << 4);
+    pub const CAMERA: Self = Self(1 << 5);
+    pub const RIGIDBODY: Self = Self(1 << 6);
+    pub const COLLIDER: Self = Self(1 << 7);
+    pub const MOVING_PLATFORM: Self = Self(1 << 8);
+    pub const PLAYER: Self = Self(1 << 9);
+    pub const ENEMY: Self = Self(1 << 10);
+    pub const LIFETIME: Self = Self(1 << 11);
+    pub const COOLDOWN: Self = Self(1 << 12);
+    pub const GRAVITY: Self = Self(1 << 13);
+    pub const BOUNCY: Self = Self(1 << 14);
+    pub const DRAG: Self = Self(1 << 15);
+    pub const PROJECTILE: Self = Self(1 << 16);
+    pub const ATTACK_ABILITY: Self = Self(1 << 17);
+    pub const FOLLOW_TARGET: Self = Self(1 << 18);
+    pub const PATROL_PATH: Self = Self(1 << 19);
+    pub const FADE_OUT: Self = Self(1 << 20);
+    pub const AUTO_ROTATE: Self = Self(1 << 21);
+    pub const COLLECTIBLE: Self = Self(1 << 22);
+    pub const OBSTACLE: Self = Self(1 << 23);
+    pub const DAMAGEABLE: Self = Self(1 << 24);
+    pub const MARKED_FOR_DEATH: Self = Self(1 << 25);
+    pub const PARENT: Self = Self(1 << 26);
+    pub const CHILD: Self = Self(1 << 27);
+    pub const MODEL: Self = Self(1 << 28);
+    pub const DEATH_STATE: Self = Self(1 << 29);
+    pub const TINT: Self = Self(1 << 30);
+    pub const HIT_FLASH: Self = Self(1 << 31);
+    pub const STATUS_EFFECTS: Self = Self(1 << 32);
+    pub const PERSISTENT_ID: Self = Self(1 << 33);
+    pub const CONTAINER: Self = Self(1 << 34);
+    pub const TELEGRAPH: Self = Self(1 << 35);
+    pub const SELECTED: Self = Self(1 << 36);
+    pub const SCALE_OVER_TIME: Self = Self(1 << 37);
+
+    /// Whether every component in `other` is present
+    pub fn contains(&self, other: ComponentMask) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn insert(&mut self, other: ComponentMask) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: ComponentMask) {
+        self.0 &= !other.0;
+    }
+}
+
+impl std::ops::BitOr for ComponentMask {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
 /// Simple entity - just an ID with components
 ///
 /// This struct holds optional components for each entity.
 /// Not all entities need all components - only add what you need!
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Entity {
     pub id: EntityId,
+    /// Disabled entities keep their components but are skipped by rendering,
+    /// movement and physics
+    pub enabled: bool,
 
     // Core components
     pub transform: Option<Transform>,
@@ -19,14 +92,18 @@ pub struct Entity {
     pub health: Option<Health>,
     pub name: Option<Name>,
     pub camera: Option<Camera>,
+    pub persistent_id: Option<PersistentId>,
+    pub container: Option<Container>,
 
     // Physics components
     pub rigidbody: Option<Rigidbody>,
     pub collider: Option<Collider>,
+    pub moving_platform: Option<MovingPlatform>,
 
     // Tag components
     pub is_player: bool,
     pub is_enemy: bool,
+    pub selected: Option<Selected>,
 
     // Example components - Timing
     pub lifetime: Option<Lifetime>,
@@ -40,6 +117,7 @@ pub struct Entity {
     // Example components - Combat
     pub projectile: Option<Projectile>,
     pub attack_ability: Option<AttackAbility>,
+    pub telegraph: Option<Telegraph>,
 
     // Example components - AI
     pub follow_target: Option<FollowTarget>,
@@ -48,12 +126,17 @@ pub struct Entity {
     // Example components - Visual Effects
     pub fade_out: Option<FadeOut>,
     pub auto_rotate: Option<AutoRotate>,
+    pub scale_over_time: Option<ScaleOverTime>,
+    pub tint: Option<Tint>,
+    pub hit_flash: Option<HitFlash>,
+    pub status_effects: Option<StatusEffects>,
 
     // Example components - Tags
     pub collectible: Option<Collectible>,
     pub obstacle: Option<Obstacle>,
     pub damageable: Option<Damageable>,
     pub marked_for_death: Option<MarkedForDeath>,
+    pub death_state: Option<DeathState>,
 
     // Example components - Utility
     pub parent: Option<Parent>,
@@ -67,16 +150,21 @@ impl Entity {
     pub fn new(id: EntityId) -> Self {
         Self {
             id,
+            enabled: true,
             transform: None,
             renderable: None,
             velocity: None,
             health: None,
             name: None,
             camera: None,
+            persistent_id: None,
+            container: None,
             rigidbody: None,
             collider: None,
+            moving_platform: None,
             is_player: false,
             is_enemy: false,
+            selected: None,
             lifetime: None,
             cooldown: None,
             gravity: None,
@@ -84,14 +172,20 @@ impl Entity {
             drag: None,
             projectile: None,
             attack_ability: None,
+            telegraph: None,
             follow_target: None,
             patrol_path: None,
             fade_out: None,
             auto_rotate: None,
+            scale_over_time: None,
+            tint: None,
+            hit_flash: None,
+            status_effects: None,
             collectible: None,
             obstacle: None,
             damageable: None,
             marked_for_death: None,
+            death_state: None,
             parent: None,
             child: None,
             model: None,
@@ -148,49 +242,156 @@ impl Entity {
         self
     }
 
+    pub fn with_moving_platform(mut self, platform: MovingPlatform) -> Self {
+        self.moving_platform = Some(platform);
+        self
+    }
+
     pub fn with_model(mut self, model: Model) -> Self {
         self.model = Some(model);
         self
     }
+
+    /// Compute the component mask from the components currently present
+    pub fn component_mask(&self) -> ComponentMask {
+        let present = [
+            (self.transform.is_some(), ComponentMask::TRANSFORM),
+            (self.renderable.is_some(), ComponentMask::RENDERABLE),
+            (self.velocity.is_some(), ComponentMask::VELOCITY),
+            (self.health.is_some(), ComponentMask::HEALTH),
+            (self.name.is_some(), ComponentMask::NAME),
+            (self.camera.is_some(), ComponentMask::CAMERA),
+            (self.rigidbody.is_some(), ComponentMask::RIGIDBODY),
+            (self.collider.is_some(), ComponentMask::COLLIDER),
+            (self.moving_platform.is_some(), ComponentMask::MOVING_PLATFORM),
+            (self.is_player, ComponentMask::PLAYER),
+            (self.is_enemy, ComponentMask::ENEMY),
+            (self.lifetime.is_some(), ComponentMask::LIFETIME),
+            (self.cooldown.is_some(), ComponentMask::COOLDOWN),
+            (self.gravity.is_some(), ComponentMask::GRAVITY),
+            (self.bouncy.is_some(), ComponentMask::BOUNCY),
+            (self.drag.is_some(), ComponentMask::DRAG),
+            (self.projectile.is_some(), ComponentMask::PROJECTILE),
+            (self.attack_ability.is_some(), ComponentMask::ATTACK_ABILITY),
+            (self.follow_target.is_some(), ComponentMask::FOLLOW_TARGET),
+            (self.patrol_path.is_some(), ComponentMask::PATROL_PATH),
+            (self.fade_out.is_some(), ComponentMask::FADE_OUT),
+            (self.auto_rotate.is_some(), ComponentMask::AUTO_ROTATE),
+            (self.collectible.is_some(), ComponentMask::COLLECTIBLE),
+            (self.obstacle.is_some(), ComponentMask::OBSTACLE),
+            (self.damageable.is_some(), ComponentMask::DAMAGEABLE),
+            (self.marked_for_death.is_some(), ComponentMask::MARKED_FOR_DEATH),
+            (self.parent.is_some(), ComponentMask::PARENT),
+            (self.child.is_some(), ComponentMask::CHILD),
+            (self.model.is_some(), ComponentMask::MODEL),
+            (self.death_state.is_some(), ComponentMask::DEATH_STATE),
+            (self.tint.is_some(), ComponentMask::TINT),
+            (self.hit_flash.is_some(), ComponentMask::HIT_FLASH),
+            (self.status_effects.is_some(), ComponentMask::STATUS_EFFECTS),
+            (self.persistent_id.is_some(), ComponentMask::PERSISTENT_ID),
+            (self.container.is_some(), ComponentMask::CONTAINER),
+            (self.telegraph.is_some(), ComponentMask::TELEGRAPH),
+            (self.selected.is_some(), ComponentMask::SELECTED),
+            (self.scale_over_time.is_some(), ComponentMask::SCALE_OVER_TIME),
+        ];
+
+        let mut mask = ComponentMask::EMPTY;
+        for (has, bit) in present {
+            if has {
+                mask.insert(bit);
+            }
+        }
+        mask
+    }
 }
 
 /// World holds all entities
 pub struct World {
-    entities: HashMap<EntityId, Entity>,
+    /// Ordered by id so iteration (and therefore every system) is deterministic
+    entities: BTreeMap<EntityId, Entity>,
     next_id: EntityId,
+    /// Cached component masks, refreshed lazily by query_mask
+    masks: BTreeMap<EntityId, ComponentMask>,
+    /// Entities handed out mutably since their mask was last computed
+    dirty_masks: HashSet<EntityId>,
+    /// Set when every entity may have changed (entities_mut)
+    all_masks_dirty: bool,
+    /// User-defined components that have no field on `Entity`
+    custom_components: HashMap<(EntityId, TypeId), Box<dyn Any>>,
 }
 
 impl World {
     pub fn new() -> Self {
         Self {
-            entities: HashMap::new(),
+            entities: BTreeMap::new(),
             next_id: 0,
+            masks: BTreeMap::new(),
+            dirty_masks: HashSet::new(),
+            all_masks_dirty: false,
+            custom_components: HashMap::new(),
         }
     }
 
     pub fn create_entity(&mut self) -> EntityId {
         let id = self.next_id;
         self.next_id += 1;
-        self.entities.insert(id, Entity::new(id));
+        self.insert_entity(Entity::new(id));
         id
     }
 
     pub fn add_entity(&mut self, entity: Entity) -> EntityId {
         let id = entity.id;
-        self.entities.insert(id, entity);
+        self.insert_entity(entity);
         id
     }
 
+    fn insert_entity(&mut self, entity: Entity) {
+        self.masks.insert(entity.id, entity.component_mask());
+        self.dirty_masks.remove(&entity.id);
+        self.entities.insert(entity.id, entity);
+    }
+
     pub fn get_entity(&self, id: EntityId) -> Option<&Entity> {
         self.entities.get(&id)
     }
 
     pub fn get_entity_mut(&mut self, id: EntityId) -> Option<&mut Entity> {
-        self.entities.get_mut(&id)
+        let entity = self.entities.get_mut(&id)?;
+        self.dirty_masks.insert(id);
+        Some(entity)
     }
 
+    /// Remove an entity and scrub references to it from the rest of the world
+    /// Followers lose their FollowTarget, orphaned children are detached and the
+    /// id is dropped from any parent's children list
     pub fn remove_entity(&mut self, id: EntityId) -> Option<Entity> {
-        self.entities.remove(&id)
+        let removed = self.entities.remove(&id)?;
+        self.masks.remove(&id);
+        self.dirty_masks.remove(&id);
+        self.custom_components.retain(|(entity_id, _), _| *entity_id != id);
+
+        for entity in self.entities.values_mut() {
+            let mut changed = false;
+            if entity.follow_target.is_some_and(|follow| follow.target_id == id) {
+                entity.follow_target = None;
+                changed = true;
+            }
+            if entity.child.is_some_and(|child| child.parent_id == id) {
+                entity.child = None;
+                changed = true;
+            }
+            if let Some(parent) = &mut entity.parent
+                && parent.children.contains(&id)
+            {
+                parent.children.retain(|&child_id| child_id != id);
+                changed = true;
+            }
+            if changed {
+                self.dirty_masks.insert(entity.id);
+            }
+        }
+
+        Some(removed)
     }
 
     pub fn entities(&self) -> impl Iterator<Item = &Entity> {
@@ -198,14 +399,216 @@ impl World {
     }
 
     pub fn entities_mut(&mut self) -> impl Iterator<Item = &mut Entity> {
+        self.all_masks_dirty = true;
         self.entities.values_mut()
     }
 
+    /// Entities that haven't been disabled
+    pub fn enabled_entities(&self) -> impl Iterator<Item = &Entity> {
+        self.entities.values().filter(|entity| entity.enabled)
+    }
+
+    pub fn enabled_entities_mut(&mut self) -> impl Iterator<Item = &mut Entity> {
+        self.entities_mut().filter(|entity| entity.enabled)
+    }
+
+    /// Enable or disable an entity without removing it
+    /// Returns false if the entity doesn't exist
+    pub fn set_enabled(&mut self, id: EntityId, enabled: bool) -> bool {
+        match self.entities.get_mut(&id) {
+            Some(entity) => {
+                entity.enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Attach a user-defined component, replacing any previous one of the same type
+    /// Returns false if the entity doesn't exist
+    pub fn insert_component<T: 'static>(&mut self, id: EntityId, component: T) -> bool {
+        if !self.entities.contains_key(&id) {
+            return false;
+        }
+        self.custom_components.insert((id, TypeId::of::<T>()), Box::new(component));
+        true
+    }
+
+    pub fn get_component<T: 'static>(&self, id: EntityId) -> Option<&T> {
+        self.custom_components.get(&(id, TypeId::of::<T>()))?.downcast_ref()
+    }
+
+    pub fn get_component_mut<T: 'static>(&mut self, id: EntityId) -> Option<&mut T> {
+        self.custom_components.get_mut(&(id, TypeId::of::<T>()))?.downcast_mut()
+    }
+
+    /// Detach a user-defined component, returning it
+    pub fn remove_component<T: 'static>(&mut self, id: EntityId) -> Option<T> {
+        let component = self.custom_components.remove(&(id, TypeId::of::<T>()))?;
+        component.downcast().ok().map(|component| *component)
+    }
+
+    /// Ids of entities that have every component in `required`, in ascending id order
+    ///
+    /// Uses cached bitmasks, only recomputing those of entities accessed mutably since
+    /// the last query.
+    pub fn query_mask(&mut self, required: ComponentMask) -> Vec<EntityId> {
+        self.refresh_masks();
+        self.masks
+            .iter()
+            .filter(|(_, mask)| mask.contains(required))
+            .map(|(&id, _)| id)
+            .collect()
+    }
+
+    fn refresh_masks(&mut self) {
+        if self.all_masks_dirty {
+            self.masks = self.entities.iter().map(|(&id, e)| (id, e.component_mask())).collect();
+            self.all_masks_dirty = false;
+            self.dirty_masks.clear();
+            return;
+        }
+
+        for id in self.dirty_masks.drain() {
+            if let Some(entity) = self.entities.get(&id) {
+                self.masks.insert(id, entity.component_mask());
+            }
+        }
+    }
+
+    /// Entities that have both a transform and a renderable
+    pub fn renderables(&self) -> impl Iterator<Item = (EntityId, &Transform, &Renderable)> {
+        self.entities.values().filter_map(|entity| {
+            Some((entity.id, entity.transform.as_ref()?, entity.renderable.as_ref()?))
+        })
+    }
+
+    /// Entities taking part in physics (transform, rigidbody and collider)
+    pub fn physics_bodies(&self) -> impl Iterator<Item = (EntityId, &Transform, &Rigidbody, &Collider)> {
+        self.entities.values().filter_map(|entity| {
+            Some((
+                entity.id,
+                entity.transform.as_ref()?,
+                entity.rigidbody.as_ref()?,
+                entity.collider.as_ref()?,
+            ))
+        })
+    }
+
+    /// Enabled entities with both a transform and a velocity
+    pub fn with_transform_velocity(&self) -> impl Iterator<Item = (EntityId, &Transform, &Velocity)> {
+        self.enabled_entities().filter_map(|entity| {
+            Some((entity.id, entity.transform.as_ref()?, entity.velocity.as_ref()?))
+        })
+    }
+
+    /// Like `with_transform_velocity`, with the transform mutable
+    pub fn with_transform_velocity_mut(&mut self) -> impl Iterator<Item = (EntityId, &mut Transform, &Velocity)> {
+        self.enabled_entities_mut().filter_map(|entity| {
+            Some((entity.id, entity.transform.as_mut()?, entity.velocity.as_ref()?))
+        })
+    }
+
+    /// Enabled physics bodies with their transform and rigidbody mutable
+    pub fn physics_bodies_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (EntityId, &mut Transform, &mut Rigidbody, &Collider)> {
+        self.enabled_entities_mut().filter_map(|entity| {
+            Some((
+                entity.id,
+                entity.transform.as_mut()?,
+                entity.rigidbody.as_mut()?,
+                entity.collider.as_ref()?,
+            ))
+        })
+    }
+
+    /// Enemy-tagged entities with a transform (health is optional)
+    pub fn enemies(&self) -> impl Iterator<Item = (EntityId, &Transform, Option<&Health>)> {
+        self.entities
+            .values()
+            .filter(|entity| entity.is_enemy)
+            .filter_map(|entity| Some((entity.id, entity.transform.as_ref()?, entity.health.as_ref())))
+    }
+
+    /// All camera entities (active or not), e.g. for split-screen or picture-in-picture views
+    pub fn cameras(&self) -> impl Iterator<Item = (EntityId, &Camera)> {
+        self.entities
+            .values()
+            .filter_map(|entity| Some((entity.id, entity.camera.as_ref()?)))
+    }
+
+    /// Entity whose camera should be rendered from
+    /// Picks the highest-priority active camera; ties prefer a player-controlled
+    /// entity, then the lowest id
+    pub fn active_camera(&self) -> Option<EntityId> {
+        self.entities
+            .values()
+            .filter_map(|entity| Some((entity, entity.camera.as_ref()?)))
+            .filter(|(_, camera)| camera.active)
+            .min_by_key(|(entity, camera)| (-camera.priority, !entity.is_player, entity.id))
+            .map(|(entity, _)| entity.id)
+    }
+
     pub fn clear(&mut self) {
         self.entities.clear();
+        self.masks.clear();
+        self.dirty_masks.clear();
+        self.custom_components.clear();
+        self.all_masks_dirty = false;
         self.next_id = 0;
     }
 
+    /// Id the next spawned entity will get
+    pub fn next_id(&self) -> EntityId {
+        self.next_id
+    }
+
+    /// Replace every entity with `entities`, keeping their ids
+    /// New entities are numbered from `next_id`, or after the highest restored id
+    /// if that is larger. Custom components are dropped along with the old entities
+    pub fn restore(&mut self, entities: impl IntoIterator<Item = Entity>, next_id: EntityId) {
+        self.clear();
+        self.next_id = next_id;
+        for entity in entities {
+            self.next_id = self.next_id.max(entity.id + 1);
+            self.insert_entity(entity);
+        }
+    }
+
+    /// Spawn a dynamic physics prop (mass 1.0) whose renderable matches its collider
+    pub fn spawn_prop(&mut self, position: Vector3, shape: ColliderShape, color: Color) -> EntityId {
+        self.spawn_physics_body(position, shape, color, Rigidbody::new(1.0))
+    }
+
+    /// Spawn an immovable body (walls, floors, obstacles)
+    pub fn spawn_static(&mut self, position: Vector3, shape: ColliderShape, color: Color) -> EntityId {
+        self.spawn_physics_body(position, shape, color, Rigidbody::static_body())
+    }
+
+    /// Spawn a dynamic box with the given mass
+    pub fn spawn_dynamic_box(&mut self, position: Vector3, size: Vector3, color: Color, mass: f32) -> EntityId {
+        self.spawn_physics_body(position, ColliderShape::Box { size }, color, Rigidbody::new(mass))
+    }
+
+    fn spawn_physics_body(
+        &mut self,
+        position: Vector3,
+        shape: ColliderShape,
+        color: Color,
+        rigidbody: Rigidbody,
+    ) -> EntityId {
+        self.spawn()
+            .with_transform(Transform::new(position))
+            .with_renderable(Renderable::from_collider_shape(&shape, color))
+            .with_rigidbody(rigidbody)
+            .with_collider(Collider {
+                shape,
+                ..Collider::box_collider(Vector3::one())
+            })
+            .build()
+    }
+
     /// Builder-style entity creation
     pub fn spawn(&mut self) -> EntityBuilder {
         let id = self.next_id;
@@ -285,9 +688,312 @@ impl<'a> EntityBuilder<'a> {
         self
     }
 
+    pub fn with_moving_platform(mut self, platform: MovingPlatform) -> Self {
+        self.entity.moving_platform = Some(platform);
+        self
+    }
+
+    pub fn with_persistent_id(mut self, id: String) -> Self {
+        self.entity.persistent_id = Some(PersistentId(id));
+        self
+    }
+
+    pub fn with_container(mut self, container: Container) -> Self {
+        self.entity.container = Some(container);
+        self
+    }
+
+    pub fn with_scale_over_time(mut self, scale_over_time: ScaleOverTime) -> Self {
+        self.entity.scale_over_time = Some(scale_over_time);
+        self
+    }
+
     pub fn build(self) -> EntityId {
         let id = self.entity.id;
-        self.world.entities.insert(id, self.entity);
+        self.world.insert_entity(self.entity);
         id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spawn_dynamic_box() {
+        let mut world = World::new();
+        let size = Vector3::new(1.0, 2.0, 3.0);
+        let id = world.spawn_dynamic_box(Vector3::new(0.0, 5.0, 0.0), size, Color::new(200, 120, 40, 255), 4.0);
+
+        let entity = world.get_entity(id).unwrap();
+        assert_eq!(entity.transform.unwrap().position, Vector3::new(0.0, 5.0, 0.0));
+
+        match entity.collider.unwrap().shape {
+            ColliderShape::Box { size: collider_size } => assert_eq!(collider_size, size),
+            other => panic!("expected box collider, got {:?}", other),
+        }
+        match &entity.renderable.as_ref().unwrap().shape {
+            RenderShape::Cube { size: render_size, color } => {
+                assert_eq!(*render_size, size);
+                assert_eq!(*color, [200, 120, 40, 255]);
+            }
+            other => panic!("expected cube renderable, got {:?}", other),
+        }
+
+        let rigidbody = entity.rigidbody.unwrap();
+        assert_eq!(rigidbody.mass, 4.0);
+        assert!(rigidbody.is_dynamic());
+    }
+
+    #[test]
+    fn test_spawn_static_and_prop() {
+        let mut world = World::new();
+        let wall = world.spawn_static(Vector3::zero(), ColliderShape::Box { size: Vector3::one() }, Color::GRAY);
+        let ball = world.spawn_prop(Vector3::zero(), ColliderShape::Sphere { radius: 0.5 }, Color::RED);
+
+        assert!(world.get_entity(wall).unwrap().rigidbody.unwrap().is_static());
+
+        let ball = world.get_entity(ball).unwrap();
+        assert!(ball.rigidbody.unwrap().is_dynamic());
+        assert!(matches!(ball.renderable.as_ref().unwrap().shape, RenderShape::Sphere { radius, .. } if radius == 0.5));
+    }
+
+    #[test]
+    fn test_renderables_requires_transform_and_renderable() {
+        let mut world = World::new();
+        let both = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_renderable(Renderable::cube(Vector3::one(), Color::RED))
+            .build();
+        world.spawn().with_transform(Transform::new(Vector3::zero())).build();
+        world.spawn().with_renderable(Renderable::sphere(1.0, Color::RED)).build();
+
+        let ids: Vec<EntityId> = world.renderables().map(|(id, _, _)| id).collect();
+        assert_eq!(ids, vec![both]);
+    }
+
+    #[test]
+    fn test_physics_bodies_and_enemies() {
+        let mut world = World::new();
+        let body = world.spawn_prop(Vector3::zero(), ColliderShape::Sphere { radius: 0.5 }, Color::RED);
+        world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_rigidbody(Rigidbody::new(1.0))
+            .build();
+        let enemy = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_health(Health::new(10.0))
+            .as_enemy()
+            .build();
+
+        let bodies: Vec<EntityId> = world.physics_bodies().map(|(id, ..)| id).collect();
+        assert_eq!(bodies, vec![body]);
+
+        let enemies: Vec<_> = world.enemies().collect();
+        assert_eq!(enemies.len(), 1);
+        assert_eq!(enemies[0].0, enemy);
+        assert_eq!(enemies[0].2.unwrap().max, 10.0);
+    }
+
+    #[test]
+    fn test_remove_followed_target_clears_follower() {
+        let mut world = World::new();
+        let target = world.spawn().with_transform(Transform::new(Vector3::zero())).build();
+        let other = world.spawn().with_transform(Transform::new(Vector3::zero())).build();
+        let follower = world.spawn().with_transform(Transform::new(Vector3::zero())).build();
+        let loyal = world.spawn().with_transform(Transform::new(Vector3::zero())).build();
+        world.get_entity_mut(follower).unwrap().follow_target = Some(FollowTarget::new(target, 2.0, 3.0));
+        world.get_entity_mut(loyal).unwrap().follow_target = Some(FollowTarget::new(other, 2.0, 3.0));
+
+        assert!(world.remove_entity(target).is_some());
+
+        assert!(world.get_entity(follower).unwrap().follow_target.is_none());
+        assert_eq!(world.get_entity(loyal).unwrap().follow_target.unwrap().target_id, other);
+    }
+
+    #[test]
+    fn test_remove_parent_detaches_children() {
+        let mut world = World::new();
+        let parent = world.spawn().build();
+        let child_a = world.spawn().build();
+        let child_b = world.spawn().build();
+
+        let mut parent_component = Parent::new();
+        parent_component.add_child(child_a);
+        parent_component.add_child(child_b);
+        world.get_entity_mut(parent).unwrap().parent = Some(parent_component);
+        world.get_entity_mut(child_a).unwrap().child = Some(Child::new(parent));
+        world.get_entity_mut(child_b).unwrap().child = Some(Child::new(parent));
+
+        // Removing a child drops it from the parent's list
+        world.remove_entity(child_b);
+        assert_eq!(world.get_entity(parent).unwrap().parent.as_ref().unwrap().children, vec![child_a]);
+
+        // Removing the parent detaches the remaining child
+        world.remove_entity(parent);
+        assert!(world.get_entity(child_a).unwrap().child.is_none());
+        assert!(world.remove_entity(parent).is_none());
+    }
+
+    #[test]
+    fn test_remove_entity_refreshes_masks_of_detached_entities() {
+        let mut world = World::new();
+        let target = world.spawn().with_transform(Transform::new(Vector3::zero())).build();
+        let follower = world.spawn().with_transform(Transform::new(Vector3::zero())).build();
+        world.get_entity_mut(follower).unwrap().follow_target = Some(FollowTarget::new(target, 2.0, 3.0));
+        world.get_entity_mut(follower).unwrap().child = Some(Child::new(target));
+        assert_eq!(world.query_mask(ComponentMask::FOLLOW_TARGET), vec![follower]);
+        assert_eq!(world.query_mask(ComponentMask::CHILD), vec![follower]);
+
+        world.remove_entity(target);
+
+        assert!(world.query_mask(ComponentMask::FOLLOW_TARGET).is_empty());
+        assert!(world.query_mask(ComponentMask::CHILD).is_empty());
+    }
+
+    #[test]
+    fn test_component_mask_tracks_changes() {
+        let mut world = World::new();
+        let id = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_health(Health::new(10.0))
+            .as_enemy()
+            .build();
+
+        let mask = world.get_entity(id).unwrap().component_mask();
+        assert!(mask.contains(ComponentMask::TRANSFORM | ComponentMask::HEALTH | ComponentMask::ENEMY));
+        assert!(!mask.contains(ComponentMask::RENDERABLE));
+
+        world.get_entity_mut(id).unwrap().renderable = Some(Renderable::sphere(1.0, Color::RED));
+        assert_eq!(world.query_mask(ComponentMask::RENDERABLE), vec![id]);
+
+        world.get_entity_mut(id).unwrap().health = None;
+        assert!(world.query_mask(ComponentMask::HEALTH).is_empty());
+
+        for entity in world.entities_mut() {
+            entity.renderable = None;
+        }
+        assert!(world.query_mask(ComponentMask::RENDERABLE).is_empty());
+    }
+
+    #[test]
+    fn test_query_mask_returns_matching_entities() {
+        let mut world = World::new();
+        let body = world.spawn_prop(Vector3::zero(), ColliderShape::Sphere { radius: 0.5 }, Color::RED);
+        world.spawn().with_transform(Transform::new(Vector3::zero())).build();
+        let mover = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_velocity(Velocity::default())
+            .build();
+
+        let physics = ComponentMask::TRANSFORM | ComponentMask::RIGIDBODY | ComponentMask::COLLIDER;
+        assert_eq!(world.query_mask(physics), vec![body]);
+        assert_eq!(world.query_mask(ComponentMask::VELOCITY), vec![mover]);
+
+        // Same order as `entities()`, whatever order the masks were cached in
+        world.get_entity_mut(mover);
+        let all: Vec<EntityId> = world.entities().map(|e| e.id).collect();
+        assert_eq!(all.len(), 3);
+        assert_eq!(world.query_mask(ComponentMask::TRANSFORM), all);
+
+        world.remove_entity(body);
+        assert!(world.query_mask(physics).is_empty());
+    }
+
+    #[test]
+    fn test_cameras_and_active_camera_priority() {
+        let mut world = World::new();
+        let player = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_camera(Camera::default())
+            .as_player()
+            .build();
+        let security = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::new(10.0, 5.0, 0.0)))
+            .with_camera(Camera::default().with_priority(5))
+            .build();
+        let disabled = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_camera(Camera::default().with_priority(10).with_active(false))
+            .build();
+        world.spawn().with_transform(Transform::new(Vector3::zero())).build();
+
+        let mut cameras: Vec<EntityId> = world.cameras().map(|(id, _)| id).collect();
+        cameras.sort();
+        assert_eq!(cameras, vec![player, security, disabled]);
+
+        assert_eq!(world.active_camera(), Some(security));
+
+        world.get_entity_mut(security).unwrap().camera.as_mut().unwrap().active = false;
+        assert_eq!(world.active_camera(), Some(player));
+
+        world.get_entity_mut(disabled).unwrap().camera.as_mut().unwrap().active = true;
+        assert_eq!(world.active_camera(), Some(disabled));
+    }
+
+    #[test]
+    fn test_custom_components_attach_mutate_and_remove() {
+        #[derive(Debug, PartialEq)]
+        struct Mana {
+            current: u32,
+        }
+        struct Faction(&'static str);
+
+        let mut world = World::new();
+        let id = world.spawn().with_transform(Transform::new(Vector3::zero())).build();
+
+        assert!(world.insert_component(id, Mana { current: 10 }));
+        assert!(world.insert_component(id, Faction("goblins")));
+        assert!(!world.insert_component(id + 1, Mana { current: 1 }));
+
+        world.get_component_mut::<Mana>(id).unwrap().current -= 4;
+        assert_eq!(world.get_component::<Mana>(id), Some(&Mana { current: 6 }));
+        assert_eq!(world.get_component::<Faction>(id).map(|f| f.0), Some("goblins"));
+        assert!(world.get_component::<u32>(id).is_none());
+
+        assert_eq!(world.remove_component::<Mana>(id), Some(Mana { current: 6 }));
+        assert!(world.get_component::<Mana>(id).is_none());
+
+        // Removing the entity drops its remaining components
+        world.remove_entity(id);
+        assert!(world.get_component::<Faction>(id).is_none());
+    }
+
+    #[test]
+    fn test_component_queries_skip_incomplete_entities() {
+        let mut world = World::new();
+        let mover = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_velocity(Velocity::default())
+            .build();
+        world.spawn().with_transform(Transform::new(Vector3::zero())).build();
+        world.spawn().with_velocity(Velocity::default()).build();
+        let body = world.spawn_prop(Vector3::zero(), ColliderShape::Sphere { radius: 0.5 }, Color::RED);
+        let disabled = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_velocity(Velocity::default())
+            .build();
+        world.set_enabled(disabled, false);
+
+        let ids: Vec<_> = world.with_transform_velocity().map(|(id, _, _)| id).collect();
+        assert_eq!(ids, vec![mover]);
+
+        for (_, transform, _) in world.with_transform_velocity_mut() {
+            transform.position.y = 2.0;
+        }
+        assert_eq!(world.get_entity(mover).unwrap().transform.unwrap().position.y, 2.0);
+
+        let bodies: Vec<_> = world.physics_bodies_mut().map(|(id, _, _, _)| id).collect();
+        assert_eq!(bodies, vec![body]);
+    }
+}
@@ -4,10 +4,13 @@
 //! Each system should have a single, focused responsibility.
 
 use raylib::prelude::*;
-use super::entity::{World, EntityId};
+use rand::Rng;
+use super::entity::{Entity, EntityId, World};
 use super::components::*;
 use super::examples::*;
 use super::System;
+use crate::rpg::{Enemy, EnemyAction, EnemyDatabase, GameRng, Position, StatusEffect};
+use crate::terrain::Terrain;
 
 // =============================================================================
 // LIFETIME AND CLEANUP SYSTEMS
@@ -60,26 +63,133 @@ impl System for LifetimeSystem {
             }
         }
 
-        // Second pass: remove expired entities
+        // Second pass: remove expired entities (pooled ones are only deactivated)
         // We do this separately because we can't modify world while iterating
         for &id in &self.entities_to_remove {
-            world.remove_entity(id);
+            despawn(world, id);
         }
     }
 }
 
+/// Remove an entity, or deactivate it if it belongs to a pool
+fn despawn(world: &mut World, id: EntityId) {
+    if world.get_component::<Pooled>(id).is_some() {
+        ProjectilePool::deactivate(world, id);
+    } else {
+        world.remove_entity(id);
+    }
+}
+
 /// System that removes entities marked for death
 ///
-/// Useful for deferred deletion when you can't remove during iteration
-pub struct DeathSystem;
+/// Useful for deferred deletion when you can't remove during iteration.
+/// Enemies don't vanish instantly: when they die they get a `DeathState` and
+/// are only removed once its animation has finished.
+pub struct DeathSystem {
+    /// Animation used for dying enemies
+    pub style: DeathStyle,
+    /// Seconds a corpse stays around before removal
+    pub corpse_duration: f32,
+    /// Enemies that started dying this update (e.g. to spawn their loot)
+    pub started_dying: Vec<EntityId>,
+}
+
+impl DeathSystem {
+    /// How far a corpse sinks with DeathStyle::SinkIntoGround
+    pub const SINK_DEPTH: f32 = 1.5;
+
+    pub fn new() -> Self {
+        Self {
+            style: DeathStyle::FadeOut,
+            corpse_duration: 2.0,
+            started_dying: Vec::new(),
+        }
+    }
+
+    pub fn with_style(mut self, style: DeathStyle, corpse_duration: f32) -> Self {
+        self.style = style;
+        self.corpse_duration = corpse_duration;
+        self
+    }
+
+    /// Advance a death animation by one step
+    fn animate(entity: &mut Entity, delta_time: f32) {
+        let Some(death) = &mut entity.death_state else {
+            return;
+        };
+        let previous = death.progress();
+        death.timer += delta_time;
+        let progress = death.progress();
+        let step = progress - previous;
+
+        match death.style {
+            DeathStyle::FadeOut => {
+                let initial_alpha = entity.fade_out.map_or(1.0, |fade| fade.initial_alpha);
+                let alpha = ((1.0 - progress) * initial_alpha * 255.0) as u8;
+                if let Some(renderable) = &mut entity.renderable {
+                    match &mut renderable.shape {
+                        RenderShape::Cube { color, .. } => color[3] = alpha,
+                        RenderShape::Sphere { color, .. } => color[3] = alpha,
+                        RenderShape::Cylinder { color, .. } => color[3] = alpha,
+                        _ => {}
+                    }
+                }
+            }
+            DeathStyle::SinkIntoGround => {
+                if let Some(transform) = &mut entity.transform {
+                    transform.position.y -= Self::SINK_DEPTH * step;
+                }
+            }
+            DeathStyle::Ragdoll => {
+                if let Some(transform) = &mut entity.transform {
+                    transform.rotation.z += std::f32::consts::FRAC_PI_2 * step;
+                }
+            }
+        }
+    }
+}
+
+impl Default for DeathSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl System for DeathSystem {
-    fn update(&mut self, world: &mut World, _delta_time: f32) {
-        let to_remove: Vec<EntityId> = world
-            .entities()
-            .filter(|e| e.marked_for_death.is_some())
-            .map(|e| e.id)
-            .collect();
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        self.started_dying.clear();
+        let mut to_remove = Vec::new();
+
+        for entity in world.entities_mut() {
+            let killed_enemy = entity.is_enemy
+                && entity.death_state.is_none()
+                && (entity.marked_for_death.is_some()
+                    || entity.health.is_some_and(|health| !health.is_alive()));
+
+            if killed_enemy {
+                // Start the corpse animation instead of removing right away
+                entity.marked_for_death = None;
+                entity.death_state = Some(DeathState::new(self.style, self.corpse_duration));
+                if self.style == DeathStyle::FadeOut && entity.fade_out.is_none() {
+                    entity.fade_out = Some(FadeOut::new());
+                }
+                // Corpses no longer move, block or take hits
+                entity.velocity = None;
+                entity.collider = None;
+                entity.damageable = None;
+                self.started_dying.push(entity.id);
+                continue;
+            }
+
+            if entity.death_state.is_some() {
+                Self::animate(entity, delta_time);
+                if entity.death_state.is_some_and(|death| death.is_finished()) {
+                    to_remove.push(entity.id);
+                }
+            } else if entity.marked_for_death.is_some() {
+                to_remove.push(entity.id);
+            }
+        }
 
         for id in to_remove {
             world.remove_entity(id);
@@ -164,6 +274,25 @@ impl System for AutoRotateSystem {
     }
 }
 
+/// System that grows or shrinks entities
+///
+/// This demonstrates:
+/// - Simple visual effects
+/// - Clamping a value per axis
+///
+/// Each axis changes by `rate` per second and stops at `min_scale`/`max_scale`.
+pub struct ScaleOverTimeSystem;
+
+impl System for ScaleOverTimeSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        for entity in world.entities_mut() {
+            if let (Some(transform), Some(scaling)) = (&mut entity.transform, &entity.scale_over_time) {
+                transform.scale = scaling.clamp(transform.scale + scaling.rate * delta_time);
+            }
+        }
+    }
+}
+
 /// System that fades out entities based on their lifetime
 ///
 /// This demonstrates:
@@ -194,6 +323,64 @@ impl System for FadeOutSystem {
     }
 }
 
+/// System that shows status effects on the entity itself
+///
+/// This demonstrates:
+/// - Layering visual state (hit flash over status tint)
+/// - Gameplay side effects from components (Frozen stops movement)
+/// - Expiring timed data
+pub struct StatusVisualSystem;
+
+impl StatusVisualSystem {
+    /// Tint an entity should show: an active hit flash wins over the dominant status effect
+    pub fn resolve_tint(hit_flash: Option<&HitFlash>, status: Option<&StatusEffects>) -> Option<Tint> {
+        if let Some(flash) = hit_flash.filter(|flash| flash.is_active()) {
+            return Some(Tint::new(flash.color));
+        }
+        status
+            .and_then(|status| status.dominant())
+            .and_then(StatusEffects::tint_for)
+            .map(Tint::new)
+    }
+}
+
+impl System for StatusVisualSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        for entity in world.entities_mut() {
+            let mut flash_expired = false;
+            if let Some(flash) = &mut entity.hit_flash {
+                flash.remaining -= delta_time;
+                if !flash.is_active() {
+                    entity.hit_flash = None;
+                    flash_expired = true;
+                }
+            }
+
+            if let Some(status) = &mut entity.status_effects {
+                status.effects.retain_mut(|effect| !effect.update(delta_time));
+            }
+
+            let frozen = entity
+                .status_effects
+                .as_ref()
+                .is_some_and(|status| status.has(StatusEffect::Frozen));
+            if frozen {
+                if let Some(velocity) = &mut entity.velocity {
+                    velocity.linear = Vector3::zero();
+                }
+                if let Some(rigidbody) = &mut entity.rigidbody {
+                    rigidbody.velocity = Vector3::zero();
+                }
+            }
+
+            // An expired flash still owns the tint until it's recomputed here
+            if flash_expired || entity.hit_flash.is_some() || entity.status_effects.is_some() {
+                entity.tint = Self::resolve_tint(entity.hit_flash.as_ref(), entity.status_effects.as_ref());
+            }
+        }
+    }
+}
+
 // =============================================================================
 // COOLDOWN SYSTEMS
 // =============================================================================
@@ -212,6 +399,11 @@ impl System for CooldownSystem {
                 attack.cooldown.remaining = (attack.cooldown.remaining - delta_time).max(0.0);
             }
 
+            // Invincibility frames after taking damage
+            if let Some(health) = &mut entity.health {
+                health.tick_invulnerability(delta_time);
+            }
+
             // Can easily extend to other cooldowns by adding similar checks
             // This shows how components can be independently managed
         }
@@ -338,6 +530,147 @@ impl System for PatrolSystem {
     }
 }
 
+/// System that drives rpg `Enemy` AI on ECS entities
+///
+/// This demonstrates:
+/// - Bridging 2D game logic (`Position`) to 3D transforms (XZ plane)
+/// - Custom components (`Enemy` is attached with `World::insert_component`)
+/// - Turning returned actions into velocity and damage
+///
+/// Enemy logic works in rpg units (ranges and speeds tuned for pixels), so
+/// positions are multiplied by `world_scale` on the way in and velocities
+/// divided by it on the way out. Attacks use the entity's `AttackAbility`,
+/// which `spawn_enemy` adds; run `CooldownSystem` alongside to recharge it.
+pub struct EnemyAISystem {
+    pub enemy_db: EnemyDatabase,
+    /// Rpg units per world unit
+    pub world_scale: f32,
+    rng: GameRng,
+}
+
+impl EnemyAISystem {
+    /// Attacks per second of enemies spawned with `spawn_enemy`
+    pub const ATTACK_SPEED: f32 = 1.0;
+    /// Rpg-unit distance at which `Enemy::update` switches from chasing to attacking
+    const ATTACK_RANGE: f32 = 32.0;
+
+    pub fn new(enemy_db: EnemyDatabase, seed: u64) -> Self {
+        Self {
+            enemy_db,
+            world_scale: 16.0,
+            rng: GameRng::new(seed),
+        }
+    }
+
+    pub fn with_world_scale(mut self, world_scale: f32) -> Self {
+        self.world_scale = world_scale;
+        self
+    }
+
+    /// Rpg position of a world position
+    pub fn to_rpg_position(&self, position: Vector3) -> Position {
+        Position::new(position.x * self.world_scale, position.z * self.world_scale)
+    }
+
+    /// Spawn an enemy of a registered type with health, velocity and an attack
+    /// Returns None if `def_id` isn't in the database
+    pub fn spawn_enemy(&self, world: &mut World, def_id: u32, position: Vector3) -> Option<EntityId> {
+        let def = self.enemy_db.get(def_id)?;
+        let attack = AttackAbility::new(
+            def.base_stats.physical_damage as f32,
+            Self::ATTACK_RANGE / self.world_scale,
+            Self::ATTACK_SPEED,
+        );
+
+        let id = world
+            .spawn()
+            .with_name(def.name.clone())
+            .with_transform(super::components::Transform::new(position))
+            .with_velocity(Velocity::default())
+            .with_health(Health::new(def.base_stats.max_health as f32))
+            .as_enemy()
+            .build();
+        let enemy = Enemy::from_def(crate::rpg::EntityId(id as u64), def, self.to_rpg_position(position));
+        world.insert_component(id, enemy);
+        if let Some(entity) = world.get_entity_mut(id) {
+            entity.attack_ability = Some(attack);
+        }
+        Some(id)
+    }
+}
+
+impl System for EnemyAISystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let Some((player_id, player_position)) = world
+            .entities()
+            .filter(|e| e.is_player && e.enabled)
+            .find_map(|e| e.transform.map(|t| (e.id, t.position)))
+        else {
+            return;
+        };
+        let player_pos = self.to_rpg_position(player_position);
+
+        let enemies: Vec<(EntityId, Vector3)> = world
+            .entities()
+            .filter(|e| e.is_enemy && e.enabled && e.marked_for_death.is_none())
+            .filter_map(|e| e.transform.map(|t| (e.id, t.position)))
+            .collect();
+
+        for (id, position) in enemies {
+            let my_pos = self.to_rpg_position(position);
+            let Some(enemy) = world.get_component_mut::<Enemy>(id) else {
+                continue;
+            };
+            let Some(def) = self.enemy_db.get(enemy.def_id) else {
+                continue;
+            };
+            if enemy.is_dead() {
+                continue;
+            }
+            let action = enemy.update(delta_time, &player_pos, def, &my_pos, &mut self.rng);
+            let speed = def.move_speed / self.world_scale;
+
+            // Roll the crit up front; the enemy component isn't reachable once the entity is borrowed
+            let crit = self.rng.r#gen::<f32>() < enemy.stats.crit_chance;
+            let crit_multiplier = enemy.stats.crit_multiplier;
+
+            let mut hit = None;
+            if let Some(entity) = world.get_entity_mut(id) {
+                let mut heading = Vector3::zero();
+                match action {
+                    Some(EnemyAction::MoveTowards(target)) => {
+                        let offset = Vector3::new(target.x - my_pos.x, 0.0, target.y - my_pos.y) / self.world_scale;
+                        // Don't overshoot a target closer than one step
+                        if offset.length() > speed * delta_time {
+                            heading = offset.normalized() * speed;
+                        } else if delta_time > 0.0 {
+                            heading = offset / delta_time;
+                        }
+                    }
+                    Some(EnemyAction::Attack) => {
+                        let in_reach = |attack: &&mut AttackAbility| {
+                            attack.can_attack() && position.distance_to(player_position) <= attack.range
+                        };
+                        if let Some(attack) = entity.attack_ability.as_mut().filter(in_reach) {
+                            attack.attack();
+                            hit = Some(if crit { attack.damage * crit_multiplier } else { attack.damage });
+                        }
+                    }
+                    None => {}
+                }
+
+                let velocity = entity.velocity.get_or_insert_with(Velocity::default);
+                velocity.linear.x = heading.x;
+                velocity.linear.z = heading.z;
+            }
+
+            if let Some(damage) = hit {
+                apply_damage(world, player_id, damage, crit);
+            }
+        }
+    }
+}
+
 // =============================================================================
 // COMBAT SYSTEMS
 // =============================================================================
@@ -355,16 +688,23 @@ impl System for PatrolSystem {
 pub struct ProjectileCollisionSystem;
 
 impl System for ProjectileCollisionSystem {
-    fn update(&mut self, world: &mut World, _delta_time: f32) {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
         let mut collisions = Vec::new();
 
+        // Age projectiles so their spawn immunity runs out
+        for entity in world.enabled_entities_mut() {
+            if let Some(projectile) = &mut entity.projectile {
+                projectile.age += delta_time;
+            }
+        }
+
         // Find projectiles
-        let projectiles: Vec<(EntityId, Vector3, f32, Option<EntityId>)> = world
-            .entities()
+        let projectiles: Vec<(EntityId, Vector3, f32, Option<EntityId>, bool)> = world
+            .enabled_entities()
             .filter_map(|e| {
                 if let (Some(transform), Some(projectile)) = (&e.transform, &e.projectile) {
-                    if !projectile.has_hit {
-                        return Some((e.id, transform.position, projectile.damage, projectile.owner_id));
+                    if !projectile.has_hit && !projectile.is_spawn_immune() {
+                        return Some((e.id, transform.position, projectile.damage, projectile.owner_id, projectile.crit));
                     }
                 }
                 None
@@ -373,7 +713,7 @@ impl System for ProjectileCollisionSystem {
 
         // Find damageable entities
         let damageables: Vec<(EntityId, Vector3, f32)> = world
-            .entities()
+            .enabled_entities()
             .filter_map(|e| {
                 if e.damageable.is_some() {
                     if let (Some(transform), Some(renderable)) = (&e.transform, &e.renderable) {
@@ -392,7 +732,7 @@ impl System for ProjectileCollisionSystem {
             .collect();
 
         // Check collisions
-        for (proj_id, proj_pos, damage, owner_id) in projectiles {
+        for (proj_id, proj_pos, damage, owner_id, crit) in projectiles {
             for (target_id, target_pos, target_radius) in &damageables {
                 // Don't hit owner
                 if let Some(owner) = owner_id {
@@ -407,40 +747,378 @@ impl System for ProjectileCollisionSystem {
                 let dist = (dx * dx + dy * dy + dz * dz).sqrt();
 
                 if dist < *target_radius {
-                    collisions.push((proj_id, *target_id, damage));
+                    collisions.push((proj_id, *target_id, damage, crit));
                 }
             }
         }
 
         // Apply collisions
-        for (proj_id, target_id, damage) in collisions {
-            // Mark projectile as hit and for removal
-            if let Some(proj) = world.get_entity_mut(proj_id) {
-                if let Some(projectile) = &mut proj.projectile {
-                    projectile.has_hit = true;
+        for (proj_id, target_id, damage, crit) in collisions {
+            // A projectile touching two targets in one step only hits the first
+            let Some(projectile) = Self::mark_hit(world, proj_id) else {
+                continue;
+            };
+
+            apply_damage(world, target_id, damage, crit);
+            if let Some(radius) = projectile.explosion_radius {
+                let impact = world.get_entity(proj_id).and_then(|e| e.transform).map(|t| t.position);
+                if let Some(impact) = impact {
+                    Self::explode(world, impact, radius, &projectile, Some(target_id));
                 }
-                proj.marked_for_death = Some(MarkedForDeath);
             }
+        }
+    }
+}
+
+impl ProjectileCollisionSystem {
+    /// Stop projectiles that have dropped below the terrain surface
+    ///
+    /// The projectile is snapped to the surface point, marked as hit, and explodes
+    /// there if it's explosive. Call this after movement each step, like
+    /// `TerrainCollisionSystem::apply_terrain_collision`.
+    pub fn apply_terrain_collision(&self, world: &mut World, terrain: &Terrain) {
+        let mut impacts = Vec::new();
 
-            // Damage target
-            if let Some(target) = world.get_entity_mut(target_id) {
-                if let Some(health) = &mut target.health {
-                    health.current = (health.current - damage).max(0.0);
+        for entity in world.enabled_entities_mut() {
+            if let (Some(transform), Some(projectile)) = (&mut entity.transform, &entity.projectile) {
+                if projectile.has_hit {
+                    continue;
+                }
 
-                    // Mark for death if health depleted
-                    if !health.is_alive() {
-                        target.marked_for_death = Some(MarkedForDeath);
+                let terrain_height = terrain.get_height_at(transform.position.x, transform.position.z);
+                if transform.position.y < terrain_height {
+                    transform.position.y = terrain_height;
+                    if let Some(velocity) = &mut entity.velocity {
+                        velocity.linear = Vector3::zero();
                     }
+                    impacts.push((entity.id, transform.position));
+                }
+            }
+        }
+
+        for (proj_id, impact) in impacts {
+            let Some(projectile) = Self::mark_hit(world, proj_id) else {
+                continue;
+            };
+            if let Some(radius) = projectile.explosion_radius {
+                Self::explode(world, impact, radius, &projectile, None);
+            }
+        }
+    }
+
+    /// Mark a projectile as spent and queue it for removal
+    /// Pooled projectiles are deactivated right away instead
+    /// Returns `None` if it had already hit something
+    fn mark_hit(world: &mut World, proj_id: EntityId) -> Option<Projectile> {
+        let proj = world.get_entity_mut(proj_id)?;
+        let projectile = proj.projectile.as_mut()?;
+        if projectile.has_hit {
+            return None;
+        }
+        projectile.has_hit = true;
+        let projectile = *projectile;
+        if world.get_component::<Pooled>(proj_id).is_some() {
+            ProjectilePool::deactivate(world, proj_id);
+        } else {
+            world.get_entity_mut(proj_id)?.marked_for_death = Some(MarkedForDeath);
+        }
+        Some(projectile)
+    }
+
+    /// Damage every damageable entity within `radius` of `center` with the projectile's damage
+    /// The owner and an already directly-hit entity are skipped
+    fn explode(world: &mut World, center: Vector3, radius: f32, projectile: &Projectile, skip: Option<EntityId>) {
+        let owner_id = projectile.owner_id;
+        let targets: Vec<EntityId> = world
+            .entities()
+            .filter(|e| e.damageable.is_some() && Some(e.id) != owner_id && Some(e.id) != skip)
+            .filter(|e| e.transform.is_some_and(|t| t.position.distance_to(center) <= radius))
+            .map(|e| e.id)
+            .collect();
+
+        for target_id in targets {
+            apply_damage(world, target_id, projectile.damage, projectile.crit);
+        }
+    }
+}
+
+/// Fixed set of reusable projectile entities
+///
+/// Firing and expiring projectiles every frame would otherwise insert into and
+/// remove from the world constantly. The pool spawns its entities once; spent
+/// projectiles are disabled (not rendered, not moved, not collided) by
+/// `ProjectileCollisionSystem` and `LifetimeSystem`, and `spawn_projectile`
+/// hands them out again. When every slot is in flight the oldest shot is recycled.
+///
+/// # Usage
+/// ```
+/// let mut pool = ProjectilePool::new(&mut world, 32, Renderable::sphere(0.1, Color::YELLOW));
+/// pool.spawn_projectile(&mut world, muzzle, forward * 40.0, Projectile::new(25.0), 3.0);
+/// ```
+pub struct ProjectilePool {
+    slots: Vec<EntityId>,
+    /// Shot number each slot was last fired with, for finding the oldest
+    fired: Vec<u64>,
+    shots: u64,
+    renderable: Renderable,
+}
+
+impl ProjectilePool {
+    pub fn new(world: &mut World, capacity: usize, renderable: Renderable) -> Self {
+        let mut pool = Self {
+            slots: Vec::with_capacity(capacity),
+            fired: vec![0; capacity],
+            shots: 0,
+            renderable,
+        };
+        for _ in 0..capacity {
+            let id = pool.spawn_inactive(world);
+            pool.slots.push(id);
+        }
+        pool
+    }
+
+    fn spawn_inactive(&self, world: &mut World) -> EntityId {
+        let id = world
+            .spawn()
+            .with_transform(super::components::Transform::default())
+            .with_velocity(Velocity::default())
+            .with_renderable(self.renderable.clone())
+            .with_collider(Collider::from_renderable(&self.renderable))
+            .build();
+        world.insert_component(id, Pooled);
+        Self::deactivate(world, id);
+        id
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Entity ids owned by the pool
+    pub fn entities(&self) -> &[EntityId] {
+        &self.slots
+    }
+
+    /// Number of projectiles currently in flight
+    pub fn active_count(&self, world: &World) -> usize {
+        self.slots
+            .iter()
+            .filter(|&&id| world.get_entity(id).is_some_and(|e| e.enabled))
+            .count()
+    }
+
+    /// Fire a projectile from an inactive slot, or recycle the oldest one in flight
+    /// Returns `None` only for an empty pool
+    pub fn spawn_projectile(
+        &mut self,
+        world: &mut World,
+        position: Vector3,
+        velocity: Vector3,
+        projectile: Projectile,
+        lifetime: f32,
+    ) -> Option<EntityId> {
+        let is_inactive = |id: &EntityId| world.get_entity(*id).is_none_or(|e| !e.enabled);
+        let slot = match self.slots.iter().position(is_inactive) {
+            Some(slot) => slot,
+            None => (0..self.slots.len()).min_by_key(|&slot| self.fired[slot])?,
+        };
+
+        // Something removed the entity outright; replace it
+        if world.get_entity(self.slots[slot]).is_none() {
+            self.slots[slot] = self.spawn_inactive(world);
+        }
+
+        self.shots += 1;
+        self.fired[slot] = self.shots;
+        let id = self.slots[slot];
+        let entity = world.get_entity_mut(id)?;
+        entity.enabled = true;
+        entity.transform = Some(super::components::Transform::new(position));
+        entity.velocity = Some(Velocity { linear: velocity, angular: Vector3::zero() });
+        entity.projectile = Some(projectile);
+        entity.lifetime = Some(Lifetime::new(lifetime));
+        entity.marked_for_death = None;
+        if let Some(renderable) = &mut entity.renderable {
+            renderable.visible = true;
+        }
+        Some(id)
+    }
+
+    /// Put a pooled projectile back into the pool
+    pub fn deactivate(world: &mut World, id: EntityId) {
+        if let Some(entity) = world.get_entity_mut(id) {
+            entity.enabled = false;
+            entity.lifetime = None;
+            entity.marked_for_death = None;
+            if let Some(velocity) = &mut entity.velocity {
+                *velocity = Velocity::default();
+            }
+            if let Some(renderable) = &mut entity.renderable {
+                renderable.visible = false;
+            }
+        }
+    }
+}
+
+/// Subtract damage from an entity's health, marking it for death when depleted
+/// Hits during the target's invincibility frames are ignored
+/// A landed crit tags the target with `CriticalHit` so its number shows as one
+fn apply_damage(world: &mut World, target_id: EntityId, damage: f32, crit: bool) {
+    let mut landed = false;
+    if let Some(target) = world.get_entity_mut(target_id)
+        && let Some(health) = &mut target.health
+    {
+        landed = health.take_damage(damage);
+
+        // Mark for death if health depleted
+        if !health.is_alive() {
+            target.marked_for_death = Some(MarkedForDeath);
+        }
+    }
+    if landed && crit {
+        world.insert_component(target_id, CriticalHit);
+    }
+}
+
+/// System that counts down telegraphed area attacks and lands them
+///
+/// This demonstrates:
+/// - Delayed effects driven by a timer component
+/// - Shape queries against entity positions
+/// - Drawing a warning decal on the ground
+pub struct TelegraphSystem;
+
+impl TelegraphSystem {
+    /// Fill color of the warning: fades in as the attack approaches and
+    /// flashes during the last quarter of the delay
+    pub fn warning_color(telegraph: &Telegraph) -> Color {
+        let progress = telegraph.progress();
+        let flashing = progress > 0.75 && (telegraph.remaining * 12.0).sin() > 0.0;
+        let alpha = if flashing { 0.9 } else { 0.25 + 0.5 * progress };
+        Color::RED.alpha(alpha)
+    }
+
+    /// Draw every pending telegraph as a ground decal
+    /// The filled area grows from the center until the attack lands
+    pub fn render(world: &World, d: &mut RaylibMode3D<RaylibDrawHandle>) {
+        for telegraph in world.entities().filter_map(|e| e.telegraph.as_ref()) {
+            let color = Self::warning_color(telegraph);
+            let growth = telegraph.progress();
+            // Lift the decal slightly so it doesn't z-fight with the ground
+            let center = telegraph.position + Vector3::new(0.0, 0.02, 0.0);
+
+            match telegraph.shape {
+                TelegraphShape::Circle { radius } => {
+                    d.draw_circle_3D(center, radius, Vector3::new(1.0, 0.0, 0.0), 90.0, Color::RED);
+                    d.draw_cylinder(center, radius * growth, radius * growth, 0.01, 32, color);
+                }
+                TelegraphShape::Rect { half_extents } => {
+                    let full = Vector3::new(half_extents.x * 2.0, 0.01, half_extents.y * 2.0);
+                    d.draw_cube_wires_v(center, full, Color::RED);
+                    d.draw_cube_v(center, Vector3::new(full.x * growth, 0.01, full.z * growth), color);
                 }
             }
         }
     }
 }
 
+impl System for TelegraphSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        let mut landed = Vec::new();
+
+        for entity in world.entities_mut() {
+            if let Some(telegraph) = &mut entity.telegraph {
+                telegraph.remaining -= delta_time;
+                if telegraph.is_ready() {
+                    landed.push((entity.id, *telegraph));
+                }
+            }
+        }
+
+        for (telegraph_id, telegraph) in landed {
+            let targets: Vec<EntityId> = world
+                .entities()
+                .filter(|e| e.id != telegraph_id && e.health.is_some())
+                .filter(|e| e.transform.is_some_and(|t| telegraph.shape.contains(telegraph.position, t.position)))
+                .map(|e| e.id)
+                .collect();
+
+            for target_id in targets {
+                apply_damage(world, target_id, telegraph.damage, false);
+            }
+
+            // Each telegraph lands once
+            if let Some(entity) = world.get_entity_mut(telegraph_id) {
+                entity.telegraph = None;
+                entity.marked_for_death = Some(MarkedForDeath);
+            }
+        }
+    }
+}
+
 // =============================================================================
 // UTILITY SYSTEMS
 // =============================================================================
 
+/// System that keeps child entities attached to their parents
+///
+/// This demonstrates:
+/// - Entity-to-entity relationships
+/// - Processing in dependency order (parents before children)
+/// - Safe handling of missing entities
+///
+/// Each child's position becomes its parent's position plus `local_offset`
+/// rotated by the parent's rotation. Children are processed by depth, so a
+/// grandchild sees its parent's position from this frame. Orphans (whose parent
+/// is gone or has no transform) and parent cycles are left alone.
+pub struct HierarchySystem;
+
+impl HierarchySystem {
+    /// Every child with its parent and offset, parents before their children
+    fn update_order(world: &World) -> Vec<(EntityId, EntityId, Vector3)> {
+        let limit = world.entities().count();
+        let mut children: Vec<(usize, EntityId, EntityId, Vector3)> = world
+            .entities()
+            .filter_map(|e| {
+                let child = e.child?;
+                let depth = Self::depth(world, e.id, limit)?;
+                Some((depth, e.id, child.parent_id, child.local_offset))
+            })
+            .collect();
+        children.sort_by_key(|&(depth, id, _, _)| (depth, id));
+        children.into_iter().map(|(_, id, parent_id, offset)| (id, parent_id, offset)).collect()
+    }
+
+    /// Number of ancestors above an entity, or `None` if the chain loops
+    fn depth(world: &World, id: EntityId, limit: usize) -> Option<usize> {
+        let mut depth = 0;
+        let mut current = id;
+        while let Some(child) = world.get_entity(current).and_then(|e| e.child) {
+            depth += 1;
+            if depth > limit {
+                return None;
+            }
+            current = child.parent_id;
+        }
+        Some(depth)
+    }
+}
+
+impl System for HierarchySystem {
+    fn update(&mut self, world: &mut World, _delta_time: f32) {
+        for (id, parent_id, offset) in Self::update_order(world) {
+            let Some(parent) = world.get_entity(parent_id).and_then(|e| e.transform) else {
+                continue;
+            };
+            let rotation = parent.rotation_quaternion();
+            if let Some(transform) = world.get_entity_mut(id).and_then(|e| e.transform.as_mut()) {
+                transform.position = parent.position + offset.rotate_by(rotation);
+            }
+        }
+    }
+}
+
 /// Example of a state machine update system
 ///
 /// This demonstrates:
@@ -473,3 +1151,451 @@ impl System for StateMachineSystem {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::components::{Health, Renderable, Transform, Velocity};
+    use crate::ecs::systems::MovementSystem;
+    use crate::rpg::TimedStatusEffect;
+    use crate::terrain::TerrainConfig;
+
+    fn spawn_shooter_with_projectile(world: &mut World) -> (EntityId, EntityId) {
+        let shooter = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_renderable(Renderable::sphere(1.0, Color::BLUE))
+            .with_health(Health::new(100.0))
+            .build();
+        world.get_entity_mut(shooter).unwrap().damageable = Some(Damageable);
+
+        // Ownerless projectile spawned inside the shooter
+        let projectile = world.spawn().with_transform(Transform::new(Vector3::zero())).build();
+        world.get_entity_mut(projectile).unwrap().projectile = Some(Projectile::new(25.0));
+
+        (shooter, projectile)
+    }
+
+    #[test]
+    fn test_aggressive_enemy_approaches_and_attacks() {
+        let mut world = World::new();
+        let player = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_health(Health::new(100.0))
+            .as_player()
+            .build();
+        // Goblins are aggressive; 6 units is 96 rpg units, inside aggro but out of reach
+        let mut ai = EnemyAISystem::new(EnemyDatabase::with_starter_enemies(), 3);
+        let goblin = ai.spawn_enemy(&mut world, 2, Vector3::new(6.0, 0.0, 0.0)).unwrap();
+        let mut movement = MovementSystem;
+        let mut cooldowns = CooldownSystem;
+
+        let distance = |world: &World| world.get_entity(goblin).unwrap().transform.unwrap().position.length();
+        let player_health = |world: &World| world.get_entity(player).unwrap().health.unwrap().current;
+
+        ai.update(&mut world, 0.1);
+        assert!(world.get_entity(goblin).unwrap().velocity.unwrap().linear.x < 0.0);
+        movement.update(&mut world, 0.1);
+        assert!(distance(&world) < 6.0);
+        assert_eq!(player_health(&world), 100.0);
+
+        for _ in 0..30 {
+            cooldowns.update(&mut world, 0.1);
+            ai.update(&mut world, 0.1);
+            movement.update(&mut world, 0.1);
+        }
+        assert!(distance(&world) <= EnemyAISystem::ATTACK_RANGE / ai.world_scale);
+        let damage = world.get_entity(goblin).unwrap().attack_ability.unwrap().damage;
+        let health = player_health(&world);
+        assert!(health < 100.0);
+
+        // The attack cooldown holds back the next hit
+        ai.update(&mut world, 0.1);
+        assert_eq!(player_health(&world), health);
+        assert_eq!((100.0 - health) % damage, 0.0);
+    }
+
+    #[test]
+    fn test_projectile_pool_recycles_oldest() {
+        let mut world = World::new();
+        let mut pool = ProjectilePool::new(&mut world, 3, Renderable::sphere(0.1, Color::YELLOW));
+        assert_eq!(world.entities().count(), 3);
+        assert_eq!(pool.active_count(&world), 0);
+
+        let shots: Vec<EntityId> = (0..4)
+            .map(|i| {
+                let position = Vector3::new(i as f32, 0.0, 0.0);
+                pool.spawn_projectile(&mut world, position, Vector3::zero(), Projectile::new(5.0), 10.0).unwrap()
+            })
+            .collect();
+        // The fourth shot reused the first one's entity
+        assert_eq!(shots[3], shots[0]);
+        assert_eq!(world.entities().count(), 3);
+        assert_eq!(pool.active_count(&world), 3);
+        assert_eq!(world.get_entity(shots[0]).unwrap().transform.unwrap().position.x, 3.0);
+
+        // Expiring deactivates instead of removing, freeing the slots
+        LifetimeSystem::new().update(&mut world, 11.0);
+        assert_eq!(world.entities().count(), 3);
+        assert_eq!(pool.active_count(&world), 0);
+        assert!(!world.get_entity(shots[1]).unwrap().renderable.as_ref().unwrap().visible);
+    }
+
+    #[test]
+    fn test_children_follow_moving_parent() {
+        let mut world = World::new();
+        let parent = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_velocity(Velocity { linear: Vector3::new(2.0, 0.0, 0.0), angular: Vector3::zero() })
+            .build();
+        // Spawned before its own parent, so id order alone would update it too early
+        let grandchild = world.spawn().with_transform(Transform::default()).build();
+        let child = world.spawn().with_transform(Transform::default()).build();
+        let orphan = world.spawn().with_transform(Transform::new(Vector3::new(9.0, 9.0, 9.0))).build();
+        world.get_entity_mut(child).unwrap().child = Some(Child::with_offset(parent, Vector3::new(0.0, 1.0, 0.0)));
+        world.get_entity_mut(grandchild).unwrap().child = Some(Child::with_offset(child, Vector3::new(1.0, 0.0, 0.0)));
+        world.get_entity_mut(orphan).unwrap().child = Some(Child::new(999));
+        let mut movement = MovementSystem;
+        let mut hierarchy = HierarchySystem;
+
+        for _ in 0..10 {
+            movement.update(&mut world, 0.1);
+            hierarchy.update(&mut world, 0.1);
+        }
+        let position = |world: &World, id| world.get_entity(id).unwrap().transform.unwrap().position;
+        assert!((position(&world, parent) - Vector3::new(2.0, 0.0, 0.0)).length() < 1e-4);
+        assert!((position(&world, child) - Vector3::new(2.0, 1.0, 0.0)).length() < 1e-4);
+        assert!((position(&world, grandchild) - Vector3::new(3.0, 1.0, 0.0)).length() < 1e-4);
+        assert_eq!(position(&world, orphan), Vector3::new(9.0, 9.0, 9.0));
+
+        // Turning the parent a quarter turn about Y swings the offset around
+        world.get_entity_mut(child).unwrap().transform.as_mut().unwrap().rotation.y = std::f32::consts::FRAC_PI_2;
+        hierarchy.update(&mut world, 0.1);
+        assert!((position(&world, grandchild) - Vector3::new(2.0, 1.0, -1.0)).length() < 1e-4);
+    }
+
+    #[test]
+    fn test_child_offset_uses_renderer_rotation_order() {
+        let mut world = World::new();
+        let parent_transform = Transform::new(Vector3::new(1.0, 2.0, 3.0)).with_rotation(Vector3::new(0.7, 0.0, 0.9));
+        let parent = world.spawn().with_transform(parent_transform).build();
+        let child = world.spawn().with_transform(Transform::default()).build();
+        world.get_entity_mut(child).unwrap().child = Some(Child::with_offset(parent, Vector3::new(0.0, 1.0, 0.0)));
+
+        HierarchySystem.update(&mut world, 0.1);
+
+        // A unit offset along local up lands where the renderer draws the parent's up axis
+        let expected = parent_transform.position + parent_transform.up();
+        let position = world.get_entity(child).unwrap().transform.unwrap().position;
+        assert!((position - expected).length() < 1e-4, "{:?} vs {:?}", position, expected);
+    }
+
+    #[test]
+    fn test_scale_over_time_stops_at_max() {
+        let mut world = World::new();
+        let pickup = world
+            .spawn()
+            .with_transform(Transform::default())
+            .with_scale_over_time(ScaleOverTime::uniform(2.0, 0.5, 2.0))
+            .build();
+        let mut system = ScaleOverTimeSystem;
+        let scale = |world: &World| world.get_entity(pickup).unwrap().transform.unwrap().scale;
+
+        system.update(&mut world, 0.25);
+        assert_eq!(scale(&world), Vector3::new(1.5, 1.5, 1.5));
+        for _ in 0..10 {
+            system.update(&mut world, 0.25);
+        }
+        assert_eq!(scale(&world), Vector3::new(2.0, 2.0, 2.0));
+
+        // Shrinking stops at the minimum
+        world.get_entity_mut(pickup).unwrap().scale_over_time.as_mut().unwrap().rate.x = -4.0;
+        system.update(&mut world, 1.0);
+        assert_eq!(scale(&world), Vector3::new(0.5, 2.0, 2.0));
+    }
+
+    #[test]
+    fn test_scale_over_time_with_swapped_limits_does_not_panic() {
+        let mut world = World::new();
+        let pickup = world
+            .spawn()
+            .with_transform(Transform::default())
+            .with_scale_over_time(ScaleOverTime::uniform(2.0, 3.0, 1.5))
+            .build();
+
+        ScaleOverTimeSystem.update(&mut world, 1.0);
+        assert_eq!(world.get_entity(pickup).unwrap().transform.unwrap().scale, Vector3::new(3.0, 3.0, 3.0));
+    }
+
+    #[test]
+    fn test_projectile_spawn_immunity() {
+        let mut world = World::new();
+        let (shooter, projectile) = spawn_shooter_with_projectile(&mut world);
+        let mut system = ProjectileCollisionSystem;
+
+        system.update(&mut world, 0.05);
+        assert_eq!(world.get_entity(shooter).unwrap().health.unwrap().current, 100.0);
+        assert!(!world.get_entity(projectile).unwrap().projectile.unwrap().has_hit);
+    }
+
+    #[test]
+    fn test_projectile_hits_after_immunity() {
+        let mut world = World::new();
+        let (shooter, projectile) = spawn_shooter_with_projectile(&mut world);
+        let mut system = ProjectileCollisionSystem;
+
+        system.update(&mut world, 0.05);
+        system.update(&mut world, Projectile::DEFAULT_SPAWN_IMMUNITY);
+        assert_eq!(world.get_entity(shooter).unwrap().health.unwrap().current, 75.0);
+        assert!(world.get_entity(projectile).unwrap().projectile.unwrap().has_hit);
+    }
+
+    #[test]
+    fn test_critical_projectile_tags_its_target() {
+        let mut world = World::new();
+        let (shooter, projectile) = spawn_shooter_with_projectile(&mut world);
+        world.get_entity_mut(projectile).unwrap().projectile = Some(Projectile::new(25.0).with_crit(true));
+        let mut system = ProjectileCollisionSystem;
+
+        system.update(&mut world, Projectile::DEFAULT_SPAWN_IMMUNITY);
+        assert!(world.get_component::<CriticalHit>(shooter).is_some());
+    }
+
+    #[test]
+    fn test_killed_enemy_leaves_corpse_before_removal() {
+        let mut world = World::new();
+        let enemy = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_renderable(Renderable::cube(Vector3::one(), Color::RED))
+            .with_health(Health::new(10.0))
+            .as_enemy()
+            .build();
+        world.get_entity_mut(enemy).unwrap().health.as_mut().unwrap().current = 0.0;
+        world.get_entity_mut(enemy).unwrap().marked_for_death = Some(MarkedForDeath);
+
+        let mut system = DeathSystem::new().with_style(DeathStyle::FadeOut, 1.0);
+        system.update(&mut world, 0.016);
+
+        assert_eq!(system.started_dying, vec![enemy]);
+        let corpse = world.get_entity(enemy).expect("corpse should not be removed immediately");
+        assert!(corpse.death_state.is_some());
+        assert!(corpse.marked_for_death.is_none());
+
+        system.update(&mut world, 0.5);
+        assert!(world.get_entity(enemy).is_some());
+        assert!(system.started_dying.is_empty());
+
+        system.update(&mut world, 0.6);
+        assert!(world.get_entity(enemy).is_none());
+    }
+
+    #[test]
+    fn test_marked_non_enemy_removed_immediately() {
+        let mut world = World::new();
+        let bullet = world.spawn().with_transform(Transform::new(Vector3::zero())).build();
+        world.get_entity_mut(bullet).unwrap().marked_for_death = Some(MarkedForDeath);
+
+        DeathSystem::new().update(&mut world, 0.016);
+        assert!(world.get_entity(bullet).is_none());
+    }
+
+    fn spawn_with_status(world: &mut World, effect: StatusEffect) -> EntityId {
+        let id = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_renderable(Renderable::cube(Vector3::one(), Color::GRAY))
+            .with_velocity(Velocity { linear: Vector3::new(3.0, 0.0, 1.0), angular: Vector3::zero() })
+            .build();
+        let mut status = StatusEffects::new();
+        status.add(TimedStatusEffect::new(effect, 2.0, 1));
+        world.get_entity_mut(id).unwrap().status_effects = Some(status);
+        id
+    }
+
+    #[test]
+    fn test_poisoned_entity_gets_poison_tint() {
+        let mut world = World::new();
+        let id = spawn_with_status(&mut world, StatusEffect::Poisoned);
+
+        StatusVisualSystem.update(&mut world, 0.1);
+        let tint = world.get_entity(id).unwrap().tint.expect("poisoned entity should be tinted");
+        assert_eq!(tint.color, StatusEffects::tint_for(StatusEffect::Poisoned).unwrap());
+
+        // A hit flash takes over while it lasts
+        world.get_entity_mut(id).unwrap().hit_flash = Some(HitFlash::new(0.2));
+        StatusVisualSystem.update(&mut world, 0.1);
+        assert_eq!(world.get_entity(id).unwrap().tint.unwrap().color, Color::WHITE);
+        StatusVisualSystem.update(&mut world, 0.2);
+        assert_eq!(world.get_entity(id).unwrap().tint.unwrap().color, tint.color);
+
+        // Tint clears when the effect expires
+        StatusVisualSystem.update(&mut world, 2.0);
+        assert!(world.get_entity(id).unwrap().tint.is_none());
+    }
+
+    #[test]
+    fn test_flash_only_entity_loses_tint_when_flash_expires() {
+        let mut world = World::new();
+        let id = world.spawn().with_transform(Transform::new(Vector3::zero())).build();
+        world.get_entity_mut(id).unwrap().hit_flash = Some(HitFlash::new(0.2));
+
+        StatusVisualSystem.update(&mut world, 0.1);
+        assert_eq!(world.get_entity(id).unwrap().tint.unwrap().color, Color::WHITE);
+
+        StatusVisualSystem.update(&mut world, 0.2);
+        let entity = world.get_entity(id).unwrap();
+        assert!(entity.hit_flash.is_none());
+        assert!(entity.tint.is_none());
+    }
+
+    #[test]
+    fn test_frozen_zeroes_velocity_while_active() {
+        let mut world = World::new();
+        let id = spawn_with_status(&mut world, StatusEffect::Frozen);
+
+        StatusVisualSystem.update(&mut world, 0.5);
+        assert_eq!(world.get_entity(id).unwrap().velocity.unwrap().linear, Vector3::zero());
+
+        // Once the effect has worn off velocity is left alone
+        StatusVisualSystem.update(&mut world, 2.0);
+        world.get_entity_mut(id).unwrap().velocity.as_mut().unwrap().linear = Vector3::new(1.0, 0.0, 0.0);
+        StatusVisualSystem.update(&mut world, 0.1);
+        assert_eq!(world.get_entity(id).unwrap().velocity.unwrap().linear, Vector3::new(1.0, 0.0, 0.0));
+    }
+
+    fn flat_terrain(height: f32) -> Terrain {
+        let mut terrain = Terrain::generate(TerrainConfig { width: 16, depth: 16, ..Default::default() });
+        for column in &mut terrain.heightmap {
+            column.fill(height);
+        }
+        terrain
+    }
+
+    fn spawn_falling_projectile(world: &mut World, projectile: Projectile) -> EntityId {
+        let id = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::new(1.0, 5.0, 1.0)))
+            .with_velocity(Velocity { linear: Vector3::new(0.0, -20.0, 0.0), angular: Vector3::zero() })
+            .build();
+        world.get_entity_mut(id).unwrap().projectile = Some(projectile);
+        id
+    }
+
+    #[test]
+    fn test_downward_projectile_stops_at_terrain() {
+        let mut world = World::new();
+        let terrain = flat_terrain(2.0);
+        let projectile = spawn_falling_projectile(&mut world, Projectile::new(10.0));
+        let system = ProjectileCollisionSystem;
+
+        // Above the surface it keeps flying
+        MovementSystem.update(&mut world, 0.1);
+        system.apply_terrain_collision(&mut world, &terrain);
+        assert!(!world.get_entity(projectile).unwrap().projectile.unwrap().has_hit);
+
+        // 20 units/s for another 0.1s puts it at y = 1, below the surface
+        MovementSystem.update(&mut world, 0.1);
+        system.apply_terrain_collision(&mut world, &terrain);
+
+        let entity = world.get_entity(projectile).unwrap();
+        assert!((entity.transform.unwrap().position.y - 2.0).abs() < 1e-4);
+        assert_eq!(entity.velocity.unwrap().linear, Vector3::zero());
+        assert!(entity.projectile.unwrap().has_hit);
+        assert!(entity.marked_for_death.is_some());
+    }
+
+    #[test]
+    fn test_explosive_projectile_damages_around_impact_point() {
+        let mut world = World::new();
+        let terrain = flat_terrain(0.0);
+        let mut spawn_target = |position: Vector3| {
+            let id = world
+                .spawn()
+                .with_transform(Transform::new(position))
+                .with_health(Health::new(100.0))
+                .build();
+            world.get_entity_mut(id).unwrap().damageable = Some(Damageable);
+            id
+        };
+        let near = spawn_target(Vector3::new(2.0, 0.5, 1.0));
+        let far = spawn_target(Vector3::new(10.0, 0.5, 1.0));
+
+        let projectile = spawn_falling_projectile(&mut world, Projectile::new(30.0).with_explosion(3.0));
+        world.get_entity_mut(projectile).unwrap().transform.as_mut().unwrap().position.y = -0.5;
+        ProjectileCollisionSystem.apply_terrain_collision(&mut world, &terrain);
+
+        assert_eq!(world.get_entity(projectile).unwrap().transform.unwrap().position, Vector3::new(1.0, 0.0, 1.0));
+        assert_eq!(world.get_entity(near).unwrap().health.unwrap().current, 70.0);
+        assert_eq!(world.get_entity(far).unwrap().health.unwrap().current, 100.0);
+    }
+
+    fn spawn_target(world: &mut World, position: Vector3) -> EntityId {
+        world
+            .spawn()
+            .with_transform(Transform::new(position))
+            .with_health(Health::new(100.0))
+            .build()
+    }
+
+    #[test]
+    fn test_telegraph_damages_only_after_delay_and_inside_shape() {
+        let mut world = World::new();
+        let inside = spawn_target(&mut world, Vector3::new(1.0, 0.0, 1.5));
+        let outside = spawn_target(&mut world, Vector3::new(4.0, 0.0, 0.0));
+        let slam = world.spawn().build();
+        world.get_entity_mut(slam).unwrap().telegraph =
+            Some(Telegraph::new(TelegraphShape::Circle { radius: 2.0 }, Vector3::zero(), 1.0, 30.0));
+
+        let mut system = TelegraphSystem;
+        system.update(&mut world, 0.6);
+        assert_eq!(world.get_entity(inside).unwrap().health.unwrap().current, 100.0);
+        assert!((world.get_entity(slam).unwrap().telegraph.unwrap().progress() - 0.6).abs() < 1e-4);
+
+        system.update(&mut world, 0.6);
+        assert_eq!(world.get_entity(inside).unwrap().health.unwrap().current, 70.0);
+        assert_eq!(world.get_entity(outside).unwrap().health.unwrap().current, 100.0);
+
+        // Lands only once
+        system.update(&mut world, 1.0);
+        assert_eq!(world.get_entity(inside).unwrap().health.unwrap().current, 70.0);
+    }
+
+    #[test]
+    fn test_rect_telegraph_shape() {
+        let shape = TelegraphShape::Rect { half_extents: Vector2::new(3.0, 1.0) };
+        let center = Vector3::new(10.0, 0.0, 10.0);
+
+        assert!(shape.contains(center, Vector3::new(12.5, 5.0, 10.5)));
+        assert!(!shape.contains(center, Vector3::new(10.0, 0.0, 11.5)));
+    }
+
+    #[test]
+    fn test_invulnerability_frames_block_repeat_hits() {
+        let mut world = World::new();
+        let target = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_health(Health::new(100.0).with_invulnerability(0.5))
+            .build();
+        let health = |world: &World| world.get_entity(target).unwrap().health.unwrap();
+
+        apply_damage(&mut world, target, 10.0, false);
+        assert_eq!(health(&world).current, 90.0);
+        assert!(health(&world).is_invulnerable());
+
+        // Still inside the window
+        CooldownSystem.update(&mut world, 0.3);
+        apply_damage(&mut world, target, 10.0, false);
+        assert_eq!(health(&world).current, 90.0);
+
+        // Window over, the next hit lands and starts a new one
+        CooldownSystem.update(&mut world, 0.3);
+        assert!(!health(&world).is_invulnerable());
+        apply_damage(&mut world, target, 10.0, false);
+        assert_eq!(health(&world).current, 80.0);
+        assert!(health(&world).is_invulnerable());
+    }
+}
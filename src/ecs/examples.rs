@@ -4,7 +4,9 @@
 //! Components should be pure data with no behavior - all logic belongs in systems.
 
 use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
 use super::entity::EntityId;
+use crate::rpg::{apply_status_effect, StatusEffect, TimedStatusEffect};
 
 // =============================================================================
 // TAG COMPONENTS
@@ -21,17 +23,31 @@ use super::entity::EntityId;
 ///     .with_collectible(Collectible)
 ///     .build();
 /// ```
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Collectible;
 
 /// Marks an entity as an obstacle that blocks movement
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Obstacle;
 
 /// Marks an entity as damageable by the player
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Damageable;
 
+/// Marks an entity owned by a pool (see `ProjectilePool`)
+///
+/// Stored with `World::insert_component`. Systems deactivate pooled entities
+/// instead of removing them so the pool can hand them out again.
+#[derive(Debug, Clone, Copy)]
+pub struct Pooled;
+
+/// Marks an entity whose latest damage was a critical hit
+///
+/// Stored with `World::insert_component` by the combat systems;
+/// `CombatTextSystem` takes it off when it shows that hit's number.
+#[derive(Debug, Clone, Copy)]
+pub struct CriticalHit;
+
 // =============================================================================
 // LIFETIME AND TIMING COMPONENTS
 // =============================================================================
@@ -52,7 +68,7 @@ pub struct Damageable;
 ///     .with_lifetime(Lifetime::new(2.0))
 ///     .build();
 /// ```
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Lifetime {
     /// Time remaining in seconds
     pub remaining: f32,
@@ -96,7 +112,7 @@ impl Lifetime {
 ///     }
 /// }
 /// ```
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Cooldown {
     /// Time remaining until ready
     pub remaining: f32,
@@ -155,7 +171,7 @@ impl Cooldown {
 ///     .with_gravity(Gravity::new(9.8))
 ///     .build();
 /// ```
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Gravity {
     /// Gravitational acceleration (units per second squared)
     pub force: f32,
@@ -190,7 +206,7 @@ impl Gravity {
 }
 
 /// Makes an entity bounce when colliding with surfaces
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Bouncy {
     /// Restitution coefficient (0.0 = no bounce, 1.0 = perfect bounce)
     pub restitution: f32,
@@ -206,7 +222,7 @@ impl Bouncy {
 }
 
 /// Applies drag/friction to velocity over time
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Drag {
     /// Linear drag coefficient (higher = more drag)
     pub linear: f32,
@@ -245,7 +261,7 @@ impl Drag {
 ///     .with_lifetime(Lifetime::new(3.0))
 ///     .build();
 /// ```
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Projectile {
     /// Damage dealt on hit
     pub damage: f32,
@@ -253,30 +269,66 @@ pub struct Projectile {
     pub owner_id: Option<EntityId>,
     /// Whether this projectile has already hit something
     pub has_hit: bool,
+    /// Seconds after spawning during which an ownerless projectile can't hit anything
+    /// (keeps it from damaging the shooter it spawned inside)
+    pub spawn_immunity: f32,
+    /// Seconds since the projectile was fired
+    pub age: f32,
+    /// Explodes on impact, damaging everything within this radius
+    pub explosion_radius: Option<f32>,
+    /// The shot rolled a critical hit when it was fired
+    pub crit: bool,
 }
 
 impl Projectile {
+    /// Default spawn immunity for projectiles without an owner
+    pub const DEFAULT_SPAWN_IMMUNITY: f32 = 0.1;
+
     /// Create a new projectile with given damage
     pub fn new(damage: f32) -> Self {
         Self {
             damage,
             owner_id: None,
             has_hit: false,
+            spawn_immunity: Self::DEFAULT_SPAWN_IMMUNITY,
+            age: 0.0,
+            explosion_radius: None,
+            crit: false,
         }
     }
 
     /// Create a projectile with an owner
     pub fn with_owner(damage: f32, owner_id: EntityId) -> Self {
         Self {
-            damage,
             owner_id: Some(owner_id),
-            has_hit: false,
+            ..Self::new(damage)
         }
     }
+
+    pub fn with_spawn_immunity(mut self, seconds: f32) -> Self {
+        self.spawn_immunity = seconds;
+        self
+    }
+
+    pub fn with_explosion(mut self, radius: f32) -> Self {
+        self.explosion_radius = Some(radius);
+        self
+    }
+
+    pub fn with_crit(mut self, crit: bool) -> Self {
+        self.crit = crit;
+        self
+    }
+
+    /// Whether the projectile is still inside its spawn immunity window
+    /// Owned projectiles never need it since they always skip their owner
+    pub fn is_spawn_immune(&self) -> bool {
+        self.owner_id.is_none() && self.age < self.spawn_immunity
+    }
 }
 
 /// Component for entities that can attack
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct AttackAbility {
     /// Damage per attack
     pub damage: f32,
@@ -307,6 +359,72 @@ impl AttackAbility {
     }
 }
 
+/// Ground area covered by a telegraphed attack, measured on the XZ plane
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TelegraphShape {
+    Circle { radius: f32 },
+    /// Axis-aligned rectangle; `half_extents` is (x, z)
+    Rect { half_extents: Vector2 },
+}
+
+impl TelegraphShape {
+    /// Whether a point lies inside the shape centered on `center` (height is ignored)
+    pub fn contains(&self, center: Vector3, point: Vector3) -> bool {
+        let dx = point.x - center.x;
+        let dz = point.z - center.z;
+        match self {
+            TelegraphShape::Circle { radius } => dx * dx + dz * dz <= radius * radius,
+            TelegraphShape::Rect { half_extents } => dx.abs() <= half_extents.x && dz.abs() <= half_extents.y,
+        }
+    }
+}
+
+/// Warning zone for a delayed area attack (boss ground slams and the like)
+///
+/// The zone is shown for `delay` seconds, then everything with health inside
+/// `shape` takes `damage` once.
+///
+/// # Example
+/// ```
+/// let slam = world.spawn().build();
+/// world.get_entity_mut(slam).unwrap().telegraph =
+///     Some(Telegraph::new(TelegraphShape::Circle { radius: 4.0 }, slam_point, 1.5, 40.0));
+/// ```
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Telegraph {
+    pub shape: TelegraphShape,
+    pub position: Vector3,
+    /// Total warning time in seconds
+    pub delay: f32,
+    /// Seconds until the attack lands
+    pub remaining: f32,
+    pub damage: f32,
+}
+
+impl Telegraph {
+    pub fn new(shape: TelegraphShape, position: Vector3, delay: f32, damage: f32) -> Self {
+        Self {
+            shape,
+            position,
+            delay,
+            remaining: delay,
+            damage,
+        }
+    }
+
+    /// How far through the warning we are (0.0 = just appeared, 1.0 = landing)
+    pub fn progress(&self) -> f32 {
+        if self.delay <= 0.0 {
+            return 1.0;
+        }
+        (1.0 - self.remaining / self.delay).clamp(0.0, 1.0)
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.remaining <= 0.0
+    }
+}
+
 // =============================================================================
 // AI AND BEHAVIOR COMPONENTS
 // =============================================================================
@@ -355,7 +473,7 @@ impl<T> StateMachine<T> {
 }
 
 /// AI that follows a target entity
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct FollowTarget {
     /// Entity to follow
     pub target_id: EntityId,
@@ -377,7 +495,7 @@ impl FollowTarget {
 }
 
 /// AI that patrols between waypoints
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PatrolPath {
     /// Waypoints to patrol
     pub waypoints: Vec<Vector3>,
@@ -432,7 +550,7 @@ impl PatrolPath {
 /// Makes an entity fade out over time (requires Lifetime component)
 ///
 /// The alpha value will decrease from 1.0 to 0.0 over the entity's lifetime.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct FadeOut {
     /// Initial alpha value
     pub initial_alpha: f32,
@@ -450,8 +568,52 @@ impl Default for FadeOut {
     }
 }
 
+/// How a dying entity leaves the world
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeathStyle {
+    /// Fade the renderable to transparent
+    FadeOut,
+    /// Sink below the ground
+    SinkIntoGround,
+    /// Topple over sideways
+    Ragdoll,
+}
+
+/// Death animation in progress - the entity is removed once it finishes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DeathState {
+    /// Seconds since death
+    pub timer: f32,
+    /// Seconds until the corpse is removed
+    pub duration: f32,
+    pub style: DeathStyle,
+}
+
+impl DeathState {
+    pub fn new(style: DeathStyle, duration: f32) -> Self {
+        Self {
+            timer: 0.0,
+            duration,
+            style,
+        }
+    }
+
+    /// Animation progress from 0.0 (just died) to 1.0 (ready for removal)
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            1.0
+        } else {
+            (self.timer / self.duration).clamp(0.0, 1.0)
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.timer >= self.duration
+    }
+}
+
 /// Makes an entity scale up or down over time
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct ScaleOverTime {
     /// Scale change per second
     pub rate: Vector3,
@@ -461,8 +623,30 @@ pub struct ScaleOverTime {
     pub max_scale: Vector3,
 }
 
+impl ScaleOverTime {
+    /// Scale every axis at the same rate between two uniform limits
+    pub fn uniform(rate: f32, min_scale: f32, max_scale: f32) -> Self {
+        Self {
+            rate: Vector3::new(rate, rate, rate),
+            min_scale: Vector3::new(min_scale, min_scale, min_scale),
+            max_scale: Vector3::new(max_scale, max_scale, max_scale),
+        }
+    }
+
+    /// Keep `scale` within the limits on each axis
+    /// Limits given the wrong way round are swapped rather than panicking
+    pub fn clamp(&self, scale: Vector3) -> Vector3 {
+        let axis = |value: f32, a: f32, b: f32| value.clamp(a.min(b), a.max(b));
+        Vector3::new(
+            axis(scale.x, self.min_scale.x, self.max_scale.x),
+            axis(scale.y, self.min_scale.y, self.max_scale.y),
+            axis(scale.z, self.min_scale.z, self.max_scale.z),
+        )
+    }
+}
+
 /// Rotates an entity continuously
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct AutoRotate {
     /// Rotation speed in radians per second for each axis
     pub speed: Vector3,
@@ -484,12 +668,90 @@ impl AutoRotate {
     }
 }
 
+/// Brief color flash when an entity is hit; overrides status effect tints
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct HitFlash {
+    /// Seconds left on the flash
+    pub remaining: f32,
+    pub color: Color,
+}
+
+impl HitFlash {
+    pub fn new(duration: f32) -> Self {
+        Self {
+            remaining: duration,
+            color: Color::WHITE,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.remaining > 0.0
+    }
+}
+
+/// Timed status effects (poison, freeze, ...) applied to a 3D entity
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatusEffects {
+    pub effects: Vec<TimedStatusEffect>,
+}
+
+impl StatusEffects {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, effect: TimedStatusEffect) {
+        apply_status_effect(&mut self.effects, effect);
+    }
+
+    pub fn has(&self, effect: StatusEffect) -> bool {
+        self.effects.iter().any(|e| e.effect == effect)
+    }
+
+    /// The effect that should drive visuals when several are active
+    pub fn dominant(&self) -> Option<StatusEffect> {
+        self.effects
+            .iter()
+            .map(|e| e.effect)
+            .max_by_key(|effect| Self::visual_priority(*effect))
+    }
+
+    /// Higher values win when choosing the dominant effect
+    pub fn visual_priority(effect: StatusEffect) -> u8 {
+        match effect {
+            StatusEffect::Frozen => 9,
+            StatusEffect::Stunned => 8,
+            StatusEffect::Burning => 7,
+            StatusEffect::Poisoned => 6,
+            StatusEffect::Cursed => 5,
+            StatusEffect::Slowed => 4,
+            StatusEffect::Invulnerable => 3,
+            StatusEffect::Hasted => 2,
+            StatusEffect::Blessed => 1,
+            StatusEffect::Regenerating => 0,
+        }
+    }
+
+    /// Tint shown on an entity with this effect (alpha is the blend strength)
+    pub fn tint_for(effect: StatusEffect) -> Option<Color> {
+        match effect {
+            StatusEffect::Poisoned => Some(Color::new(60, 200, 60, 150)),
+            StatusEffect::Burning => Some(Color::new(255, 110, 20, 150)),
+            StatusEffect::Frozen => Some(Color::new(90, 160, 255, 170)),
+            StatusEffect::Stunned => Some(Color::new(250, 230, 80, 120)),
+            StatusEffect::Cursed => Some(Color::new(130, 50, 160, 140)),
+            StatusEffect::Slowed => Some(Color::new(120, 120, 160, 100)),
+            _ => None,
+        }
+    }
+}
+
 // =============================================================================
 // UTILITY COMPONENTS
 // =============================================================================
 
 /// Stores a color that can be used for tinting or effects
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Tint {
     pub color: Color,
 }
@@ -498,16 +760,29 @@ impl Tint {
     pub fn new(color: Color) -> Self {
         Self { color }
     }
+
+    /// Blend a base color towards the tint, using the tint's alpha as strength
+    /// The base color keeps its own alpha
+    pub fn apply(&self, base: Color) -> Color {
+        let strength = self.color.a as f32 / 255.0;
+        let mix = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * strength).round() as u8;
+        Color::new(
+            mix(base.r, self.color.r),
+            mix(base.g, self.color.g),
+            mix(base.b, self.color.b),
+            base.a,
+        )
+    }
 }
 
 /// Marks an entity to be removed at the end of the frame
 ///
 /// Useful for deferred deletion when you can't remove during iteration
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct MarkedForDeath;
 
 /// Parent-child relationship for hierarchical transforms
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Parent {
     pub children: Vec<EntityId>,
 }
@@ -533,7 +808,7 @@ impl Default for Parent {
 }
 
 /// Child component storing parent reference
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Child {
     pub parent_id: EntityId,
     /// Local transform relative to parent
@@ -0,0 +1,94 @@
+//! View frustum for culling
+//!
+//! `Frustum` is built from a perspective `Camera3D` and the clip distances passed
+//! to `RenderSystem::apply_clip_planes`. Each plane's normal points into the frustum, so a point
+//! is inside when it is in front of all six planes.
+
+use raylib::prelude::{Camera3D, Vector3};
+
+/// Plane through `point` with an inward-facing unit `normal`
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Plane {
+    normal: Vector3,
+    point: Vector3,
+}
+
+impl Plane {
+    fn new(normal: Vector3, point: Vector3) -> Self {
+        Self { normal: normal.normalized(), point }
+    }
+
+    /// Signed distance from the plane, positive on the inside
+    fn distance(&self, position: Vector3) -> f32 {
+        self.normal.dot(position - self.point)
+    }
+}
+
+/// The six planes bounding what a camera can see
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    planes: [Plane; 6],
+}
+
+impl Frustum {
+    /// Frustum of a perspective camera (`fovy` in degrees, vertical)
+    pub fn from_camera(camera: &Camera3D, aspect: f32, near: f32, far: f32) -> Self {
+        let position = camera.position;
+        let forward = (camera.target - camera.position).normalized();
+        let right = forward.cross(camera.up).normalized();
+        let up = right.cross(forward);
+
+        let half_v = (camera.fovy.to_radians() / 2.0).tan();
+        let half_h = half_v * aspect;
+
+        Self {
+            planes: [
+                Plane::new(forward, position + forward * near),
+                Plane::new(-forward, position + forward * far),
+                Plane::new(forward * half_h + right, position),
+                Plane::new(forward * half_h - right, position),
+                Plane::new(forward * half_v + up, position),
+                Plane::new(forward * half_v - up, position),
+            ],
+        }
+    }
+
+    /// Whether any part of the sphere may be visible
+    ///
+    /// Conservative near the frustum corners: a sphere just outside two side
+    /// planes at once can still count as visible.
+    pub fn intersects_sphere(&self, center: Vector3, radius: f32) -> bool {
+        self.planes.iter().all(|plane| plane.distance(center) >= -radius)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frustum() -> Frustum {
+        let camera = Camera3D::perspective(
+            Vector3::zero(),
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            90.0,
+        );
+        Frustum::from_camera(&camera, 1.0, 0.1, 100.0)
+    }
+
+    #[test]
+    fn test_frustum_sphere_tests() {
+        let frustum = frustum();
+
+        assert!(frustum.intersects_sphere(Vector3::new(0.0, 0.0, -10.0), 1.0));
+        // Behind the camera and past the far plane
+        assert!(!frustum.intersects_sphere(Vector3::new(0.0, 0.0, 10.0), 1.0));
+        assert!(!frustum.intersects_sphere(Vector3::new(0.0, 0.0, -150.0), 1.0));
+        // 90 degree fov: the sides are at 45 degrees
+        assert!(!frustum.intersects_sphere(Vector3::new(12.0, 0.0, -10.0), 1.0));
+        assert!(!frustum.intersects_sphere(Vector3::new(0.0, -12.0, -10.0), 1.0));
+        // Overlapping an edge still counts
+        assert!(frustum.intersects_sphere(Vector3::new(11.0, 0.0, -10.0), 1.0));
+        assert!(frustum.intersects_sphere(Vector3::new(0.0, 0.0, 0.5), 1.0));
+    }
+}
@@ -2,13 +2,28 @@ pub mod components;
 pub mod entity;
 pub mod systems;
 pub mod physics;
+pub mod aabb;
+pub mod batching;
 pub mod examples;
 pub mod example_systems;
+pub mod prelude;
+pub mod simulation;
+pub mod raycast;
+pub mod frustum;
+pub mod bindings;
+pub mod combat_text;
 
 pub use components::*;
-pub use entity::World;
+pub use entity::{ComponentMask, World};
 pub use systems::*;
 pub use physics::*;
+pub use aabb::*;
+pub use batching::*;
+pub use simulation::*;
+pub use raycast::*;
+pub use frustum::*;
+pub use bindings::*;
+pub use combat_text::*;
 
 // Re-export examples for convenience
 pub use examples::*;
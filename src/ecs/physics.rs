@@ -1,37 +1,85 @@
 use raylib::prelude::*;
-use super::entity::World;
-use super::components::{Transform, Rigidbody, Collider, ColliderShape};
+use std::collections::{BTreeSet, HashMap};
+use std::rc::Rc;
+use super::entity::{Entity, World};
+use super::components::{Transform, Rigidbody, Collider, ColliderShape, MovingPlatform};
+use super::aabb::Aabb;
+use super::entity::EntityId;
 use super::systems::System;
 use crate::terrain::Terrain;
 
+/// Default physics step (60 Hz)
+pub const DEFAULT_FIXED_TIMESTEP: f32 = 1.0 / 60.0;
+
 /// Physics system that applies gravity and integrates velocity
+///
+/// Integration runs in fixed `fixed_dt` steps so the result doesn't depend on the
+/// frame rate; the leftover frame time is carried over to the next update.
+/// With a terrain (`with_terrain`), dynamic bodies are kept on the ground after
+/// every step instead of falling through the heightmap.
 pub struct PhysicsSystem {
     pub gravity: Vector3,
+    /// Step size in seconds; 0 integrates with the raw frame delta instead
+    pub fixed_dt: f32,
+    /// Ground that dynamic bodies rest on
+    pub terrain: Option<Rc<Terrain>>,
+    /// Frame time not yet consumed by a fixed step
+    accumulator: f32,
 }
 
 impl Default for PhysicsSystem {
     fn default() -> Self {
         Self {
             gravity: Vector3::new(0.0, -9.8, 0.0), // Standard gravity
+            fixed_dt: DEFAULT_FIXED_TIMESTEP,
+            terrain: None,
+            accumulator: 0.0,
         }
     }
 }
 
 impl PhysicsSystem {
+    /// Most fixed steps taken in one update; time beyond that is dropped so a
+    /// long stall doesn't snowball into ever longer frames
+    const MAX_STEPS_PER_UPDATE: u32 = 8;
+    /// Slack for float error, so summed frame deltas that should make a whole
+    /// step don't fall just short of it
+    const STEP_EPSILON: f32 = 1e-5;
+
     pub fn new(gravity: Vector3) -> Self {
-        Self { gravity }
+        Self { gravity, ..Default::default() }
     }
-}
 
-impl System for PhysicsSystem {
-    fn update(&mut self, world: &mut World, delta_time: f32) {
-        for entity in world.entities_mut() {
+    pub fn with_fixed_timestep(mut self, dt: f32) -> Self {
+        self.fixed_dt = dt;
+        self.accumulator = 0.0;
+        self
+    }
+
+    pub fn with_terrain(mut self, terrain: Rc<Terrain>) -> Self {
+        self.terrain = Some(terrain);
+        self
+    }
+
+    /// Integrate every body by one step of `delta_time`
+    fn step(&self, world: &mut World, delta_time: f32) {
+        let terrain = self.terrain.as_deref();
+        for entity in world.enabled_entities_mut() {
             if let (Some(transform), Some(rigidbody)) = (&mut entity.transform, &mut entity.rigidbody) {
                 // Skip static objects
                 if rigidbody.is_static() {
                     continue;
                 }
 
+                // Kinematic bodies follow their velocity exactly - no forces, gravity or drag
+                if rigidbody.is_kinematic() {
+                    transform.position += rigidbody.velocity * delta_time;
+                    transform.rotation += rigidbody.angular_velocity * delta_time;
+                    rigidbody.force = Vector3::zero();
+                    rigidbody.torque = Vector3::zero();
+                    continue;
+                }
+
                 // Apply gravity
                 if rigidbody.use_gravity {
                     rigidbody.add_force(self.gravity * rigidbody.mass);
@@ -54,12 +102,47 @@ impl System for PhysicsSystem {
                 // Update position
                 transform.position = transform.position + (rigidbody.velocity * delta_time);
 
+                // Same again for spin: torque, angular drag, then rotation
+                if rigidbody.inertia > 0.0 {
+                    let angular_acceleration = rigidbody.torque / rigidbody.inertia;
+                    rigidbody.angular_velocity += angular_acceleration * delta_time;
+                }
+                let angular_drag = rigidbody.angular_velocity * -rigidbody.angular_drag;
+                rigidbody.angular_velocity += angular_drag * delta_time;
+                transform.rotation += rigidbody.angular_velocity * delta_time;
+
                 // Reset forces for next frame
                 rigidbody.force = Vector3::zero();
+                rigidbody.torque = Vector3::zero();
 
                 // Reset grounded state (will be set by collision system)
                 rigidbody.is_grounded = false;
+
+                if let Some(terrain) = terrain {
+                    TerrainCollisionSystem::follow_ground(transform, rigidbody, entity.collider.as_ref(), terrain);
+                }
+            }
+        }
+    }
+}
+
+impl System for PhysicsSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        if self.fixed_dt <= 0.0 {
+            self.step(world, delta_time);
+            return;
+        }
+
+        self.accumulator += delta_time;
+        let mut steps = 0;
+        while self.accumulator >= self.fixed_dt - Self::STEP_EPSILON {
+            if steps == Self::MAX_STEPS_PER_UPDATE {
+                self.accumulator = self.accumulator.rem_euclid(self.fixed_dt);
+                break;
             }
+            self.step(world, self.fixed_dt);
+            self.accumulator -= self.fixed_dt;
+            steps += 1;
         }
     }
 }
@@ -73,6 +156,23 @@ pub struct Collision {
     pub penetration: f32,
 }
 
+/// How a trigger overlap changed since the previous update
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerKind {
+    Enter,
+    Stay,
+    Exit,
+}
+
+/// Overlap involving at least one trigger collider
+/// `entity_a` is always the lower id
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TriggerEvent {
+    pub entity_a: EntityId,
+    pub entity_b: EntityId,
+    pub kind: TriggerKind,
+}
+
 /// Terrain collision system - keeps entities above terrain
 pub struct TerrainCollisionSystem;
 
@@ -83,50 +183,342 @@ impl TerrainCollisionSystem {
 
     /// Apply terrain collision to all entities with rigidbody
     pub fn apply_terrain_collision(&self, world: &mut World, terrain: &Terrain) {
-        for entity in world.entities_mut() {
+        for entity in world.enabled_entities_mut() {
             if let (Some(transform), Some(rigidbody)) = (&mut entity.transform, &mut entity.rigidbody) {
-                // Get terrain height at entity position
-                let terrain_height = terrain.get_height_at(transform.position.x, transform.position.z);
-
-                // Get entity bottom position (assuming collider height)
-                let entity_bottom = if let Some(collider) = &entity.collider {
-                    match collider.shape {
-                        ColliderShape::Box { size } => size.y / 2.0,
-                        ColliderShape::Sphere { radius } => radius,
-                        ColliderShape::Capsule { radius, .. } => radius,
-                    }
-                } else {
-                    0.5 // Default offset
-                };
+                Self::follow_ground(transform, rigidbody, entity.collider.as_ref(), terrain);
+            }
+        }
+    }
 
-                let min_y = terrain_height + entity_bottom;
+    /// Keep one body on or above the terrain
+    ///
+    /// A body below the surface is lifted so its collider rests on it, and the
+    /// part of its velocity going into the ground is removed, so on a slope it
+    /// slides along the surface instead of stopping dead. Bodies on or just above
+    /// the ground are marked grounded.
+    pub fn follow_ground(transform: &mut Transform, rigidbody: &mut Rigidbody, collider: Option<&Collider>, terrain: &Terrain) {
+        let (x, z) = (transform.position.x, transform.position.z);
+        let terrain_height = terrain.get_height_at(x, z);
 
-                // If entity is below terrain, push it up and stop downward velocity
-                if transform.position.y < min_y {
-                    transform.position.y = min_y;
+        // Distance from the entity's center to the bottom of its collider
+        let half_height = collider.map_or(0.5, |collider| collider.shape.bounds(Vector3::zero()).half_extents().y);
+        let min_y = terrain_height + half_height;
 
-                    // Stop downward velocity
-                    if rigidbody.velocity.y < 0.0 {
-                        rigidbody.velocity.y = 0.0;
-                    }
+        if transform.position.y < min_y {
+            transform.position.y = min_y;
+
+            let normal = terrain.get_normal_at(x, z);
+            let into_ground = rigidbody.velocity.dot(normal);
+            if into_ground < 0.0 {
+                rigidbody.velocity -= normal * into_ground;
+            }
+
+            rigidbody.is_grounded = true;
+        } else if transform.position.y < min_y + 0.1 {
+            // Very close to ground
+            rigidbody.is_grounded = true;
+        }
+    }
+}
+
+/// Moves platforms along their waypoints and carries entities standing on them
+///
+/// Platforms are moved by setting their transform directly, so give them a
+/// kinematic rigidbody (with zero velocity) if dynamic bodies should collide with them.
+pub struct MovingPlatformSystem;
+
+impl MovingPlatformSystem {
+    /// How far below a rider we look for the platform surface
+    const CONTACT_TOLERANCE: f32 = 0.05;
+
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Whether `rider` is standing on top of `platform` (contact normal pointing up)
+    pub fn is_standing_on(
+        platform_pos: Vector3,
+        platform_shape: &ColliderShape,
+        rider_pos: Vector3,
+        rider_shape: &ColliderShape,
+    ) -> bool {
+        let probe = rider_pos - Vector3::new(0.0, Self::CONTACT_TOLERANCE, 0.0);
+        CollisionSystem::check_collision(platform_pos, platform_shape, probe, rider_shape)
+            .map(|(normal, _)| normal.y > 0.5)
+            .unwrap_or(false)
+    }
+
+    /// Move a platform toward its current waypoint, returning the displacement
+    fn advance(platform: &mut MovingPlatform, position: Vector3, delta_time: f32) -> Vector3 {
+        let Some(target) = platform.waypoints.get(platform.current_waypoint).copied() else {
+            return Vector3::zero();
+        };
 
-                    // Mark as grounded
-                    rigidbody.is_grounded = true;
-                } else if transform.position.y < min_y + 0.1 {
-                    // Very close to ground
-                    rigidbody.is_grounded = true;
+        let to_target = target - position;
+        let distance = to_target.length();
+        let step = platform.speed * delta_time;
+
+        if distance <= step {
+            platform.current_waypoint = (platform.current_waypoint + 1) % platform.waypoints.len();
+            to_target
+        } else {
+            to_target / distance * step
+        }
+    }
+}
+
+impl System for MovingPlatformSystem {
+    fn update(&mut self, world: &mut World, delta_time: f32) {
+        // Find riders against each platform's pre-move position, then move the platform
+        let mut moves: Vec<(EntityId, Vector3, Vector3, ColliderShape)> = Vec::new();
+        for entity in world.enabled_entities_mut() {
+            if let (Some(transform), Some(platform)) = (&mut entity.transform, &mut entity.moving_platform) {
+                let displacement = Self::advance(platform, transform.position, delta_time);
+                platform.frame_displacement = displacement;
+
+                if let Some(collider) = &entity.collider {
+                    moves.push((entity.id, transform.position, displacement, collider.shape));
                 }
+                transform.position += displacement;
+            }
+        }
+
+        for entity in world.enabled_entities_mut() {
+            let (Some(transform), Some(collider)) = (&mut entity.transform, &entity.collider) else {
+                continue;
+            };
+            if collider.is_trigger || entity.moving_platform.is_some() {
+                continue;
+            }
+            // Only bodies that physics can move get carried
+            if entity.rigidbody.is_some_and(|rb| !rb.is_dynamic()) {
+                continue;
+            }
+
+            let carrier = moves.iter().find(|(platform_id, platform_pos, _, platform_shape)| {
+                *platform_id != entity.id
+                    && Self::is_standing_on(*platform_pos, platform_shape, transform.position, &collider.shape)
+            });
+
+            if let Some((_, _, displacement, _)) = carrier {
+                transform.position += *displacement;
             }
         }
     }
 }
 
+/// Every pair `(i, j)` with `i < j < count` in row order, starting at the
+/// `start`th pair and wrapping around to the first
+fn pair_order(count: usize, start: usize) -> impl Iterator<Item = (usize, usize)> {
+    let pairs = (0..count).flat_map(move |i| ((i + 1)..count).map(move |j| (i, j)));
+    pairs.clone().skip(start).chain(pairs.take(start))
+}
+
+/// Per-frame collision counters, for tuning and debug overlays
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CollisionStats {
+    /// Pairs of colliders considered by the broadphase
+    pub pairs_tested: usize,
+    /// Pairs whose bounds overlapped and went on to the narrowphase
+    pub broadphase_pairs: usize,
+    /// Contacts that were actually resolved (triggers and bodiless colliders are skipped)
+    pub collisions_resolved: usize,
+    /// Whether `pair_budget` cut the narrowphase short this frame
+    pub budget_exhausted: bool,
+    /// Fast movers stopped by the continuous (swept) pass
+    pub swept_hits: usize,
+}
+
 /// Collision detection and resolution system
-pub struct CollisionSystem;
+pub struct CollisionSystem {
+    stats: CollisionStats,
+    /// Maximum narrowphase checks per update; remaining pairs wait for the next frame
+    pub pair_budget: Option<usize>,
+    /// Pair (in `pair_order`) the next update starts at, so a binding budget
+    /// works through every pair in turn instead of only the leading ones
+    pair_cursor: usize,
+    /// Sweep movers from their previous position so fast ones can't tunnel
+    /// through static colliders
+    pub continuous: bool,
+    /// Positions at the end of the last update, the start of each sweep
+    previous_positions: HashMap<EntityId, Vector3>,
+    /// Hits found by the swept pass in the last update (A is the mover)
+    swept_collisions: Vec<Collision>,
+    /// Trigger pairs overlapping as of the last update
+    trigger_pairs: BTreeSet<(EntityId, EntityId)>,
+    /// Trigger transitions from the last update
+    trigger_events: Vec<TriggerEvent>,
+}
 
 impl CollisionSystem {
     pub fn new() -> Self {
-        Self
+        Self {
+            stats: CollisionStats::default(),
+            pair_budget: None,
+            pair_cursor: 0,
+            continuous: false,
+            previous_positions: HashMap::new(),
+            swept_collisions: Vec::new(),
+            trigger_pairs: BTreeSet::new(),
+            trigger_events: Vec::new(),
+        }
+    }
+
+    pub fn with_pair_budget(mut self, budget: usize) -> Self {
+        self.pair_budget = Some(budget);
+        self
+    }
+
+    pub fn with_continuous(mut self, continuous: bool) -> Self {
+        self.continuous = continuous;
+        self
+    }
+
+    /// Counters from the most recent update
+    pub fn stats(&self) -> CollisionStats {
+        self.stats
+    }
+
+    /// Collisions found by the continuous pass in the most recent update
+    pub fn swept_collisions(&self) -> &[Collision] {
+        &self.swept_collisions
+    }
+
+    /// Trigger enter/stay/exit events from the most recent update
+    pub fn triggers(&self) -> &[TriggerEvent] {
+        &self.trigger_events
+    }
+
+    /// Turn this update's trigger overlaps into events against the previous set
+    /// Previous pairs in `untested` (skipped by the pair budget) are kept as they were
+    fn update_triggers(
+        &mut self,
+        overlapping: BTreeSet<(EntityId, EntityId)>,
+        untested: BTreeSet<(EntityId, EntityId)>,
+    ) {
+        self.trigger_events.clear();
+        for &(entity_a, entity_b) in &overlapping {
+            let kind = if self.trigger_pairs.contains(&(entity_a, entity_b)) {
+                TriggerKind::Stay
+            } else {
+                TriggerKind::Enter
+            };
+            self.trigger_events.push(TriggerEvent { entity_a, entity_b, kind });
+        }
+
+        let previous = std::mem::replace(&mut self.trigger_pairs, overlapping);
+        for (entity_a, entity_b) in previous.difference(&self.trigger_pairs).copied().collect::<Vec<_>>() {
+            // Pairs skipped by the pair budget weren't tested, so they haven't left
+            if untested.contains(&(entity_a, entity_b)) {
+                self.trigger_pairs.insert((entity_a, entity_b));
+                continue;
+            }
+            self.trigger_events.push(TriggerEvent { entity_a, entity_b, kind: TriggerKind::Exit });
+        }
+    }
+
+    /// Time of impact of a shape moving from `previous` to `current` against a
+    /// static shape, as a fraction of the move (0..=1) plus the hit face normal
+    /// (pointing out of the target, towards the mover)
+    ///
+    /// Both shapes are treated as their bounding boxes. Returns `None` when the
+    /// path misses or the mover already overlapped the target at `previous`.
+    pub fn swept_aabb(
+        previous: Vector3,
+        current: Vector3,
+        shape: &ColliderShape,
+        target_pos: Vector3,
+        target_shape: &ColliderShape,
+    ) -> Option<(f32, Vector3)> {
+        // Grow the target by the mover's half size and sweep the mover's center as a ray
+        let half = shape.bounds(Vector3::zero()).half_extents();
+        let target = target_shape.bounds(target_pos);
+        let expanded = Aabb::new(target.min - half, target.max + half);
+
+        let displacement = current - previous;
+        let time = expanded.ray_intersection(previous, displacement)?;
+        if time <= 0.0 || time > 1.0 {
+            return None;
+        }
+        Some((time, expanded.face_normal(previous + displacement * time)))
+    }
+
+    /// Whether a collider never moves, making it a target for the swept pass
+    /// Bodiless entities with a `Velocity` or `Projectile` still move, so they
+    /// are swept like any other mover
+    fn is_static_collider(entity: &Entity) -> bool {
+        match &entity.rigidbody {
+            Some(body) => body.is_static(),
+            None => entity.velocity.is_none() && entity.projectile.is_none(),
+        }
+    }
+
+    /// Continuous pass: stop movers that would have passed through a static
+    /// collider since the last update at the first point of contact
+    fn sweep_fast_movers(&mut self, world: &mut World, stats: &mut CollisionStats) {
+        let targets: Vec<_> = world
+            .enabled_entities()
+            .filter(|e| Self::is_static_collider(e))
+            .filter_map(|e| match (e.transform, e.collider) {
+                (Some(transform), Some(collider)) if !collider.is_trigger => {
+                    Some((e.id, transform.position, collider))
+                }
+                _ => None,
+            })
+            .collect();
+
+        for entity in world.enabled_entities_mut() {
+            if Self::is_static_collider(entity) {
+                continue;
+            }
+            let (Some(transform), Some(collider)) = (&mut entity.transform, &entity.collider) else {
+                continue;
+            };
+            let Some(&previous) = self.previous_positions.get(&entity.id) else {
+                continue;
+            };
+            if collider.is_trigger {
+                continue;
+            }
+
+            // Slow movers can't skip past anything; the discrete pass handles them
+            let half = collider.shape.bounds(Vector3::zero()).half_extents();
+            let displacement = transform.position - previous;
+            if displacement.length() < half.x.min(half.y).min(half.z) {
+                continue;
+            }
+
+            let earliest = targets
+                .iter()
+                .filter(|(id, _, target)| *id != entity.id && collider.can_collide_with(target))
+                .filter_map(|(id, pos, target)| {
+                    Self::swept_aabb(previous, transform.position, &collider.shape, *pos, &target.shape)
+                        .map(|(time, normal)| (*id, time, normal))
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+
+            if let Some((target_id, time, normal)) = earliest {
+                transform.position = previous + displacement * time;
+
+                // Drop the velocity into the surface so the mover stays stopped
+                if let Some(rigidbody) = &mut entity.rigidbody {
+                    let into = rigidbody.velocity.dot(normal).min(0.0);
+                    rigidbody.velocity -= normal * into;
+                }
+                if let Some(velocity) = &mut entity.velocity {
+                    let into = velocity.linear.dot(normal).min(0.0);
+                    velocity.linear -= normal * into;
+                }
+
+                self.swept_collisions.push(Collision {
+                    entity_a: entity.id,
+                    entity_b: target_id,
+                    normal: -normal,
+                    penetration: 0.0,
+                });
+                stats.swept_hits += 1;
+            }
+        }
     }
 
     /// Check collision between two axis-aligned bounding boxes
@@ -136,10 +528,16 @@ impl CollisionSystem {
         pos_b: Vector3,
         size_b: Vector3,
     ) -> Option<(Vector3, f32)> {
-        let half_a = size_a / 2.0;
-        let half_b = size_b / 2.0;
+        let box_a = Aabb::from_center_size(pos_a, size_a);
+        let box_b = Aabb::from_center_size(pos_b, size_b);
+        if !box_a.intersects(&box_b) {
+            return None;
+        }
 
-        let delta = pos_b - pos_a;
+        let half_a = box_a.half_extents();
+        let half_b = box_b.half_extents();
+
+        let delta = box_b.center() - box_a.center();
 
         let overlap_x = half_a.x + half_b.x - delta.x.abs();
         let overlap_y = half_a.y + half_b.y - delta.y.abs();
@@ -194,16 +592,11 @@ impl CollisionSystem {
         box_pos: Vector3,
         box_size: Vector3,
     ) -> Option<(Vector3, f32)> {
-        let half_size = box_size / 2.0;
-        let min = box_pos - half_size;
-        let max = box_pos + half_size;
+        let bounds = Aabb::from_center_size(box_pos, box_size);
+        let half_size = bounds.half_extents();
 
         // Find closest point on box to sphere center
-        let closest = Vector3::new(
-            sphere_pos.x.clamp(min.x, max.x),
-            sphere_pos.y.clamp(min.y, max.y),
-            sphere_pos.z.clamp(min.z, max.z),
-        );
+        let closest = bounds.closest_point(sphere_pos);
 
         let delta = sphere_pos - closest;
         let distance = delta.length();
@@ -240,6 +633,237 @@ impl CollisionSystem {
         }
     }
 
+    /// Closest point to `point` on the segment `start`-`end`
+    fn closest_point_on_segment(start: Vector3, end: Vector3, point: Vector3) -> Vector3 {
+        let segment = end - start;
+        let length_sq = segment.dot(segment);
+        if length_sq <= f32::EPSILON {
+            return start;
+        }
+        let t = ((point - start).dot(segment) / length_sq).clamp(0.0, 1.0);
+        start + segment * t
+    }
+
+    /// Closest points between segments `p1`-`q1` and `p2`-`q2`
+    fn closest_points_between_segments(
+        p1: Vector3,
+        q1: Vector3,
+        p2: Vector3,
+        q2: Vector3,
+    ) -> (Vector3, Vector3) {
+        let d1 = q1 - p1;
+        let d2 = q2 - p2;
+        let r = p1 - p2;
+        let a = d1.dot(d1);
+        let e = d2.dot(d2);
+        let f = d2.dot(r);
+
+        let (s, t) = if a <= f32::EPSILON && e <= f32::EPSILON {
+            (0.0, 0.0)
+        } else if a <= f32::EPSILON {
+            (0.0, (f / e).clamp(0.0, 1.0))
+        } else {
+            let c = d1.dot(r);
+            if e <= f32::EPSILON {
+                ((-c / a).clamp(0.0, 1.0), 0.0)
+            } else {
+                let b = d1.dot(d2);
+                let denom = a * e - b * b;
+                // Parallel segments: any s works, start from p1
+                let s = if denom > f32::EPSILON {
+                    ((b * f - c * e) / denom).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let t = (b * s + f) / e;
+                if t < 0.0 {
+                    ((-c / a).clamp(0.0, 1.0), 0.0)
+                } else if t > 1.0 {
+                    (((b - c) / a).clamp(0.0, 1.0), 1.0)
+                } else {
+                    (s, t)
+                }
+            }
+        };
+
+        (p1 + d1 * s, p2 + d2 * t)
+    }
+
+    /// End points of a capsule's inner segment (the capsule minus its end caps)
+    ///
+    /// `up` is the capsule's local up axis and `height` its full height including the caps.
+    pub fn capsule_segment(pos: Vector3, up: Vector3, radius: f32, height: f32) -> (Vector3, Vector3) {
+        let tip = up * (height / 2.0 - radius).max(0.0);
+        (pos + tip, pos - tip)
+    }
+
+    /// Check collision between two capsules by comparing the distance between
+    /// their segment axes to the sum of the radii
+    ///
+    /// Segments are the `(top, bottom)` end points from `capsule_segment`.
+    pub fn check_capsule_collision(
+        segment_a: (Vector3, Vector3),
+        radius_a: f32,
+        segment_b: (Vector3, Vector3),
+        radius_b: f32,
+    ) -> Option<(Vector3, f32)> {
+        let (start_a, end_a) = segment_a;
+        let (start_b, end_b) = segment_b;
+        let (closest_a, closest_b) = Self::closest_points_between_segments(start_a, end_a, start_b, end_b);
+
+        let delta = closest_b - closest_a;
+        let distance = delta.length();
+        let min_distance = radius_a + radius_b;
+        if distance >= min_distance {
+            return None;
+        }
+
+        // Crossing axes give no direction; fall back to the line between centers
+        let normal = if distance > f32::EPSILON {
+            delta / distance
+        } else {
+            let centers = (start_b + end_b) / 2.0 - (start_a + end_a) / 2.0;
+            if centers.length() > f32::EPSILON {
+                centers.normalized()
+            } else {
+                Vector3::new(1.0, 0.0, 0.0)
+            }
+        };
+        Some((normal, min_distance - distance))
+    }
+
+    /// Check collision between a capsule and a sphere
+    fn check_capsule_sphere_collision(
+        capsule_pos: Vector3,
+        up: Vector3,
+        radius: f32,
+        height: f32,
+        sphere_pos: Vector3,
+        sphere_radius: f32,
+    ) -> Option<(Vector3, f32)> {
+        let (start, end) = Self::capsule_segment(capsule_pos, up, radius, height);
+        let closest = Self::closest_point_on_segment(start, end, sphere_pos);
+        Self::check_sphere_collision(closest, radius, sphere_pos, sphere_radius)
+    }
+
+    /// Check collision between a capsule and a box
+    /// Like `check_sphere_box_collision`, the normal points from the box to the capsule
+    fn check_capsule_box_collision(
+        capsule_pos: Vector3,
+        up: Vector3,
+        radius: f32,
+        height: f32,
+        box_pos: Vector3,
+        box_size: Vector3,
+    ) -> Option<(Vector3, f32)> {
+        let (start, end) = Self::capsule_segment(capsule_pos, up, radius, height);
+        let bounds = Aabb::from_center_size(box_pos, box_size);
+
+        // Distance from the segment to a box is convex along the segment, so a
+        // ternary search finds the segment point nearest the box
+        let distance_at = |t: f32| {
+            let point = start + (end - start) * t;
+            (point - bounds.closest_point(point)).length()
+        };
+        let (mut low, mut high) = (0.0_f32, 1.0_f32);
+        for _ in 0..32 {
+            let m1 = low + (high - low) / 3.0;
+            let m2 = high - (high - low) / 3.0;
+            if distance_at(m1) <= distance_at(m2) {
+                high = m2;
+            } else {
+                low = m1;
+            }
+        }
+        let nearest = start + (end - start) * ((low + high) / 2.0);
+
+        Self::check_sphere_box_collision(nearest, radius, box_pos, box_size)
+    }
+
+    /// Check collision between two collider shapes
+    /// Returns the normal (pointing from A to B) and penetration depth
+    ///
+    /// Capsules are treated as upright; use `check_collision_oriented` for rotated ones.
+    pub fn check_collision(
+        pos_a: Vector3,
+        shape_a: &ColliderShape,
+        pos_b: Vector3,
+        shape_b: &ColliderShape,
+    ) -> Option<(Vector3, f32)> {
+        Self::check_collision_oriented(pos_a, Vector3::up(), shape_a, pos_b, Vector3::up(), shape_b)
+    }
+
+    /// Check collision between two collider shapes whose local up axes are `up_a`/`up_b`
+    /// Returns the normal (pointing from A to B) and penetration depth
+    ///
+    /// Only capsules use the up axis; boxes stay axis-aligned.
+    pub fn check_collision_oriented(
+        pos_a: Vector3,
+        up_a: Vector3,
+        shape_a: &ColliderShape,
+        pos_b: Vector3,
+        up_b: Vector3,
+        shape_b: &ColliderShape,
+    ) -> Option<(Vector3, f32)> {
+        match (shape_a, shape_b) {
+            (ColliderShape::Box { size: size_a }, ColliderShape::Box { size: size_b }) => {
+                Self::check_aabb_collision(pos_a, *size_a, pos_b, *size_b)
+            }
+            (ColliderShape::Sphere { radius: radius_a }, ColliderShape::Sphere { radius: radius_b }) => {
+                Self::check_sphere_collision(pos_a, *radius_a, pos_b, *radius_b)
+            }
+            // check_sphere_box_collision's normal points from the box to the sphere
+            (ColliderShape::Sphere { radius }, ColliderShape::Box { size }) => {
+                Self::check_sphere_box_collision(pos_a, *radius, pos_b, *size)
+                    .map(|(normal, pen)| (-normal, pen))
+            }
+            (ColliderShape::Box { size }, ColliderShape::Sphere { radius }) => {
+                Self::check_sphere_box_collision(pos_b, *radius, pos_a, *size)
+            }
+            (ColliderShape::Capsule { radius, height }, ColliderShape::Sphere { radius: radius_b }) => {
+                Self::check_capsule_sphere_collision(pos_a, up_a, *radius, *height, pos_b, *radius_b)
+            }
+            (ColliderShape::Sphere { radius }, ColliderShape::Capsule { radius: radius_b, height }) => {
+                Self::check_capsule_sphere_collision(pos_b, up_b, *radius_b, *height, pos_a, *radius)
+                    .map(|(normal, pen)| (-normal, pen))
+            }
+            (
+                ColliderShape::Capsule { radius: radius_a, height: height_a },
+                ColliderShape::Capsule { radius: radius_b, height: height_b },
+            ) => Self::check_capsule_collision(
+                Self::capsule_segment(pos_a, up_a, *radius_a, *height_a),
+                *radius_a,
+                Self::capsule_segment(pos_b, up_b, *radius_b, *height_b),
+                *radius_b,
+            ),
+            // check_capsule_box_collision's normal points from the box to the capsule
+            (ColliderShape::Capsule { radius, height }, ColliderShape::Box { size }) => {
+                Self::check_capsule_box_collision(pos_a, up_a, *radius, *height, pos_b, *size)
+                    .map(|(normal, pen)| (-normal, pen))
+            }
+            (ColliderShape::Box { size }, ColliderShape::Capsule { radius, height }) => {
+                Self::check_capsule_box_collision(pos_b, up_b, *radius, *height, pos_a, *size)
+            }
+        }
+    }
+
+    /// How far a shape's bounds extend from its center along `direction` (unit length)
+    fn reach_along(shape: &ColliderShape, direction: Vector3) -> f32 {
+        let half = shape.bounds(Vector3::zero()).half_extents();
+        half.x * direction.x.abs() + half.y * direction.y.abs() + half.z * direction.z.abs()
+    }
+
+    /// An entity's collider as used for resolution
+    /// A `Bouncy` component overrides the collider's restitution, so bounciness
+    /// can be a gameplay effect independent of the physics material
+    fn effective_collider(entity: &Entity) -> Option<Collider> {
+        let mut collider = entity.collider?;
+        if let Some(bouncy) = entity.bouncy {
+            collider.restitution = bouncy.restitution;
+        }
+        Some(collider)
+    }
+
     /// Resolve collision between two entities
     fn resolve_collision(
         transform_a: &mut Transform,
@@ -250,14 +874,15 @@ impl CollisionSystem {
         collider_b: &Collider,
         normal: Vector3,
         penetration: f32,
-    ) {
+    ) -> bool {
         // Don't resolve if either is a trigger
         if collider_a.is_trigger || collider_b.is_trigger {
-            return;
+            return false;
         }
 
-        let static_a = rigidbody_a.is_static();
-        let static_b = rigidbody_b.is_static();
+        // Static and kinematic bodies are never moved by collisions
+        let static_a = !rigidbody_a.is_dynamic();
+        let static_b = !rigidbody_b.is_dynamic();
 
         // Position correction
         if !static_a && !static_b {
@@ -278,7 +903,7 @@ impl CollisionSystem {
 
         // Don't resolve if velocities are separating
         if velocity_along_normal > 0.0 {
-            return;
+            return true;
         }
 
         // Calculate restitution (bounciness)
@@ -310,10 +935,11 @@ impl CollisionSystem {
         let friction = (collider_a.friction + collider_b.friction) / 2.0;
         let tangent = relative_velocity - (normal * velocity_along_normal);
         let tangent_length = tangent.length();
+        let mut friction_impulse = Vector3::zero();
 
         if tangent_length > 0.001 {
             let tangent_normalized = tangent / tangent_length;
-            let friction_impulse = tangent_normalized * (-friction * impulse_scalar.abs());
+            friction_impulse = tangent_normalized * (-friction * impulse_scalar.abs());
 
             if !static_a {
                 rigidbody_a.velocity = rigidbody_a.velocity - (friction_impulse / rigidbody_a.mass);
@@ -323,6 +949,20 @@ impl CollisionSystem {
             }
         }
 
+        // Angular impulse at the contact point: on A's surface along the normal.
+        // The normal part passes through the centers, so in practice friction
+        // is what sets bodies spinning
+        let total_impulse = impulse + friction_impulse;
+        let contact = transform_a.position + normal * Self::reach_along(&collider_a.shape, normal);
+        if !static_a && rigidbody_a.inertia > 0.0 {
+            let arm = contact - transform_a.position;
+            rigidbody_a.angular_velocity -= arm.cross(total_impulse) / rigidbody_a.inertia;
+        }
+        if !static_b && rigidbody_b.inertia > 0.0 {
+            let arm = contact - transform_b.position;
+            rigidbody_b.angular_velocity += arm.cross(total_impulse) / rigidbody_b.inertia;
+        }
+
         // Set grounded state if colliding from above
         if normal.y > 0.5 && !static_a {
             rigidbody_a.is_grounded = true;
@@ -330,67 +970,90 @@ impl CollisionSystem {
         if normal.y < -0.5 && !static_b {
             rigidbody_b.is_grounded = true;
         }
+
+        true
     }
 }
 
 impl System for CollisionSystem {
     fn update(&mut self, world: &mut World, _delta_time: f32) {
         let mut collisions = Vec::new();
+        let mut overlapping_triggers = BTreeSet::new();
+        let mut untested_triggers = BTreeSet::new();
+        let mut stats = CollisionStats::default();
 
-        // Collect all collisions
-        let entities: Vec<_> = world.entities().collect();
-        for i in 0..entities.len() {
-            for j in (i + 1)..entities.len() {
-                let entity_a = &entities[i];
-                let entity_b = &entities[j];
-
-                if let (Some(transform_a), Some(collider_a), Some(transform_b), Some(collider_b)) = (
-                    &entity_a.transform,
-                    &entity_a.collider,
-                    &entity_b.transform,
-                    &entity_b.collider,
-                ) {
-                    let collision_result = match (&collider_a.shape, &collider_b.shape) {
-                        (ColliderShape::Box { size: size_a }, ColliderShape::Box { size: size_b }) => {
-                            Self::check_aabb_collision(transform_a.position, *size_a, transform_b.position, *size_b)
-                        }
-                        (ColliderShape::Sphere { radius: radius_a }, ColliderShape::Sphere { radius: radius_b }) => {
-                            Self::check_sphere_collision(transform_a.position, *radius_a, transform_b.position, *radius_b)
-                        }
-                        (ColliderShape::Sphere { radius }, ColliderShape::Box { size }) => {
-                            Self::check_sphere_box_collision(transform_a.position, *radius, transform_b.position, *size)
-                        }
-                        (ColliderShape::Box { size }, ColliderShape::Sphere { radius }) => {
-                            Self::check_sphere_box_collision(transform_b.position, *radius, transform_a.position, *size)
-                                .map(|(normal, pen)| (-normal, pen))
-                        }
-                        // Capsule collisions simplified to sphere for now
-                        (ColliderShape::Capsule { radius, .. }, ColliderShape::Sphere { radius: radius_b }) => {
-                            Self::check_sphere_collision(transform_a.position, *radius, transform_b.position, *radius_b)
-                        }
-                        (ColliderShape::Sphere { radius }, ColliderShape::Capsule { radius: radius_b, .. }) => {
-                            Self::check_sphere_collision(transform_a.position, *radius, transform_b.position, *radius_b)
-                        }
-                        (ColliderShape::Capsule { radius: radius_a, .. }, ColliderShape::Capsule { radius: radius_b, .. }) => {
-                            Self::check_sphere_collision(transform_a.position, *radius_a, transform_b.position, *radius_b)
-                        }
-                        (ColliderShape::Capsule { radius, .. }, ColliderShape::Box { size }) => {
-                            Self::check_sphere_box_collision(transform_a.position, *radius, transform_b.position, *size)
-                        }
-                        (ColliderShape::Box { size }, ColliderShape::Capsule { radius, .. }) => {
-                            Self::check_sphere_box_collision(transform_b.position, *radius, transform_a.position, *size)
-                                .map(|(normal, pen)| (-normal, pen))
-                        }
-                    };
+        self.swept_collisions.clear();
+        if self.continuous {
+            self.sweep_fast_movers(world, &mut stats);
+        }
+
+        // Collect all collisions, starting at the pair the budget stopped at last update
+        let entities: Vec<_> = world
+            .enabled_entities()
+            .filter(|e| e.transform.is_some() && e.collider.is_some())
+            .collect();
+        let pair_count = entities.len() * entities.len().saturating_sub(1) / 2;
+        let first_pair = if pair_count == 0 { 0 } else { self.pair_cursor % pair_count };
+        self.pair_cursor = 0;
+        let mut order = pair_order(entities.len(), first_pair).enumerate();
+        while let Some((index, (i, j))) = order.next() {
+            let entity_a = &entities[i];
+            let entity_b = &entities[j];
+
+            if let (Some(transform_a), Some(collider_a), Some(transform_b), Some(collider_b)) = (
+                &entity_a.transform,
+                &entity_a.collider,
+                &entity_b.transform,
+                &entity_b.collider,
+            ) {
+                stats.pairs_tested += 1;
+
+                if !collider_a.can_collide_with(collider_b) {
+                    continue;
+                }
 
-                    if let Some((normal, penetration)) = collision_result {
-                        collisions.push(Collision {
-                            entity_a: entity_a.id,
-                            entity_b: entity_b.id,
-                            normal,
-                            penetration,
-                        });
+                // Broadphase: skip pairs whose bounds don't even touch
+                let (up_a, up_b) = (transform_a.up(), transform_b.up());
+                let bounds_a = collider_a.shape.oriented_bounds(transform_a.position, up_a);
+                if !bounds_a.intersects(&collider_b.shape.oriented_bounds(transform_b.position, up_b)) {
+                    continue;
+                }
+
+                if self.pair_budget.is_some_and(|budget| stats.broadphase_pairs >= budget) {
+                    // Carry on from this pair next update so every pair gets its turn
+                    stats.budget_exhausted = true;
+                    self.pair_cursor = (first_pair + index) % pair_count;
+                    // Trigger pairs from here on weren't tested, so they keep their state
+                    untested_triggers = std::iter::once((i, j))
+                        .chain(order.by_ref().map(|(_, pair)| pair))
+                        .map(|(i, j)| (entities[i].id, entities[j].id))
+                        .filter(|pair| self.trigger_pairs.contains(pair))
+                        .collect();
+                    break;
+                }
+                stats.broadphase_pairs += 1;
+
+                let collision_result = Self::check_collision_oriented(
+                    transform_a.position,
+                    up_a,
+                    &collider_a.shape,
+                    transform_b.position,
+                    up_b,
+                    &collider_b.shape,
+                );
+
+                if collider_a.is_trigger || collider_b.is_trigger {
+                    // Triggers only report overlaps, they never push anything
+                    if collision_result.is_some() {
+                        overlapping_triggers.insert((entity_a.id, entity_b.id));
                     }
+                } else if let Some((normal, penetration)) = collision_result {
+                    collisions.push(Collision {
+                        entity_a: entity_a.id,
+                        entity_b: entity_b.id,
+                        normal,
+                        penetration,
+                    });
                 }
             }
         }
@@ -417,6 +1080,8 @@ impl System for CollisionSystem {
                         (&mut right[0], &mut left[idx_b])
                     };
 
+                    let collider_a = Self::effective_collider(entity_a);
+                    let collider_b = Self::effective_collider(entity_b);
                     if let (
                         Some(transform_a),
                         Some(rigidbody_a),
@@ -427,24 +1092,607 @@ impl System for CollisionSystem {
                     ) = (
                         &mut entity_a.transform,
                         &mut entity_a.rigidbody,
-                        &entity_a.collider,
+                        collider_a,
                         &mut entity_b.transform,
                         &mut entity_b.rigidbody,
-                        &entity_b.collider,
+                        collider_b,
                     ) {
-                        Self::resolve_collision(
+                        let resolved = Self::resolve_collision(
                             transform_a,
                             rigidbody_a,
-                            collider_a,
+                            &collider_a,
                             transform_b,
                             rigidbody_b,
-                            collider_b,
+                            &collider_b,
                             collision.normal,
                             collision.penetration,
                         );
+                        if resolved {
+                            stats.collisions_resolved += 1;
+                        }
                     }
                 }
             }
         }
+
+        self.update_triggers(overlapping_triggers, untested_triggers);
+
+        if self.continuous {
+            self.previous_positions = world
+                .enabled_entities()
+                .filter_map(|e| e.transform.map(|t| (e.id, t.position)))
+                .collect();
+        }
+
+        self.stats = stats;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::examples::Bouncy;
+
+    fn spawn_box(world: &mut World, position: Vector3, size: Vector3, rigidbody: Rigidbody) -> usize {
+        world
+            .spawn()
+            .with_transform(Transform::new(position))
+            .with_rigidbody(rigidbody)
+            .with_collider(Collider::box_collider(size))
+            .build()
+    }
+
+    #[test]
+    fn test_kinematic_pushes_dynamic() {
+        let mut world = World::new();
+        let mut platform = Rigidbody::kinematic();
+        platform.velocity = Vector3::new(2.0, 0.0, 0.0);
+        let platform_id = spawn_box(&mut world, Vector3::zero(), Vector3::one(), platform);
+        let box_id = spawn_box(
+            &mut world,
+            Vector3::new(0.9, 0.0, 0.0),
+            Vector3::one(),
+            Rigidbody::new(1.0).with_gravity(false),
+        );
+
+        CollisionSystem::new().update(&mut world, 1.0 / 60.0);
+
+        let platform = world.get_entity(platform_id).unwrap();
+        assert_eq!(platform.transform.unwrap().position, Vector3::zero());
+        assert_eq!(platform.rigidbody.unwrap().velocity, Vector3::new(2.0, 0.0, 0.0));
+
+        let pushed = world.get_entity(box_id).unwrap();
+        assert!((pushed.transform.unwrap().position.x - 1.0).abs() < 1e-4);
+        assert!(pushed.rigidbody.unwrap().velocity.x > 0.0);
+    }
+
+    #[test]
+    fn test_kinematic_platform_carries_dynamic() {
+        let mut world = World::new();
+        let mut platform = Rigidbody::kinematic();
+        platform.velocity = Vector3::new(2.0, 0.0, 0.0);
+        let platform_id = spawn_box(&mut world, Vector3::zero(), Vector3::new(20.0, 1.0, 20.0), platform);
+        let box_id = spawn_box(&mut world, Vector3::new(0.0, 1.0, 0.0), Vector3::one(), Rigidbody::new(1.0));
+
+        let mut physics = PhysicsSystem::default();
+        let mut collisions = CollisionSystem::new();
+        for _ in 0..120 {
+            physics.update(&mut world, 1.0 / 60.0);
+            collisions.update(&mut world, 1.0 / 60.0);
+        }
+
+        let platform = world.get_entity(platform_id).unwrap();
+        assert_eq!(platform.rigidbody.unwrap().velocity, Vector3::new(2.0, 0.0, 0.0));
+        assert!((platform.transform.unwrap().position.x - 4.0).abs() < 1e-3);
+
+        // Friction drags the box along with the platform and it stays on top
+        let carried = world.get_entity(box_id).unwrap();
+        assert!(carried.transform.unwrap().position.x > 2.0);
+        assert!(carried.transform.unwrap().position.y > 0.9);
+    }
+
+    fn spawn_platform(world: &mut World) -> usize {
+        world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_rigidbody(Rigidbody::kinematic())
+            .with_collider(Collider::box_collider(Vector3::new(4.0, 1.0, 4.0)))
+            .with_moving_platform(MovingPlatform::new(
+                vec![Vector3::new(10.0, 0.0, 0.0), Vector3::zero()],
+                2.0,
+            ))
+            .build()
+    }
+
+    #[test]
+    fn test_platform_carries_rider() {
+        let mut world = World::new();
+        let platform_id = spawn_platform(&mut world);
+        let rider_id = spawn_box(&mut world, Vector3::new(0.5, 1.0, 0.0), Vector3::one(), Rigidbody::new(1.0));
+
+        let mut system = MovingPlatformSystem::new();
+        for _ in 0..60 {
+            system.update(&mut world, 1.0 / 60.0);
+        }
+
+        let platform_pos = world.get_entity(platform_id).unwrap().transform.unwrap().position;
+        let rider_pos = world.get_entity(rider_id).unwrap().transform.unwrap().position;
+        assert!((platform_pos.x - 2.0).abs() < 1e-3);
+        assert!((rider_pos.x - 2.5).abs() < 1e-3);
+        assert_eq!(rider_pos.y, 1.0);
+    }
+
+    #[test]
+    fn test_platform_stops_carrying_after_step_off() {
+        let mut world = World::new();
+        spawn_platform(&mut world);
+        let rider_id = spawn_box(&mut world, Vector3::new(0.0, 1.0, 0.0), Vector3::one(), Rigidbody::new(1.0));
+
+        let mut system = MovingPlatformSystem::new();
+        system.update(&mut world, 0.5);
+        assert!((world.get_entity(rider_id).unwrap().transform.unwrap().position.x - 1.0).abs() < 1e-4);
+
+        // Step off the far edge - the platform keeps going without the rider
+        world.get_entity_mut(rider_id).unwrap().transform.as_mut().unwrap().position.z = 6.0;
+        system.update(&mut world, 0.5);
+        let rider_pos = world.get_entity(rider_id).unwrap().transform.unwrap().position;
+        assert!((rider_pos.x - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_sphere_on_box_normal_points_up() {
+        let box_shape = ColliderShape::Box { size: Vector3::new(4.0, 1.0, 4.0) };
+        let sphere_shape = ColliderShape::Sphere { radius: 0.5 };
+
+        let (normal, _) = CollisionSystem::check_collision(
+            Vector3::zero(),
+            &box_shape,
+            Vector3::new(0.0, 0.9, 0.0),
+            &sphere_shape,
+        )
+        .unwrap();
+        assert!(normal.y > 0.99);
+
+        let (normal, _) = CollisionSystem::check_collision(
+            Vector3::new(0.0, 0.9, 0.0),
+            &sphere_shape,
+            Vector3::zero(),
+            &box_shape,
+        )
+        .unwrap();
+        assert!(normal.y < -0.99);
+    }
+
+    #[test]
+    fn test_capsules_collide_along_their_segments() {
+        let capsule = ColliderShape::Capsule { radius: 0.5, height: 4.0 };
+
+        // Centers are 2.66 apart, so a sphere approximation misses, but the
+        // segments overlap vertically and sit 0.9 apart
+        let (normal, penetration) = CollisionSystem::check_collision(
+            Vector3::zero(),
+            &capsule,
+            Vector3::new(0.9, 2.5, 0.0),
+            &capsule,
+        )
+        .unwrap();
+        assert!((normal.x - 1.0).abs() < 1e-4);
+        assert!((penetration - 0.1).abs() < 1e-4);
+
+        assert!(CollisionSystem::check_collision(
+            Vector3::zero(),
+            &capsule,
+            Vector3::new(1.1, 0.0, 0.0),
+            &capsule,
+        )
+        .is_none());
+
+        // Lying along x, a capsule reaches a neighbour its upright bounds would miss
+        let lying = Transform::new(Vector3::zero())
+            .with_rotation(Vector3::new(0.0, 0.0, std::f32::consts::FRAC_PI_2));
+        assert!(CollisionSystem::check_collision_oriented(
+            lying.position,
+            lying.up(),
+            &capsule,
+            Vector3::new(2.0, 0.0, 0.0),
+            Vector3::up(),
+            &capsule,
+        )
+        .is_some());
+    }
+
+    #[test]
+    fn test_capsule_standing_on_box_uses_its_bottom_cap() {
+        let floor = ColliderShape::Box { size: Vector3::new(10.0, 1.0, 10.0) };
+        let capsule = ColliderShape::Capsule { radius: 0.5, height: 2.0 };
+
+        // Capsule bottom at y = 0.4, floor top at y = 0.5
+        let (normal, penetration) = CollisionSystem::check_collision(
+            Vector3::zero(),
+            &floor,
+            Vector3::new(0.0, 1.4, 0.0),
+            &capsule,
+        )
+        .unwrap();
+        assert!(normal.y > 0.99);
+        assert!((penetration - 0.1).abs() < 1e-3);
+
+        let (normal, _) = CollisionSystem::check_collision(
+            Vector3::new(0.0, 1.4, 0.0),
+            &capsule,
+            Vector3::zero(),
+            &floor,
+        )
+        .unwrap();
+        assert!(normal.y < -0.99);
+
+        assert!(CollisionSystem::check_collision(
+            Vector3::zero(),
+            &floor,
+            Vector3::new(0.0, 1.6, 0.0),
+            &capsule,
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_fixed_timestep_matches_across_frame_rates() {
+        let run = |frame_dt: f32, frames: usize| {
+            let mut world = World::new();
+            let mut body = Rigidbody::new(1.0);
+            body.velocity = Vector3::new(3.0, 5.0, 0.0);
+            let id = spawn_box(&mut world, Vector3::zero(), Vector3::one(), body);
+
+            let mut physics = PhysicsSystem::default().with_fixed_timestep(1.0 / 120.0);
+            for _ in 0..frames {
+                physics.update(&mut world, frame_dt);
+            }
+            world.get_entity(id).unwrap().transform.unwrap().position
+        };
+
+        // One second at 30 and at 144 FPS
+        let slow = run(1.0 / 30.0, 30);
+        let fast = run(1.0 / 144.0, 144);
+        assert!((slow - fast).length() < 1e-3, "{:?} vs {:?}", slow, fast);
+        assert!(slow.x > 2.5);
+    }
+
+    #[test]
+    fn test_continuous_collision_stops_fast_box_at_thin_wall() {
+        let mut world = World::new();
+        let wall = world.spawn_static(
+            Vector3::new(5.0, 0.0, 0.0),
+            ColliderShape::Box { size: Vector3::new(0.1, 4.0, 4.0) },
+            Color::GRAY,
+        );
+        let mut body = Rigidbody::new(1.0).with_gravity(false);
+        body.velocity = Vector3::new(600.0, 0.0, 0.0);
+        let bullet = spawn_box(&mut world, Vector3::zero(), Vector3::new(0.2, 0.2, 0.2), body);
+
+        let mut physics = PhysicsSystem::default();
+        let mut collisions = CollisionSystem::new().with_continuous(true);
+        // First update only records where everything starts
+        collisions.update(&mut world, 1.0 / 60.0);
+        // One step moves the box 10 units, straight past the wall
+        physics.update(&mut world, 1.0 / 60.0);
+        collisions.update(&mut world, 1.0 / 60.0);
+
+        let hit = world.get_entity(bullet).unwrap();
+        let position = hit.transform.unwrap().position;
+        assert!((position.x - 4.85).abs() < 1e-3, "stopped at {:?}", position);
+        assert!(hit.rigidbody.unwrap().velocity.x.abs() < 1e-4);
+        assert_eq!(collisions.stats().swept_hits, 1);
+        assert_eq!(collisions.swept_collisions()[0].entity_b, wall);
+        assert!(collisions.swept_collisions()[0].normal.x > 0.99);
+
+        // Without the continuous pass the same shot tunnels through
+        let mut world = World::new();
+        world.spawn_static(
+            Vector3::new(5.0, 0.0, 0.0),
+            ColliderShape::Box { size: Vector3::new(0.1, 4.0, 4.0) },
+            Color::GRAY,
+        );
+        let bullet = spawn_box(&mut world, Vector3::zero(), Vector3::new(0.2, 0.2, 0.2), body);
+        physics.update(&mut world, 1.0 / 60.0);
+        CollisionSystem::new().update(&mut world, 1.0 / 60.0);
+        assert!(world.get_entity(bullet).unwrap().transform.unwrap().position.x > 9.0);
+    }
+
+    #[test]
+    fn test_continuous_collision_stops_velocity_projectile() {
+        use crate::ecs::components::Velocity;
+        use crate::ecs::systems::MovementSystem;
+
+        let mut world = World::new();
+        world.spawn_static(
+            Vector3::new(5.0, 0.0, 0.0),
+            ColliderShape::Box { size: Vector3::new(0.1, 4.0, 4.0) },
+            Color::GRAY,
+        );
+        // No rigidbody: moved by MovementSystem like pooled and scripted projectiles
+        let bullet = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_velocity(Velocity { linear: Vector3::new(600.0, 0.0, 0.0), angular: Vector3::zero() })
+            .with_collider(Collider::sphere_collider(0.1))
+            .build();
+
+        let mut collisions = CollisionSystem::new().with_continuous(true);
+        collisions.update(&mut world, 1.0 / 60.0);
+        MovementSystem.update(&mut world, 1.0 / 60.0);
+        collisions.update(&mut world, 1.0 / 60.0);
+
+        let hit = world.get_entity(bullet).unwrap();
+        assert!((hit.transform.unwrap().position.x - 4.85).abs() < 1e-3, "{:?}", hit.transform);
+        assert!(hit.velocity.unwrap().linear.x.abs() < 1e-4);
+        assert_eq!(collisions.stats().swept_hits, 1);
+    }
+
+    #[test]
+    fn test_torque_spins_body() {
+        let mut world = World::new();
+        let id = spawn_box(
+            &mut world,
+            Vector3::zero(),
+            Vector3::one(),
+            Rigidbody::new(1.0).with_gravity(false).with_inertia(2.0).with_angular_drag(0.0),
+        );
+        world.get_entity_mut(id).unwrap().rigidbody.as_mut().unwrap().add_torque(Vector3::new(0.0, 4.0, 0.0));
+
+        let mut physics = PhysicsSystem::default().with_fixed_timestep(0.5);
+        physics.update(&mut world, 0.5);
+
+        let entity = world.get_entity(id).unwrap();
+        let body = entity.rigidbody.unwrap();
+        // a = 4 / 2 = 2 rad/s^2 for half a second
+        assert!((body.angular_velocity.y - 1.0).abs() < 1e-5);
+        assert!((entity.transform.unwrap().rotation.y - 0.5).abs() < 1e-5);
+        assert_eq!(body.torque, Vector3::zero());
+
+        // Torque was consumed; the body keeps spinning at a steady rate
+        physics.update(&mut world, 0.5);
+        let rotation = world.get_entity(id).unwrap().transform.unwrap().rotation;
+        assert!((rotation.y - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_friction_at_contact_spins_sliding_sphere() {
+        let mut world = World::new();
+        world.spawn_static(Vector3::zero(), ColliderShape::Box { size: Vector3::new(20.0, 1.0, 20.0) }, Color::GRAY);
+        let ball = world.spawn_prop(Vector3::new(0.0, 0.95, 0.0), ColliderShape::Sphere { radius: 0.5 }, Color::RED);
+        {
+            let body = world.get_entity_mut(ball).unwrap().rigidbody.as_mut().unwrap();
+            body.velocity = Vector3::new(3.0, -1.0, 0.0);
+        }
+
+        CollisionSystem::new().update(&mut world, 1.0 / 60.0);
+
+        // Sliding along +x on the floor rolls it forward: spin around -z
+        let spin = world.get_entity(ball).unwrap().rigidbody.unwrap().angular_velocity;
+        assert!(spin.z < 0.0, "{:?}", spin);
+    }
+
+    #[test]
+    fn test_bouncy_sphere_rebounds_off_floor() {
+        let drop = |bouncy: Option<Bouncy>| {
+            let mut world = World::new();
+            world.spawn_static(Vector3::zero(), ColliderShape::Box { size: Vector3::new(20.0, 1.0, 20.0) }, Color::GRAY);
+            let ball = world.spawn_prop(Vector3::new(0.0, 0.95, 0.0), ColliderShape::Sphere { radius: 0.5 }, Color::RED);
+            let entity = world.get_entity_mut(ball).unwrap();
+            entity.rigidbody.as_mut().unwrap().velocity = Vector3::new(0.0, -4.0, 0.0);
+            entity.bouncy = bouncy;
+
+            CollisionSystem::new().update(&mut world, 1.0 / 60.0);
+            world.get_entity(ball).unwrap().rigidbody.unwrap().velocity.y
+        };
+
+        // The floor and ball colliders have no restitution of their own
+        assert!(drop(None).abs() < 1e-4);
+        let rebound = drop(Some(Bouncy::new(1.0)));
+        assert!(rebound > 1.0, "{}", rebound);
+    }
+
+    #[test]
+    fn test_body_settles_on_terrain_hill() {
+        use crate::terrain::TerrainConfig;
+
+        // A ridge along z: 8 high at x = 0, dropping 0.5 per unit to either side
+        let mut terrain = Terrain::generate(TerrainConfig { width: 64, depth: 64, ..Default::default() });
+        for x in 0..64 {
+            let (world_x, _) = terrain.grid_to_world(x as f32, 0.0);
+            terrain.heightmap[x].fill(8.0 - 0.5 * world_x.abs());
+        }
+        let terrain = Rc::new(terrain);
+
+        let mut world = World::new();
+        let ball = world.spawn_prop(Vector3::new(2.0, 12.0, 0.0), ColliderShape::Sphere { radius: 0.5 }, Color::RED);
+        let mut physics = PhysicsSystem::default().with_terrain(Rc::clone(&terrain));
+
+        for _ in 0..60 {
+            physics.update(&mut world, 1.0 / 60.0);
+        }
+        let position = world.get_entity(ball).unwrap().transform.unwrap().position;
+        let body = world.get_entity(ball).unwrap().rigidbody.unwrap();
+        assert!(body.is_grounded);
+        assert!((position.y - (terrain.get_height_at(position.x, position.z) + 0.5)).abs() < 1e-3, "{:?}", position);
+
+        // Resting on the slope turns the fall into sliding downhill, along the surface
+        assert!(position.x > 2.0);
+        assert!(body.velocity.x > 0.0 && body.velocity.y < 0.0);
+        assert!(body.velocity.dot(terrain.get_normal_at(position.x, position.z)).abs() < 0.2);
+    }
+
+    #[test]
+    fn test_collision_layers_filter_pairs() {
+        const PLAYER: u32 = 1 << 1;
+        const BULLETS: u32 = 1 << 2;
+
+        let mut world = World::new();
+        let spawn = |world: &mut World, x: f32, collider: Collider| {
+            world
+                .spawn()
+                .with_transform(Transform::new(Vector3::new(x, 0.0, 0.0)))
+                .with_rigidbody(Rigidbody::new(1.0).with_gravity(false))
+                .with_collider(collider)
+                .build()
+        };
+        // Player and their bullet overlap each other and the enemy
+        let player = spawn(&mut world, 0.0, Collider::box_collider(Vector3::one()).on_layer(PLAYER));
+        let bullet = spawn(
+            &mut world,
+            0.5,
+            Collider::box_collider(Vector3::one()).on_layer(BULLETS).collides_with(!PLAYER),
+        );
+        let enemy = spawn(&mut world, 1.0, Collider::box_collider(Vector3::one()));
+
+        assert!(!world.get_entity(player).unwrap().collider.unwrap()
+            .can_collide_with(&world.get_entity(bullet).unwrap().collider.unwrap()));
+
+        let mut system = CollisionSystem::new();
+        system.update(&mut world, 1.0 / 60.0);
+
+        // Only bullet-enemy resolves: the player and enemy merely touch
+        assert_eq!(system.stats().collisions_resolved, 1);
+        assert_eq!(world.get_entity(player).unwrap().transform.unwrap().position.x, 0.0);
+        assert!(world.get_entity(enemy).unwrap().transform.unwrap().position.x > 1.0);
+    }
+
+    #[test]
+    fn test_trigger_enter_stay_exit() {
+        let mut world = World::new();
+        let zone = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_collider(Collider::box_collider(Vector3::new(2.0, 2.0, 2.0)).as_trigger())
+            .build();
+        let ball = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::new(5.0, 0.0, 0.0)))
+            .with_collider(Collider::sphere_collider(0.5))
+            .build();
+        let mut system = CollisionSystem::new();
+        let mut step_to = |world: &mut World, x: f32| {
+            world.get_entity_mut(ball).unwrap().transform.as_mut().unwrap().position.x = x;
+            system.update(world, 1.0 / 60.0);
+            system.triggers().iter().map(|e| (e.entity_a, e.entity_b, e.kind)).collect::<Vec<_>>()
+        };
+
+        assert!(step_to(&mut world, 5.0).is_empty());
+        assert_eq!(step_to(&mut world, 1.2), vec![(zone, ball, TriggerKind::Enter)]);
+        assert_eq!(step_to(&mut world, 0.0), vec![(zone, ball, TriggerKind::Stay)]);
+        // The trigger never pushed the ball out
+        assert_eq!(world.get_entity(ball).unwrap().transform.unwrap().position.x, 0.0);
+        assert_eq!(step_to(&mut world, 5.0), vec![(zone, ball, TriggerKind::Exit)]);
+        assert!(step_to(&mut world, 5.0).is_empty());
+    }
+
+    #[test]
+    fn test_trigger_exit_fires_under_a_binding_pair_budget() {
+        let mut world = World::new();
+        let zone = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_collider(Collider::box_collider(Vector3::new(2.0, 2.0, 2.0)).as_trigger())
+            .build();
+        let ball = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_collider(Collider::sphere_collider(0.5))
+            .build();
+        // A pile of static boxes elsewhere keeps the budget binding every frame
+        for z in [0.0, 0.5, 1.0] {
+            world
+                .spawn()
+                .with_transform(Transform::new(Vector3::new(20.0, 0.0, z)))
+                .with_collider(Collider::box_collider(Vector3::one()))
+                .build();
+        }
+        let mut system = CollisionSystem::new().with_pair_budget(1);
+        let mut events = Vec::new();
+        for _ in 0..3 {
+            system.update(&mut world, 1.0 / 60.0);
+            events.extend(system.triggers().iter().map(|e| (e.entity_a, e.entity_b, e.kind)));
+        }
+        assert!(events.contains(&(zone, ball, TriggerKind::Enter)));
+
+        world.get_entity_mut(ball).unwrap().transform.as_mut().unwrap().position.x = 5.0;
+        events.clear();
+        for _ in 0..6 {
+            system.update(&mut world, 1.0 / 60.0);
+            assert!(system.stats().budget_exhausted);
+            events.extend(system.triggers().iter().map(|e| (e.entity_a, e.entity_b, e.kind)));
+        }
+        assert_eq!(events.iter().filter(|e| e.2 == TriggerKind::Exit).count(), 1);
+        assert_eq!(events.last(), Some(&(zone, ball, TriggerKind::Exit)));
+    }
+
+    #[test]
+    fn test_static_body_not_integrated() {
+        let mut world = World::new();
+        let mut wall = Rigidbody::static_body();
+        wall.velocity = Vector3::new(5.0, 0.0, 0.0);
+        let wall_id = spawn_box(&mut world, Vector3::zero(), Vector3::one(), wall);
+
+        PhysicsSystem::default().update(&mut world, 1.0);
+
+        assert_eq!(world.get_entity(wall_id).unwrap().transform.unwrap().position, Vector3::zero());
+        assert!(Rigidbody::new(0.0).is_static());
+    }
+
+    fn overlapping_pile(n: usize) -> World {
+        let mut world = World::new();
+        for i in 0..n {
+            let offset = Vector3::new(i as f32 * 0.1, 0.0, 0.0);
+            spawn_box(&mut world, offset, Vector3::one(), Rigidbody::new(1.0).with_gravity(false));
+        }
+        // Far away: tested against everyone but rejected by the broadphase
+        spawn_box(&mut world, Vector3::new(50.0, 0.0, 0.0), Vector3::one(), Rigidbody::new(1.0));
+        world
+    }
+
+    #[test]
+    fn test_collision_stats_count_pairs() {
+        let n = 5;
+        let mut world = overlapping_pile(n);
+        let mut collisions = CollisionSystem::new();
+        collisions.update(&mut world, 1.0 / 60.0);
+
+        let overlapping_pairs = n * (n - 1) / 2;
+        let stats = collisions.stats();
+        assert_eq!(stats.pairs_tested, (n + 1) * n / 2);
+        assert_eq!(stats.broadphase_pairs, overlapping_pairs);
+        assert_eq!(stats.collisions_resolved, overlapping_pairs);
+        assert!(!stats.budget_exhausted);
+
+        let mut world = overlapping_pile(n);
+        let mut budgeted = CollisionSystem::new().with_pair_budget(4);
+        budgeted.update(&mut world, 1.0 / 60.0);
+        assert_eq!(budgeted.stats().broadphase_pairs, 4);
+        assert_eq!(budgeted.stats().collisions_resolved, 4);
+        assert!(budgeted.stats().budget_exhausted);
+    }
+
+    #[test]
+    fn test_pair_budget_rotates_through_all_pairs() {
+        let n = 5;
+        let mut world = overlapping_pile(n);
+        for entity in world.entities_mut() {
+            entity.collider.as_mut().unwrap().is_trigger = true;
+        }
+        let mut budgeted = CollisionSystem::new().with_pair_budget(4);
+
+        // Triggers report each overlapping pair the first time it's tested
+        let mut seen = BTreeSet::new();
+        for _ in 0..3 {
+            budgeted.update(&mut world, 1.0 / 60.0);
+            seen.extend(
+                budgeted
+                    .triggers()
+                    .iter()
+                    .filter(|event| event.kind == TriggerKind::Enter)
+                    .map(|event| (event.entity_a, event.entity_b)),
+            );
+        }
+        assert_eq!(seen.len(), n * (n - 1) / 2);
     }
 }
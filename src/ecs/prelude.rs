@@ -0,0 +1,60 @@
+//! Curated ECS imports
+//!
+//! `use crate::ecs::prelude::*` brings in the world, the common components and the
+//! core systems without colliding with `raylib::prelude::*`. Components whose names
+//! clash with raylib (`Transform`, `Camera`, `Model`) are exported with an `Ecs`
+//! prefix.
+//!
+//! The game itself only uses part of it, so unused re-exports are allowed here.
+
+#![allow(unused_imports)]
+
+pub use super::aabb::{Aabb, Aabb2};
+pub use super::bindings::{is_action_down, is_action_pressed, InputAction, KeyBindings, KeyState};
+pub use super::combat_text::{CombatTextSystem, FloatingText};
+pub use super::components::{
+    BodyType, Camera as EcsCamera, CameraShake, Collider, ColliderShape, EnemyTag, Health,
+    Model as EcsModel, MovingPlatform, Name, PlayerTag, RenderShape, Renderable, Rigidbody,
+    Selected, Transform as EcsTransform, Velocity,
+};
+pub use super::entity::{ComponentMask, Entity, EntityBuilder, EntityId, World};
+pub use super::physics::{
+    Collision, CollisionSystem, MovingPlatformSystem, PhysicsSystem, TerrainCollisionSystem,
+    TriggerEvent, TriggerKind,
+};
+pub use super::frustum::Frustum;
+pub use super::raycast::RayHit;
+pub use super::simulation::Simulation;
+pub use super::systems::{
+    FirstPersonCameraSystem, GameMode, InputConfig, InputState, MovementSystem, PlayerInputSystem, RenderSystem,
+    SelectionSystem, System,
+};
+
+#[cfg(test)]
+mod tests {
+    // Both preludes are glob imported together; any name clash would make the
+    // uses below ambiguous and fail to compile
+    use raylib::prelude::*;
+    use super::*;
+
+    #[test]
+    fn test_prelude_coexists_with_raylib_prelude() {
+        let mut world = World::new();
+        let id = world
+            .spawn()
+            .with_transform(EcsTransform::new(Vector3::new(1.0, 2.0, 3.0)))
+            .with_renderable(Renderable::cube(Vector3::one(), Color::RED))
+            .with_camera(EcsCamera::new(75.0))
+            .as_player()
+            .build();
+
+        let entity = world.get_entity(id).unwrap();
+        assert!(entity.component_mask().contains(ComponentMask::TRANSFORM | ComponentMask::CAMERA));
+        assert!(entity.is_player);
+
+        // The raylib types stay reachable under their own names
+        let raylib_camera = Camera3D::perspective(Vector3::zero(), Vector3::one(), Vector3::up(), 60.0);
+        assert_eq!(raylib_camera.fovy, 60.0);
+        let _marker: PlayerTag = PlayerTag;
+    }
+}
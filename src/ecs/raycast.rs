@@ -0,0 +1,197 @@
+//! Ray queries against entity colliders
+//!
+//! `World::raycast` finds the first collider along a ray, for mouse picking and
+//! line-of-sight checks. Rays only hit shapes from the outside: a shape containing
+//! the ray origin (e.g. the caster's own collider) is ignored.
+
+use raylib::prelude::Vector3;
+use super::aabb::Aabb;
+use super::components::{ColliderShape, Transform};
+use super::entity::{EntityId, World};
+use super::physics::CollisionSystem;
+
+/// Where a ray hit an entity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    pub entity: EntityId,
+    /// Distance from the ray origin to `point`
+    pub distance: f32,
+    pub point: Vector3,
+    /// Surface normal at `point`
+    pub normal: Vector3,
+}
+
+/// Entry distance of a ray into a sphere (`direction` normalized)
+fn ray_sphere(origin: Vector3, direction: Vector3, center: Vector3, radius: f32) -> Option<f32> {
+    let offset = origin - center;
+    let b = offset.dot(direction);
+    let c = offset.dot(offset) - radius * radius;
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let t = -b - discriminant.sqrt();
+    (t > 0.0).then_some(t)
+}
+
+/// Entry distance of a ray into a capsule around the segment `top`-`bottom`
+/// (`direction` normalized)
+fn ray_capsule(origin: Vector3, direction: Vector3, top: Vector3, bottom: Vector3, radius: f32) -> Option<f32> {
+    let axis = bottom - top;
+    let axis_sq = axis.dot(axis);
+    if axis_sq <= f32::EPSILON {
+        return ray_sphere(origin, direction, top, radius);
+    }
+
+    // Infinite cylinder around the axis first, then the end caps
+    let offset = origin - top;
+    let axis_dir = axis.dot(direction);
+    let axis_offset = axis.dot(offset);
+    let a = axis_sq - axis_dir * axis_dir;
+    let b = axis_sq * direction.dot(offset) - axis_offset * axis_dir;
+    let c = axis_sq * offset.dot(offset) - axis_offset * axis_offset - radius * radius * axis_sq;
+    let discriminant = b * b - a * c;
+    if a > f32::EPSILON && discriminant >= 0.0 {
+        let t = (-b - discriminant.sqrt()) / a;
+        let along = axis_offset + t * axis_dir;
+        if t > 0.0 && along > 0.0 && along < axis_sq {
+            return Some(t);
+        }
+    }
+
+    [top, bottom]
+        .into_iter()
+        .filter_map(|cap| ray_sphere(origin, direction, cap, radius))
+        .min_by(f32::total_cmp)
+}
+
+/// Intersect a ray with one collider shape, returning `(distance, normal)`
+fn ray_shape(
+    origin: Vector3,
+    direction: Vector3,
+    transform: &Transform,
+    shape: &ColliderShape,
+) -> Option<(f32, Vector3)> {
+    let position = transform.position;
+    match *shape {
+        ColliderShape::Box { size } => {
+            let bounds = Aabb::from_center_size(position, size);
+            let t = bounds.ray_intersection(origin, direction).filter(|t| *t > 0.0)?;
+            Some((t, bounds.face_normal(origin + direction * t)))
+        }
+        ColliderShape::Sphere { radius } => {
+            let t = ray_sphere(origin, direction, position, radius)?;
+            Some((t, ((origin + direction * t) - position).normalized()))
+        }
+        ColliderShape::Capsule { radius, height } => {
+            let (top, bottom) = CollisionSystem::capsule_segment(position, transform.up(), radius, height);
+            let t = ray_capsule(origin, direction, top, bottom, radius)?;
+            let point = origin + direction * t;
+            let axis_point = {
+                let axis = bottom - top;
+                let along = ((point - top).dot(axis) / axis.dot(axis).max(f32::EPSILON)).clamp(0.0, 1.0);
+                top + axis * along
+            };
+            Some((t, (point - axis_point).normalized()))
+        }
+    }
+}
+
+impl World {
+    /// Nearest collider hit by the ray within `max_dist`
+    ///
+    /// `dir` doesn't need to be normalized. Disabled entities are skipped.
+    pub fn raycast(&self, origin: Vector3, dir: Vector3, max_dist: f32) -> Option<RayHit> {
+        self.raycast_all(origin, dir, max_dist).into_iter().next()
+    }
+
+    /// Every collider hit by the ray within `max_dist`, nearest first
+    pub fn raycast_all(&self, origin: Vector3, dir: Vector3, max_dist: f32) -> Vec<RayHit> {
+        if dir.length() <= f32::EPSILON {
+            return Vec::new();
+        }
+        let direction = dir.normalized();
+
+        let mut hits: Vec<RayHit> = self
+            .enabled_entities()
+            .filter_map(|entity| {
+                let transform = entity.transform.as_ref()?;
+                let collider = entity.collider.as_ref()?;
+                let (distance, normal) = ray_shape(origin, direction, transform, &collider.shape)?;
+                (distance <= max_dist).then(|| RayHit {
+                    entity: entity.id,
+                    distance,
+                    point: origin + direction * distance,
+                    normal,
+                })
+            })
+            .collect();
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::components::Collider;
+
+    fn spawn(world: &mut World, position: Vector3, collider: Collider) -> EntityId {
+        world.spawn().with_transform(Transform::new(position)).with_collider(collider).build()
+    }
+
+    #[test]
+    fn test_raycast_miss_returns_none() {
+        let mut world = World::new();
+        spawn(&mut world, Vector3::new(0.0, 0.0, -5.0), Collider::sphere_collider(1.0));
+
+        assert!(world.raycast(Vector3::zero(), Vector3::new(0.0, 0.0, 1.0), 100.0).is_none());
+        assert!(world.raycast(Vector3::zero(), Vector3::new(0.0, 1.0, 0.0), 100.0).is_none());
+        // In the right direction but out of range
+        assert!(world.raycast(Vector3::zero(), Vector3::new(0.0, 0.0, -1.0), 3.0).is_none());
+    }
+
+    #[test]
+    fn test_raycast_hits_sphere() {
+        let mut world = World::new();
+        let near = spawn(&mut world, Vector3::new(0.0, 0.0, -5.0), Collider::sphere_collider(1.0));
+        let far = spawn(&mut world, Vector3::new(0.0, 0.0, -10.0), Collider::sphere_collider(1.0));
+
+        let hit = world.raycast(Vector3::zero(), Vector3::new(0.0, 0.0, -2.0), 100.0).unwrap();
+        assert_eq!(hit.entity, near);
+        assert!((hit.distance - 4.0).abs() < 1e-4);
+        assert!((hit.point.z + 4.0).abs() < 1e-4);
+        assert!((hit.normal.z - 1.0).abs() < 1e-4);
+
+        let all = world.raycast_all(Vector3::zero(), Vector3::new(0.0, 0.0, -1.0), 100.0);
+        assert_eq!(all.iter().map(|h| h.entity).collect::<Vec<_>>(), vec![near, far]);
+    }
+
+    #[test]
+    fn test_raycast_passes_between_boxes() {
+        let mut world = World::new();
+        spawn(&mut world, Vector3::new(-1.0, 0.0, -5.0), Collider::box_collider(Vector3::one()));
+        spawn(&mut world, Vector3::new(1.0, 0.0, -5.0), Collider::box_collider(Vector3::one()));
+
+        assert!(world.raycast(Vector3::zero(), Vector3::new(0.0, 0.0, -1.0), 100.0).is_none());
+
+        let hit = world.raycast(Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 0.0, -1.0), 100.0).unwrap();
+        assert!((hit.distance - 4.5).abs() < 1e-4);
+        assert_eq!(hit.normal, Vector3::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_raycast_hits_capsule_side_and_cap() {
+        let mut world = World::new();
+        let capsule = spawn(&mut world, Vector3::new(0.0, 0.0, -5.0), Collider::capsule_collider(0.5, 3.0));
+
+        let side = world.raycast(Vector3::new(0.0, 0.5, 0.0), Vector3::new(0.0, 0.0, -1.0), 100.0).unwrap();
+        assert_eq!(side.entity, capsule);
+        assert!((side.distance - 4.5).abs() < 1e-4);
+
+        // Straight down onto the top cap
+        let cap = world.raycast(Vector3::new(0.0, 10.0, -5.0), Vector3::new(0.0, -1.0, 0.0), 100.0).unwrap();
+        assert!((cap.point.y - 1.5).abs() < 1e-4);
+        assert!((cap.normal.y - 1.0).abs() < 1e-4);
+    }
+}
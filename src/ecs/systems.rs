@@ -1,7 +1,12 @@
 use raylib::prelude::*;
 use std::collections::HashMap;
-use super::entity::World;
-use super::components::{Transform, RenderShape, ColliderShape};
+use std::ffi::CString;
+use super::entity::{Entity, EntityId, World};
+use super::components::{Transform, RenderShape, Renderable, ColliderShape, Collider, Rigidbody, Selected};
+use super::batching::{group_batches, BatchKey, BatchMeshCache, RenderBatch};
+use super::frustum::Frustum;
+use super::bindings::{is_action_down, is_action_pressed, InputAction, KeyBindings, KeyState};
+use std::collections::HashSet;
 
 /// System trait - all systems implement this
 pub trait System {
@@ -9,57 +14,91 @@ pub trait System {
 }
 
 /// Cache for loaded models and textures to avoid reloading
+///
+/// Failed loads are remembered too, so a missing file is only tried once.
 pub struct ModelCache {
-    models: HashMap<String, raylib::ffi::Model>,
-    textures: HashMap<String, raylib::ffi::Texture2D>,
+    models: HashMap<String, Option<raylib::ffi::Model>>,
+    textures: HashMap<String, Option<raylib::ffi::Texture2D>>,
+    load_model: fn(&str) -> Option<raylib::ffi::Model>,
+    unload_model: fn(raylib::ffi::Model),
+}
+
+/// Load a model file with raylib, returning None if it couldn't be read
+fn load_model_file(path: &str) -> Option<raylib::ffi::Model> {
+    let path = CString::new(path).ok()?;
+    unsafe {
+        let model = raylib::ffi::LoadModel(path.as_ptr());
+        if model.meshes.is_null() {
+            return None; // Failed to load
+        }
+        Some(model)
+    }
+}
+
+fn unload_model_file(model: raylib::ffi::Model) {
+    unsafe {
+        raylib::ffi::UnloadModel(model);
+    }
 }
 
 impl ModelCache {
     pub fn new() -> Self {
+        Self::with_loader(load_model_file, unload_model_file)
+    }
+
+    /// Cache that loads and unloads models through the given functions
+    /// (e.g. an asset pipeline, or a fake loader in tests)
+    pub fn with_loader(
+        load_model: fn(&str) -> Option<raylib::ffi::Model>,
+        unload_model: fn(raylib::ffi::Model),
+    ) -> Self {
         Self {
             models: HashMap::new(),
             textures: HashMap::new(),
+            load_model,
+            unload_model,
         }
     }
 
     pub fn get_model(&mut self, path: &str) -> Option<raylib::ffi::Model> {
-        if !self.models.contains_key(path) {
-            // Try to load the model
-            unsafe {
-                let model = raylib::ffi::LoadModel(path.as_ptr() as *const i8);
-                if model.meshes.is_null() {
-                    return None; // Failed to load
-                }
-                self.models.insert(path.to_string(), model);
-            }
+        if let Some(model) = self.models.get(path) {
+            return *model;
         }
-        self.models.get(path).copied()
+        let model = (self.load_model)(path);
+        self.models.insert(path.to_string(), model);
+        model
+    }
+
+    /// Number of model paths that have been loaded (or tried)
+    pub fn model_count(&self) -> usize {
+        self.models.len()
     }
 
     pub fn get_texture(&mut self, path: &str) -> Option<raylib::ffi::Texture2D> {
-        if !self.textures.contains_key(path) {
-            // Try to load the texture
-            unsafe {
-                let texture = raylib::ffi::LoadTexture(path.as_ptr() as *const i8);
-                if texture.id == 0 {
-                    return None; // Failed to load
-                }
-                self.textures.insert(path.to_string(), texture);
-            }
+        if let Some(texture) = self.textures.get(path) {
+            return *texture;
         }
-        self.textures.get(path).copied()
+        let texture = CString::new(path).ok().and_then(|c_path| unsafe {
+            let texture = raylib::ffi::LoadTexture(c_path.as_ptr());
+            // A zero id means the load failed
+            (texture.id != 0).then_some(texture)
+        });
+        self.textures.insert(path.to_string(), texture);
+        texture
     }
 
     pub fn cleanup(&mut self) {
         // Unload all models and textures
         for (_, model) in self.models.drain() {
-            unsafe {
-                raylib::ffi::UnloadModel(model);
+            if let Some(model) = model {
+                (self.unload_model)(model);
             }
         }
         for (_, texture) in self.textures.drain() {
-            unsafe {
-                raylib::ffi::UnloadTexture(texture);
+            if let Some(texture) = texture {
+                unsafe {
+                    raylib::ffi::UnloadTexture(texture);
+                }
             }
         }
     }
@@ -76,16 +115,14 @@ pub struct MovementSystem;
 
 impl System for MovementSystem {
     fn update(&mut self, world: &mut World, delta_time: f32) {
-        for entity in world.entities_mut() {
-            if let (Some(transform), Some(velocity)) = (&mut entity.transform, &entity.velocity) {
-                transform.position.x += velocity.linear.x * delta_time;
-                transform.position.y += velocity.linear.y * delta_time;
-                transform.position.z += velocity.linear.z * delta_time;
-
-                transform.rotation.x += velocity.angular.x * delta_time;
-                transform.rotation.y += velocity.angular.y * delta_time;
-                transform.rotation.z += velocity.angular.z * delta_time;
-            }
+        for (_, transform, velocity) in world.with_transform_velocity_mut() {
+            transform.position.x += velocity.linear.x * delta_time;
+            transform.position.y += velocity.linear.y * delta_time;
+            transform.position.z += velocity.linear.z * delta_time;
+
+            transform.rotation.x += velocity.angular.x * delta_time;
+            transform.rotation.y += velocity.angular.y * delta_time;
+            transform.rotation.z += velocity.angular.z * delta_time;
         }
     }
 }
@@ -93,21 +130,261 @@ impl System for MovementSystem {
 /// Render system - draws all renderable entities
 pub struct RenderSystem {
     model_cache: ModelCache,
+    batch_cache: BatchMeshCache,
+    /// Draw repeated identical shapes from shared meshes
+    pub batching: bool,
+    /// Draw each batch with one `DrawMeshInstanced` call once an instance
+    /// shader is loaded (see `load_instance_shader`)
+    pub instancing: bool,
+    /// Camera distances where spheres and cylinders drop from the high to the
+    /// medium, and from the medium to the low tessellation tier
+    pub lod_distances: [f32; 2],
+    frustum_culling: bool,
+    /// View frustum and camera position from the last `set_camera`
+    frustum: Option<Frustum>,
+    camera_position: Option<Vector3>,
+    /// Entities skipped by the last culling pass
+    culled: HashSet<EntityId>,
+    /// Draw calls the last `render` spent on batches
+    batch_draw_calls: usize,
 }
 
 impl RenderSystem {
     pub fn new() -> Self {
         Self {
             model_cache: ModelCache::new(),
+            batch_cache: BatchMeshCache::new(),
+            batching: true,
+            instancing: true,
+            lod_distances: [15.0, 40.0],
+            frustum_culling: true,
+            frustum: None,
+            camera_position: None,
+            culled: HashSet::new(),
+            batch_draw_calls: 0,
         }
     }
 
-    pub fn render(&mut self, world: &World, d: &mut RaylibMode3D<RaylibDrawHandle>, show_bounding_boxes: bool) {
+    pub fn with_model_cache(mut self, model_cache: ModelCache) -> Self {
+        self.model_cache = model_cache;
+        self
+    }
+
+    pub fn model_cache(&self) -> &ModelCache {
+        &self.model_cache
+    }
+
+    /// Load the shader that reads per-instance transforms, enabling instanced
+    /// batches. Returns false if it couldn't be loaded
+    pub fn load_instance_shader(&mut self, vs_path: &str, fs_path: &str) -> bool {
+        self.batch_cache.load_instance_shader(vs_path, fs_path)
+    }
+
+    /// Load every model the world references up front, so the first frame that
+    /// shows one doesn't stall on disk access
+    pub fn preload_models(&mut self, world: &World) {
         for entity in world.entities() {
+            if let Some(Renderable { shape: RenderShape::Model { path }, .. }) = &entity.renderable {
+                self.model_cache.get_model(path);
+            }
+            if let Some(model) = &entity.model {
+                self.model_cache.get_model(&model.model_path);
+                if let Some(texture_path) = &model.texture_path {
+                    self.model_cache.get_texture(texture_path);
+                }
+            }
+        }
+    }
+
+    /// Draw a loaded model with the transform's position, rotation and scale
+    /// (times `scale`), optionally swapping in a diffuse texture
+    fn draw_model(
+        model: raylib::ffi::Model,
+        texture: Option<raylib::ffi::Texture2D>,
+        transform: &Transform,
+        scale: f32,
+        tint: Color,
+    ) {
+        unsafe {
+            // Set the diffuse texture for the first material
+            if let Some(texture) = texture
+                && !model.materials.is_null()
+                && model.materialCount > 0
+            {
+                let material = &mut *model.materials;
+                if !material.maps.is_null() {
+                    (*material.maps).texture = texture;
+                }
+            }
+
+            raylib::ffi::rlPushMatrix();
+            raylib::ffi::rlTranslatef(transform.position.x, transform.position.y, transform.position.z);
+            raylib::ffi::rlRotatef(transform.rotation.x.to_degrees(), 1.0, 0.0, 0.0);
+            raylib::ffi::rlRotatef(transform.rotation.y.to_degrees(), 0.0, 1.0, 0.0);
+            raylib::ffi::rlRotatef(transform.rotation.z.to_degrees(), 0.0, 0.0, 1.0);
+            raylib::ffi::rlScalef(transform.scale.x * scale, transform.scale.y * scale, transform.scale.z * scale);
+            raylib::ffi::DrawModel(model, Vector3::zero().into(), 1.0, tint.into());
+            raylib::ffi::rlPopMatrix();
+        }
+    }
+
+    /// Turn frustum culling on or off (e.g. to debug missing entities)
+    pub fn set_frustum_culling(&mut self, enabled: bool) {
+        self.frustum_culling = enabled;
+    }
+
+    pub fn frustum_culling(&self) -> bool {
+        self.frustum_culling
+    }
+
+    /// Set the clip distances the next `begin_mode3D` projects with
+    ///
+    /// raylib keeps them until they are set again, so call this before `begin_mode3D`.
+    pub fn apply_clip_planes(near: f32, far: f32) {
+        unsafe {
+            raylib::ffi::rlSetClipPlanes(near as f64, far as f64);
+        }
+    }
+
+    /// Set the camera the next `render` culls and picks LODs against, with the
+    /// same aspect ratio and clip distances as the projection
+    pub fn set_camera(&mut self, camera: &Camera3D, aspect: f32, near: f32, far: f32) {
+        self.frustum = Some(Frustum::from_camera(camera, aspect, near, far));
+        self.camera_position = Some(camera.position);
+    }
+
+    /// Sphere ring/slice and cylinder side count for a shape at `position`
+    ///
+    /// 24 closer than `lod_distances[0]`, 8 beyond `lod_distances[1]` and 16 in
+    /// between or when no camera has been set.
+    pub fn lod_segments(&self, position: Vector3) -> i32 {
+        let Some(camera_position) = self.camera_position else {
+            return 16;
+        };
+        let distance = position.distance_to(camera_position);
+        if distance < self.lod_distances[0] {
+            24
+        } else if distance < self.lod_distances[1] {
+            16
+        } else {
+            8
+        }
+    }
+
+    /// Work out which entities are outside the camera frustum
+    ///
+    /// Called by `render`; entities without a bounding sphere (models, or no
+    /// renderable) are never culled. Does nothing until `set_camera` is called.
+    pub fn update_culling(&mut self, world: &World) {
+        self.culled.clear();
+        let Some(frustum) = self.frustum.filter(|_| self.frustum_culling) else {
+            return;
+        };
+
+        for entity in world.enabled_entities() {
+            let (Some(transform), Some(renderable)) = (&entity.transform, &entity.renderable) else {
+                continue;
+            };
+            let Some(radius) = renderable.bounding_radius(transform.scale) else {
+                continue;
+            };
+            if !frustum.intersects_sphere(transform.position, radius) {
+                self.culled.insert(entity.id);
+            }
+        }
+    }
+
+    /// Number of entities skipped by the last `render`
+    pub fn culled_count(&self) -> usize {
+        self.culled.len()
+    }
+
+    pub fn is_culled(&self, id: EntityId) -> bool {
+        self.culled.contains(&id)
+    }
+
+    /// Draw calls the last `render` spent on batches; one per batch (per model
+    /// mesh) when instancing is active, one per entity otherwise
+    pub fn batch_draw_calls(&self) -> usize {
+        self.batch_draw_calls
+    }
+
+    /// Drop culled entities from a batch
+    fn cull_batch(&self, batch: RenderBatch) -> RenderBatch {
+        let (entities, positions) = batch
+            .entities
+            .into_iter()
+            .zip(batch.positions)
+            .filter(|(id, _)| !self.culled.contains(id))
+            .unzip();
+        RenderBatch { key: batch.key, entities, positions }
+    }
+
+    /// Draw a batch of untinted, unscaled model entities mesh by mesh
+    /// Returns the number of draw calls issued
+    fn draw_model_batch(&mut self, d: &mut RaylibMode3D<RaylibDrawHandle>, path: &str, batch: &RenderBatch) -> usize {
+        let Some(model) = self.model_cache.get_model(path) else {
+            // Placeholder cubes if the model fails to load
+            for position in &batch.positions {
+                d.draw_cube_v(*position, Vector3::one(), Color::MAGENTA);
+            }
+            return batch.len();
+        };
+        if model.meshes.is_null() || model.materials.is_null() {
+            return 0;
+        }
+
+        let transforms = batch.transforms();
+        let mut draw_calls = 0;
+        for i in 0..model.meshCount.max(0) as usize {
+            unsafe {
+                let mesh = *model.meshes.add(i);
+                let material_index = if model.meshMaterial.is_null() { 0 } else { *model.meshMaterial.add(i) };
+                let material = *model.materials.add(material_index.clamp(0, model.materialCount - 1) as usize);
+                draw_calls += self.batch_cache.draw_instances(mesh, material, &transforms, self.instancing);
+            }
+        }
+        draw_calls
+    }
+
+    /// Whether an entity's renderable should be drawn this frame
+    pub fn should_draw(entity: &Entity) -> bool {
+        entity.enabled && entity.renderable.as_ref().is_some_and(|r| r.visible)
+    }
+
+    pub fn render(&mut self, world: &World, d: &mut RaylibMode3D<RaylibDrawHandle>, show_bounding_boxes: bool) {
+        self.update_culling(world);
+
+        // Draw batched shapes first, remembering which entities they covered
+        let mut batched = HashSet::new();
+        self.batch_draw_calls = 0;
+        if self.batching {
+            for batch in group_batches(world, |position| self.lod_segments(position)) {
+                let batch = self.cull_batch(batch);
+                if batch.is_empty() {
+                    continue;
+                }
+                self.batch_draw_calls += match batch.model_path() {
+                    Some(path) => self.draw_model_batch(d, path, &batch),
+                    None => self.batch_cache.draw(&batch, self.instancing),
+                };
+                for position in &batch.positions {
+                    Self::draw_batch_wires(d, &batch.key, *position);
+                }
+                batched.extend(batch.entities);
+            }
+        }
+
+        for entity in world.enabled_entities() {
+            if self.culled.contains(&entity.id) {
+                continue;
+            }
             if let (Some(transform), Some(renderable)) = (&entity.transform, &entity.renderable) {
-                if !renderable.visible {
+                if !Self::should_draw(entity) || batched.contains(&entity.id) {
                     continue;
                 }
+                let tint = |base: Color| entity.tint.map_or(base, |t| t.apply(base));
+                let segments = self.lod_segments(transform.position);
 
                 // Helper function to draw rotated shapes
                 let draw_with_rotation = |d: &mut RaylibMode3D<RaylibDrawHandle>,
@@ -142,7 +419,7 @@ impl RenderSystem {
 
                 match &renderable.shape {
                     RenderShape::Cube { size, color } => {
-                        let c = Color::new(color[0], color[1], color[2], color[3]);
+                        let c = tint(Color::new(color[0], color[1], color[2], color[3]));
                         let has_rotation = transform.rotation.x.abs() > 0.001
                                         || transform.rotation.y.abs() > 0.001
                                         || transform.rotation.z.abs() > 0.001;
@@ -158,93 +435,63 @@ impl RenderSystem {
                         }
                     }
                     RenderShape::Sphere { radius, color } => {
-                        let c = Color::new(color[0], color[1], color[2], color[3]);
+                        let c = tint(Color::new(color[0], color[1], color[2], color[3]));
                         let has_rotation = transform.rotation.x.abs() > 0.001
                                         || transform.rotation.y.abs() > 0.001
                                         || transform.rotation.z.abs() > 0.001;
 
                         if has_rotation {
                             draw_with_rotation(d, transform, &|d| {
-                                d.draw_sphere(Vector3::zero(), *radius, c);
-                                d.draw_sphere_wires(Vector3::zero(), *radius, 16, 16, Color::BLACK);
+                                d.draw_sphere_ex(Vector3::zero(), *radius, segments, segments, c);
+                                d.draw_sphere_wires(Vector3::zero(), *radius, segments, segments, Color::BLACK);
                             });
                         } else {
-                            d.draw_sphere(transform.position, *radius, c);
-                            d.draw_sphere_wires(transform.position, *radius, 16, 16, Color::BLACK);
+                            d.draw_sphere_ex(transform.position, *radius, segments, segments, c);
+                            d.draw_sphere_wires(transform.position, *radius, segments, segments, Color::BLACK);
                         }
                     }
                     RenderShape::Cylinder { radius, height, color } => {
-                        let c = Color::new(color[0], color[1], color[2], color[3]);
+                        let c = tint(Color::new(color[0], color[1], color[2], color[3]));
                         let has_rotation = transform.rotation.x.abs() > 0.001
                                         || transform.rotation.y.abs() > 0.001
                                         || transform.rotation.z.abs() > 0.001;
 
                         if has_rotation {
                             draw_with_rotation(d, transform, &|d| {
-                                d.draw_cylinder(Vector3::zero(), *radius, *radius, *height, 16, c);
-                                d.draw_cylinder_wires(Vector3::zero(), *radius, *radius, *height, 16, Color::BLACK);
+                                d.draw_cylinder(Vector3::zero(), *radius, *radius, *height, segments, c);
+                                d.draw_cylinder_wires(Vector3::zero(), *radius, *radius, *height, segments, Color::BLACK);
                             });
                         } else {
-                            d.draw_cylinder(transform.position, *radius, *radius, *height, 16, c);
-                            d.draw_cylinder_wires(transform.position, *radius, *radius, *height, 16, Color::BLACK);
+                            d.draw_cylinder(transform.position, *radius, *radius, *height, segments, c);
+                            d.draw_cylinder_wires(transform.position, *radius, *radius, *height, segments, Color::BLACK);
                         }
                     }
-                    RenderShape::Model { path: _ } => {
-                        // TODO: Implement model loading and rendering
-                        // For now, just draw a placeholder cube
-                        d.draw_cube_v(transform.position, Vector3::one(), Color::MAGENTA);
-                    }
+                    RenderShape::Model { path } => match self.model_cache.get_model(path) {
+                        Some(model) => Self::draw_model(model, None, transform, 1.0, tint(Color::WHITE)),
+                        // Placeholder cube if the model fails to load
+                        None => d.draw_cube_v(transform.position, Vector3::one(), Color::MAGENTA),
+                    },
                 }
             }
 
             // Handle model component rendering
             if let (Some(transform), Some(model)) = (&entity.transform, &entity.model) {
-                // Load model and texture separately to avoid borrowing conflicts
-                let raylib_model = self.model_cache.get_model(&model.model_path);
-                let texture = if let Some(texture_path) = &model.texture_path {
-                    self.model_cache.get_texture(texture_path)
-                } else {
-                    None
-                };
-
-                if let Some(raylib_model) = raylib_model {
-                    // Apply texture if specified
-                    if let Some(texture) = texture {
-                        unsafe {
-                            // Set the diffuse texture for the first material
-                            if !raylib_model.materials.is_null() && raylib_model.materialCount > 0 {
-                                let material = &mut *raylib_model.materials;
-                                if !material.maps.is_null() {
-                                    let maps = &mut *material.maps;
-                                    (*maps).texture = texture;
-                                }
-                            }
-                        }
-                    }
+                let texture = model
+                    .texture_path
+                    .as_ref()
+                    .and_then(|texture_path| self.model_cache.get_texture(texture_path));
 
-                    // Draw the model with transform
-                    unsafe {
-                        raylib::ffi::rlPushMatrix();
-                        raylib::ffi::rlTranslatef(transform.position.x, transform.position.y, transform.position.z);
-                        raylib::ffi::rlRotatef(transform.rotation.x.to_degrees(), 1.0, 0.0, 0.0);
-                        raylib::ffi::rlRotatef(transform.rotation.y.to_degrees(), 0.0, 1.0, 0.0);
-                        raylib::ffi::rlRotatef(transform.rotation.z.to_degrees(), 0.0, 0.0, 1.0);
-                        raylib::ffi::rlScalef(transform.scale.x * model.scale, transform.scale.y * model.scale, transform.scale.z * model.scale);
-                        
-                        raylib::ffi::DrawModel(raylib_model, Vector3::zero().into(), model.scale, model.tint.into());
-                        
-                        raylib::ffi::rlPopMatrix();
-                    }
-                } else {
+                match self.model_cache.get_model(&model.model_path) {
+                    Some(raylib_model) => Self::draw_model(raylib_model, texture, transform, model.scale, model.tint),
                     // Fallback: draw a placeholder cube if model fails to load
-                    d.draw_cube_v(transform.position, Vector3::one(), Color::MAGENTA);
+                    None => d.draw_cube_v(transform.position, Vector3::one(), Color::MAGENTA),
                 }
             }
         }
 
         // Draw bounding boxes for entities with colliders if enabled
         if show_bounding_boxes {
-            for entity in world.entities() {
+            for entity in world.enabled_entities() {
                 if let (Some(transform), Some(collider)) = (&entity.transform, &entity.collider) {
                     let bounding_box_color = Color::new(0, 0, 139, 100); // Dark blue with transparency
                     
@@ -290,48 +537,280 @@ impl RenderSystem {
             }
         }
     }
+
+    /// Outline a batched shape the same way the per-entity path does
+    fn draw_batch_wires(d: &mut RaylibMode3D<RaylibDrawHandle>, key: &BatchKey, position: Vector3) {
+        match key {
+            BatchKey::Cube { size, .. } => {
+                let size = Vector3::new(f32::from_bits(size[0]), f32::from_bits(size[1]), f32::from_bits(size[2]));
+                d.draw_cube_wires_v(position, size, Color::BLACK);
+            }
+            BatchKey::Sphere { radius, segments, .. } => {
+                d.draw_sphere_wires(position, f32::from_bits(*radius), *segments, *segments, Color::BLACK);
+            }
+            BatchKey::Cylinder { radius, height, segments, .. } => {
+                let radius = f32::from_bits(*radius);
+                d.draw_cylinder_wires(position, radius, radius, f32::from_bits(*height), *segments, Color::BLACK);
+            }
+            BatchKey::Model { .. } => {}
+        }
+    }
+}
+
+/// Input mode - whether the mouse drives the camera or the UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    /// Cursor captured, mouse controls the camera
+    Playing,
+    /// Cursor free for clicking UI panels
+    Menu,
+}
+
+impl GameMode {
+    /// Whether mouse movement should be fed to the camera
+    pub fn consumes_look_input(&self) -> bool {
+        matches!(self, GameMode::Playing)
+    }
+
+    /// Whether the cursor should be hidden and locked to the window
+    pub fn captures_cursor(&self) -> bool {
+        matches!(self, GameMode::Playing)
+    }
+
+    /// Switch modes, enabling/disabling the cursor to match
+    pub fn apply(&mut self, new_mode: GameMode, rl: &mut RaylibHandle) {
+        if *self == new_mode {
+            return;
+        }
+
+        if new_mode.captures_cursor() {
+            rl.disable_cursor();
+        } else {
+            rl.enable_cursor();
+        }
+        *self = new_mode;
+    }
+}
+
+/// Snapshot of the player's controls for one frame
+///
+/// Plain data so the simulation can be driven without a window (see `Simulation`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InputState {
+    pub forward: bool,
+    pub back: bool,
+    pub left: bool,
+    pub right: bool,
+    pub up: bool,
+    pub down: bool,
+    pub sprint: bool,
+    /// Jump went down this frame (`up` stays set while it's held)
+    pub jump: bool,
+    /// Mouse movement since last frame, in pixels
+    pub look_delta: Vector2,
+    /// Analog movement (x = right, y = forward), each axis in [-1, 1]
+    pub move_axis: Vector2,
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self {
+            forward: false,
+            back: false,
+            left: false,
+            right: false,
+            up: false,
+            down: false,
+            sprint: false,
+            jump: false,
+            look_delta: Vector2::zero(),
+            move_axis: Vector2::zero(),
+        }
+    }
+}
+
+/// Gamepad tuning
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InputConfig {
+    /// Gamepad index to read
+    pub gamepad: i32,
+    /// Stick deflection below this is ignored
+    pub deadzone: f32,
+    /// Multiplier on left stick movement
+    pub move_sensitivity: f32,
+    /// Right stick look speed at full deflection, in mouse pixels per second
+    /// (the camera's `mouse_sensitivity` then converts them to degrees)
+    pub look_sensitivity: f32,
+}
+
+impl Default for InputConfig {
+    fn default() -> Self {
+        Self {
+            gamepad: 0,
+            deadzone: 0.15,
+            move_sensitivity: 1.0,
+            look_sensitivity: 1500.0,
+        }
+    }
+}
+
+impl InputConfig {
+    /// Radial deadzone: sticks inside `deadzone` read zero, the rest of the range
+    /// is rescaled to start from zero, and the result never exceeds length 1
+    pub fn apply_deadzone(&self, stick: Vector2) -> Vector2 {
+        let length = stick.length();
+        if !length.is_finite() || length <= self.deadzone {
+            return Vector2::zero();
+        }
+        let range = (1.0 - self.deadzone).max(f32::EPSILON);
+        let scaled = ((length - self.deadzone) / range).min(1.0);
+        stick * (scaled / length)
+    }
+}
+
+impl InputState {
+    /// Read the current keyboard, mouse and (if connected) gamepad state
+    /// with the default key bindings
+    pub fn from_raylib(rl: &RaylibHandle) -> Self {
+        Self::from_raylib_with_config(rl, &InputConfig::default(), &KeyBindings::default())
+    }
+
+    /// Movement keys held, according to the bindings
+    pub fn from_keys(keys: &impl KeyState, bindings: &KeyBindings) -> Self {
+        let down = |action: InputAction| is_action_down(bindings, action, keys);
+        Self {
+            jump: is_action_pressed(bindings, InputAction::Jump, keys),
+            forward: down(InputAction::MoveForward),
+            back: down(InputAction::MoveBack),
+            left: down(InputAction::MoveLeft),
+            right: down(InputAction::MoveRight),
+            up: down(InputAction::Jump),
+            down: down(InputAction::Descend),
+            sprint: down(InputAction::Sprint),
+            ..Default::default()
+        }
+    }
+
+    /// Read input, blending the gamepad in with keyboard and mouse
+    ///
+    /// Left stick moves, right stick looks, A jumps (`up`), B descends, and
+    /// clicking the left stick sprints.
+    pub fn from_raylib_with_config(rl: &RaylibHandle, config: &InputConfig, bindings: &KeyBindings) -> Self {
+        let mut input = Self {
+            look_delta: rl.get_mouse_delta(),
+            ..Self::from_keys(rl, bindings)
+        };
+
+        let pad = config.gamepad;
+        if rl.is_gamepad_available(pad) {
+            let axis = |axis: GamepadAxis| rl.get_gamepad_axis_movement(pad, axis);
+            let button = |button: GamepadButton| rl.is_gamepad_button_down(pad, button);
+            let button_pressed = |button: GamepadButton| rl.is_gamepad_button_pressed(pad, button);
+
+            // Stick up is negative Y
+            let left = config.apply_deadzone(Vector2::new(
+                axis(GamepadAxis::GAMEPAD_AXIS_LEFT_X),
+                -axis(GamepadAxis::GAMEPAD_AXIS_LEFT_Y),
+            ));
+            input.move_axis = left * config.move_sensitivity;
+
+            let right = config.apply_deadzone(Vector2::new(
+                axis(GamepadAxis::GAMEPAD_AXIS_RIGHT_X),
+                axis(GamepadAxis::GAMEPAD_AXIS_RIGHT_Y),
+            ));
+            input.look_delta += right * (config.look_sensitivity * rl.get_frame_time());
+
+            input.up |= button(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN);
+            input.jump |= button_pressed(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN);
+            input.down |= button(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT);
+            input.sprint |= button(GamepadButton::GAMEPAD_BUTTON_LEFT_THUMB);
+        }
+
+        input
+    }
 }
 
 /// First-person camera control system
 pub struct FirstPersonCameraSystem;
 
 impl FirstPersonCameraSystem {
-    pub fn update(&self, world: &mut World, rl: &RaylibHandle) {
-        for entity in world.entities_mut() {
-            if let Some(camera) = &mut entity.camera {
-                // Mouse look controls
-                let mouse_delta = rl.get_mouse_delta();
+    pub fn update(&self, world: &mut World, rl: &RaylibHandle, mode: GameMode) {
+        self.apply_input(world, &InputState::from_raylib(rl), mode, rl.get_frame_time());
+    }
 
-                camera.yaw += mouse_delta.x * camera.mouse_sensitivity;
-                camera.pitch -= mouse_delta.y * camera.mouse_sensitivity;
+    pub fn apply_input(&self, world: &mut World, input: &InputState, mode: GameMode, delta_time: f32) {
+        let sprint_held = input.sprint;
 
-                // Clamp pitch to avoid gimbal lock
-                camera.pitch = camera.pitch.clamp(-89.0, 89.0);
+        for entity in world.entities_mut() {
+            // Horizontal movement speed drives head-bob
+            let linear = entity
+                .velocity
+                .map(|v| v.linear)
+                .or(entity.rigidbody.map(|rb| rb.velocity))
+                .unwrap_or(Vector3::zero());
+            let horizontal_speed = (linear.x * linear.x + linear.z * linear.z).sqrt();
 
-                // Normalize yaw to 0-360 range
-                if camera.yaw > 360.0 {
-                    camera.yaw -= 360.0;
-                } else if camera.yaw < 0.0 {
-                    camera.yaw += 360.0;
+            if let Some(camera) = &mut entity.camera {
+                // Mouse look controls (ignored while a menu has the cursor)
+                if mode.consumes_look_input() {
+                    camera.apply_look(input.look_delta.x, input.look_delta.y, delta_time);
                 }
+
+                // Head-bob and sprint FOV kick
+                let sprinting = sprint_held && entity.is_player && horizontal_speed > 0.01;
+                camera.update_effects(horizontal_speed, sprinting, delta_time);
             }
         }
     }
 }
 
 /// Simple player input system
-pub struct PlayerInputSystem;
+pub struct PlayerInputSystem {
+    /// How quickly horizontal velocity reaches the input direction on the ground (units/s²)
+    pub ground_acceleration: f32,
+    /// Steering acceleration while airborne (units/s²); momentum is kept without input
+    pub air_acceleration: f32,
+    /// Upward impulse of a jump; a body's vertical speed rises by `jump_force / mass`
+    pub jump_force: f32,
+}
+
+impl Default for PlayerInputSystem {
+    fn default() -> Self {
+        Self {
+            ground_acceleration: 80.0,
+            air_acceleration: 8.0,
+            jump_force: 5.0,
+        }
+    }
+}
 
 impl PlayerInputSystem {
+    /// Movement speed multiplier while sprinting
+    pub const SPRINT_MULTIPLIER: f32 = 1.75;
+
     pub fn update(&self, world: &mut World, rl: &RaylibHandle) {
-        let speed = 5.0;
+        self.apply_input(world, &InputState::from_raylib(rl), rl.get_frame_time());
+    }
+
+    pub fn apply_input(&self, world: &mut World, input: &InputState, delta_time: f32) {
+        let speed = if input.sprint {
+            5.0 * Self::SPRINT_MULTIPLIER
+        } else {
+            5.0
+        };
 
         for entity in world.entities_mut() {
             if entity.is_player {
-                if let Some(velocity) = &mut entity.velocity {
-                    // Reset velocity
-                    velocity.linear = Vector3::zero();
+                // Entities without a rigidbody aren't simulated, treat them as grounded
+                let grounded = entity.rigidbody.is_none_or(|rb| rb.is_grounded);
+                // Dynamic bodies fall and jump; anything else flies with up/down
+                let simulated = entity.rigidbody.is_some_and(|rb| rb.is_dynamic());
+
+                if let Some(rigidbody) = entity.rigidbody.as_mut().filter(|rb| rb.is_dynamic()) {
+                    self.try_jump(rigidbody, input);
+                }
 
+                if let Some(velocity) = &mut entity.velocity {
                     // Get camera orientation if entity has a camera
                     let (forward, right) = if let Some(camera) = &entity.camera {
                         let yaw_rad = camera.yaw.to_radians();
@@ -351,27 +830,516 @@ impl PlayerInputSystem {
                         (Vector3::new(0.0, 0.0, -1.0), Vector3::new(1.0, 0.0, 0.0))
                     };
 
-                    // WASD movement relative to camera direction
-                    if rl.is_key_down(KeyboardKey::KEY_W) {
-                        velocity.linear = velocity.linear + forward * speed;
-                    }
-                    if rl.is_key_down(KeyboardKey::KEY_S) {
-                        velocity.linear = velocity.linear - forward * speed;
-                    }
-                    if rl.is_key_down(KeyboardKey::KEY_A) {
-                        velocity.linear = velocity.linear - right * speed;
-                    }
-                    if rl.is_key_down(KeyboardKey::KEY_D) {
-                        velocity.linear = velocity.linear + right * speed;
-                    }
-                    if rl.is_key_down(KeyboardKey::KEY_SPACE) {
-                        velocity.linear.y = speed;
-                    }
-                    if rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) {
-                        velocity.linear.y = -speed;
+                    let mut wish = Self::wish_velocity(input, forward, right, speed);
+                    if simulated {
+                        wish.y = 0.0;
                     }
+                    velocity.linear = self.steer_velocity(velocity.linear, wish, grounded, delta_time);
                 }
             }
         }
     }
+
+    /// Jump if the body is on the ground and jump was just pressed
+    /// Holding the key doesn't jump again, and neither does pressing it mid-air
+    pub fn try_jump(&self, rigidbody: &mut Rigidbody, input: &InputState) -> bool {
+        if !input.jump || !rigidbody.is_grounded || rigidbody.mass <= 0.0 {
+            return false;
+        }
+        rigidbody.velocity.y = rigidbody.velocity.y.max(0.0) + self.jump_force / rigidbody.mass;
+        rigidbody.is_grounded = false;
+        true
+    }
+
+    /// Velocity the input asks for, relative to the camera's forward/right
+    ///
+    /// Keys and the analog stick are added together. Holding the stick on top of
+    /// the keys never makes the player faster than the keys (or full tilt) alone.
+    pub fn wish_velocity(input: &InputState, forward: Vector3, right: Vector3, speed: f32) -> Vector3 {
+        let key = |pressed: bool| if pressed { 1.0 } else { 0.0 };
+        let keys = forward * (key(input.forward) - key(input.back)) + right * (key(input.right) - key(input.left));
+        let stick = forward * input.move_axis.y + right * input.move_axis.x;
+
+        let max_length = keys.length().max(1.0);
+        let mut wish = keys + stick;
+        if wish.length() > max_length {
+            wish = wish.normalized() * max_length;
+        }
+        wish *= speed;
+
+        if input.up {
+            wish.y = speed;
+        }
+        if input.down {
+            wish.y = -speed;
+        }
+        wish
+    }
+
+    /// Move the horizontal velocity towards the wished velocity
+    /// Grounded bodies accelerate quickly and stop without input; airborne bodies
+    /// keep their momentum and only steer at `air_acceleration`
+    /// The vertical component is always taken from `wish`
+    pub fn steer_velocity(&self, current: Vector3, wish: Vector3, grounded: bool, delta_time: f32) -> Vector3 {
+        let current_h = Vector3::new(current.x, 0.0, current.z);
+        let wish_h = Vector3::new(wish.x, 0.0, wish.z);
+
+        let has_input = wish_h.length() > 0.0001;
+        if !grounded && !has_input {
+            return Vector3::new(current.x, wish.y, current.z);
+        }
+
+        let acceleration = if grounded { self.ground_acceleration } else { self.air_acceleration };
+        let max_change = acceleration * delta_time;
+        let difference = wish_h - current_h;
+        let distance = difference.length();
+
+        let new_h = if distance <= max_change || distance < 0.0001 {
+            wish_h
+        } else {
+            current_h + difference * (max_change / distance)
+        };
+
+        Vector3::new(new_h.x, wish.y, new_h.z)
+    }
+}
+
+/// Click-to-select targeting
+///
+/// On left click, casts a ray from the camera through the cursor and tags the
+/// nearest entity it hits with `Selected`, clearing any previous selection.
+/// While the cursor is captured the ray goes through the crosshair instead.
+pub struct SelectionSystem {
+    /// Entities further away than this can't be selected
+    pub max_distance: f32,
+}
+
+impl Default for SelectionSystem {
+    fn default() -> Self {
+        Self { max_distance: 100.0 }
+    }
+}
+
+impl SelectionSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&self, world: &mut World, rl: &RaylibHandle, camera: &Camera3D, mode: GameMode) {
+        if !rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+            return;
+        }
+
+        let viewport = Vector2::new(rl.get_screen_width() as f32, rl.get_screen_height() as f32);
+        let cursor = if mode.captures_cursor() {
+            viewport / 2.0
+        } else {
+            rl.get_mouse_position()
+        };
+
+        let ray = Self::screen_to_ray(cursor, camera, viewport);
+        self.select_along_ray(world, &ray);
+    }
+
+    /// Ray from the camera through a screen position (perspective cameras only)
+    ///
+    /// `viewport` is the screen size in pixels; the returned direction is normalized.
+    pub fn screen_to_ray(screen: Vector2, camera: &Camera3D, viewport: Vector2) -> Ray {
+        let forward = (camera.target - camera.position).normalized();
+        let right = forward.cross(camera.up).normalized();
+        let up = right.cross(forward);
+
+        // Screen y grows downwards, world up doesn't
+        let ndc_x = 2.0 * screen.x / viewport.x - 1.0;
+        let ndc_y = 1.0 - 2.0 * screen.y / viewport.y;
+        let half_height = (camera.fovy.to_radians() / 2.0).tan();
+        let half_width = half_height * viewport.x / viewport.y;
+
+        let direction = forward + right * (ndc_x * half_width) + up * (ndc_y * half_height);
+        Ray {
+            position: camera.position,
+            direction: direction.normalized(),
+        }
+    }
+
+    /// Select the nearest entity hit by the ray, returning its id
+    ///
+    /// Entities with a collider are tested against its bounds, otherwise against
+    /// their renderable's. Disabled entities and those containing the ray origin
+    /// (the viewer) are skipped.
+    pub fn select_along_ray(&self, world: &mut World, ray: &Ray) -> Option<EntityId> {
+        let hit = world
+            .enabled_entities()
+            .filter_map(|entity| {
+                let position = entity.transform?.position;
+                let shape = match (&entity.collider, &entity.renderable) {
+                    (Some(collider), _) => collider.shape,
+                    (None, Some(renderable)) => Collider::from_renderable(renderable).shape,
+                    (None, None) => return None,
+                };
+                let distance = shape.bounds(position).ray_intersection(ray.position, ray.direction)?;
+                (distance > 0.0 && distance <= self.max_distance).then_some((entity.id, distance))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(id, _)| id);
+
+        for entity in world.entities_mut() {
+            entity.selected = (Some(entity.id) == hit).then_some(Selected);
+        }
+        hit
+    }
+
+    /// Outline the selected entities
+    pub fn render(world: &World, d: &mut RaylibMode3D<RaylibDrawHandle>) {
+        for entity in world.enabled_entities().filter(|e| e.selected.is_some()) {
+            let Some(transform) = entity.transform else {
+                continue;
+            };
+            let shape = match (&entity.collider, &entity.renderable) {
+                (Some(collider), _) => collider.shape,
+                (None, Some(renderable)) => Collider::from_renderable(renderable).shape,
+                (None, None) => continue,
+            };
+            let bounds = shape.bounds(transform.position).expand(0.05);
+            d.draw_cube_wires_v(bounds.center(), bounds.size(), Color::GOLD);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::components::{Camera as EcsCamera, Velocity};
+
+    #[test]
+    fn test_render_culls_entities_behind_camera() {
+        let mut world = World::new();
+        let mut spawn = |z: f32| {
+            world
+                .spawn()
+                .with_transform(Transform::new(Vector3::new(0.0, 0.0, z)))
+                .with_renderable(Renderable::cube(Vector3::one(), Color::RED))
+                .build()
+        };
+        let ahead = spawn(-10.0);
+        let behind = [spawn(5.0), spawn(20.0)];
+        // Close behind, but big enough to reach into view
+        let large = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::new(0.0, 0.0, 1.0)))
+            .with_renderable(Renderable::sphere(3.0, Color::RED))
+            .build();
+
+        let camera = Camera3D::perspective(
+            Vector3::zero(),
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            60.0,
+        );
+        let mut render_system = RenderSystem::new();
+        render_system.update_culling(&world);
+        assert_eq!(render_system.culled_count(), 0, "nothing is culled without a camera");
+
+        render_system.set_camera(&camera, 16.0 / 9.0, 0.1, 1000.0);
+        render_system.update_culling(&world);
+        assert_eq!(render_system.culled_count(), 2);
+        assert!(behind.iter().all(|&id| render_system.is_culled(id)));
+        assert!(!render_system.is_culled(ahead));
+        assert!(!render_system.is_culled(large));
+
+        render_system.set_frustum_culling(false);
+        render_system.update_culling(&world);
+        assert_eq!(render_system.culled_count(), 0);
+    }
+
+    #[test]
+    fn test_camera_bobs_while_walking() {
+        let mut world = World::new();
+        let player = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::new(0.0, 1.7, 0.0)))
+            .with_velocity(Velocity { linear: Vector3::new(4.0, 0.0, 0.0), ..Default::default() })
+            .with_camera(EcsCamera::new(60.0).with_head_bob(0.1, 2.0))
+            .as_player()
+            .build();
+
+        let system = FirstPersonCameraSystem;
+        let (mut min_y, mut max_y) = (f32::MAX, f32::MIN);
+        for _ in 0..240 {
+            system.apply_input(&mut world, &InputState::default(), GameMode::Playing, 1.0 / 60.0);
+            let entity = world.get_entity(player).unwrap();
+            let eye = entity.camera.unwrap().to_camera3d(entity.transform.unwrap().position).position;
+            min_y = min_y.min(eye.y);
+            max_y = max_y.max(eye.y);
+        }
+        assert!(max_y > 1.75 && min_y < 1.65, "eye height stayed within {}..{}", min_y, max_y);
+
+        // Standing still settles back to the resting height
+        world.get_entity_mut(player).unwrap().velocity = Some(Velocity::default());
+        for _ in 0..120 {
+            system.apply_input(&mut world, &InputState::default(), GameMode::Playing, 1.0 / 60.0);
+        }
+        let entity = world.get_entity(player).unwrap();
+        let eye = entity.camera.unwrap().to_camera3d(entity.transform.unwrap().position).position;
+        assert!((eye.y - 1.7).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_lod_tiers_follow_camera_distance() {
+        let mut render_system = RenderSystem::new();
+        let near = Vector3::new(0.0, 0.0, -5.0);
+        let middle = Vector3::new(0.0, 0.0, -25.0);
+        let far = Vector3::new(0.0, 0.0, -100.0);
+        assert_eq!(render_system.lod_segments(far), 16, "default tier without a camera");
+
+        let camera = Camera3D::perspective(
+            Vector3::zero(),
+            Vector3::new(0.0, 0.0, -1.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            60.0,
+        );
+        render_system.set_camera(&camera, 1.0, 0.1, 1000.0);
+        assert_eq!(render_system.lod_segments(near), 24);
+        assert_eq!(render_system.lod_segments(middle), 16);
+        assert_eq!(render_system.lod_segments(far), 8);
+
+        render_system.lod_distances = [200.0, 400.0];
+        assert_eq!(render_system.lod_segments(far), 24);
+    }
+
+    #[test]
+    fn test_game_mode_look_input() {
+        assert!(GameMode::Playing.consumes_look_input());
+        assert!(!GameMode::Menu.consumes_look_input());
+
+        assert!(GameMode::Playing.captures_cursor());
+        assert!(!GameMode::Menu.captures_cursor());
+    }
+
+    #[test]
+    fn test_input_state_follows_bindings() {
+        let mut bindings = KeyBindings::default();
+        bindings.rebind(InputAction::MoveForward, KeyboardKey::KEY_UP);
+        let held: HashSet<KeyboardKey> = [KeyboardKey::KEY_UP, KeyboardKey::KEY_LEFT_CONTROL].into_iter().collect();
+
+        let input = InputState::from_keys(&held, &bindings);
+        assert_eq!(input, InputState { forward: true, sprint: true, ..Default::default() });
+    }
+
+    #[test]
+    fn test_stick_deadzone_and_wish_velocity() {
+        let config = InputConfig::default();
+        assert_eq!(config.apply_deadzone(Vector2::new(0.1, -0.05)), Vector2::zero());
+        // Just past the deadzone starts near zero, full tilt stays at 1
+        assert!(config.apply_deadzone(Vector2::new(0.2, 0.0)).x < 0.1);
+        assert!((config.apply_deadzone(Vector2::new(1.0, 0.0)).x - 1.0).abs() < 1e-5);
+        assert!((config.apply_deadzone(Vector2::new(1.0, 1.0)).length() - 1.0).abs() < 1e-5);
+
+        let forward = Vector3::new(0.0, 0.0, -1.0);
+        let right = Vector3::new(1.0, 0.0, 0.0);
+        let stick = |x: f32, y: f32| InputState {
+            move_axis: config.apply_deadzone(Vector2::new(x, y)),
+            ..Default::default()
+        };
+
+        // Half tilt forward walks at half speed along the camera's forward
+        let half = PlayerInputSystem::wish_velocity(&stick(0.0, 0.575), forward, right, 5.0);
+        assert!((half.z + 2.5).abs() < 1e-4 && half.x.abs() < 1e-6);
+        // Stick drift inside the deadzone doesn't move the player
+        assert_eq!(PlayerInputSystem::wish_velocity(&stick(0.1, 0.1), forward, right, 5.0), Vector3::zero());
+
+        // Stick on top of a key doesn't add speed
+        let both = InputState { forward: true, ..stick(0.0, 1.0) };
+        assert!((PlayerInputSystem::wish_velocity(&both, forward, right, 5.0).length() - 5.0).abs() < 1e-4);
+        let keys = InputState { forward: true, up: true, ..Default::default() };
+        assert_eq!(PlayerInputSystem::wish_velocity(&keys, forward, right, 5.0), Vector3::new(0.0, 5.0, -5.0));
+    }
+
+    #[test]
+    fn test_jump_only_from_the_ground() {
+        use crate::ecs::physics::PhysicsSystem;
+
+        let mut world = World::new();
+        let player = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_velocity(Velocity::default())
+            .with_rigidbody(Rigidbody { is_grounded: true, ..Rigidbody::default() })
+            .as_player()
+            .build();
+        let input = PlayerInputSystem::default();
+        let mut physics = PhysicsSystem::default();
+        let jump = InputState { up: true, jump: true, ..Default::default() };
+        let held = InputState { up: true, ..Default::default() };
+        let body = |world: &World| world.get_entity(player).unwrap().rigidbody.unwrap();
+
+        input.apply_input(&mut world, &jump, 1.0 / 60.0);
+        assert_eq!(body(&world).velocity.y, input.jump_force);
+        // Holding the key doesn't fly: the Velocity component gets no vertical part
+        assert_eq!(world.get_entity(player).unwrap().velocity.unwrap().linear.y, 0.0);
+
+        physics.update(&mut world, 0.1);
+        assert!(!body(&world).is_grounded);
+        let rising = body(&world).velocity.y;
+        assert!(rising < input.jump_force);
+
+        // Mid-air presses and holds do nothing; gravity keeps pulling
+        input.apply_input(&mut world, &jump, 1.0 / 60.0);
+        input.apply_input(&mut world, &held, 1.0 / 60.0);
+        assert_eq!(body(&world).velocity.y, rising);
+        for _ in 0..10 {
+            physics.update(&mut world, 0.1);
+        }
+        assert!(body(&world).velocity.y < 0.0);
+    }
+
+    #[test]
+    fn test_grounded_steering_is_immediate() {
+        let input = PlayerInputSystem::default();
+        let current = Vector3::new(-5.0, 0.0, 0.0);
+        let wish = Vector3::new(0.0, 0.0, 5.0);
+
+        let result = input.steer_velocity(current, wish, true, 0.2);
+        assert_eq!(result, wish);
+
+        // No input on the ground stops the player
+        let stopped = input.steer_velocity(current, Vector3::zero(), true, 0.2);
+        assert_eq!(stopped, Vector3::zero());
+    }
+
+    #[test]
+    fn test_airborne_steering_is_damped() {
+        let input = PlayerInputSystem::default();
+        let current = Vector3::new(5.0, 0.0, 0.0);
+        let wish = Vector3::new(0.0, 0.0, 5.0);
+        let dt = 1.0 / 60.0;
+
+        let result = input.steer_velocity(current, wish, false, dt);
+        let change = (result - current).length();
+        assert!((change - input.air_acceleration * dt).abs() < 1e-4);
+        assert!(result.x > 4.5, "momentum should mostly be preserved, got {:?}", result);
+
+        // Without input, airborne momentum is kept
+        let coasting = input.steer_velocity(current, Vector3::zero(), false, dt);
+        assert_eq!(coasting, current);
+    }
+
+    fn selection_camera() -> Camera3D {
+        Camera3D::perspective(Vector3::zero(), Vector3::new(0.0, 0.0, -1.0), Vector3::new(0.0, 1.0, 0.0), 90.0)
+    }
+
+    #[test]
+    fn test_center_click_ray_points_forward() {
+        let camera = selection_camera();
+        let viewport = Vector2::new(1280.0, 720.0);
+
+        let ray = SelectionSystem::screen_to_ray(viewport / 2.0, &camera, viewport);
+        assert_eq!(ray.position, camera.position);
+        assert!((ray.direction - Vector3::new(0.0, 0.0, -1.0)).length() < 1e-5);
+
+        // Top edge at 90 degree fov is 45 degrees up
+        let top = SelectionSystem::screen_to_ray(Vector2::new(640.0, 0.0), &camera, viewport);
+        assert!((top.direction.y - top.direction.z.abs()).abs() < 1e-5);
+        assert!(top.direction.y > 0.0);
+    }
+
+    #[test]
+    fn test_select_nearest_hit_clears_previous() {
+        let mut world = World::new();
+        let box_at = |world: &mut World, z: f32| {
+            world
+                .spawn()
+                .with_transform(Transform::new(Vector3::new(0.0, 0.0, z)))
+                .with_collider(Collider::box_collider(Vector3::one()))
+                .build()
+        };
+        let far = box_at(&mut world, -10.0);
+        let near = box_at(&mut world, -4.0);
+        world.get_entity_mut(far).unwrap().selected = Some(Selected);
+
+        let system = SelectionSystem::new();
+        let viewport = Vector2::new(800.0, 600.0);
+        let ray = SelectionSystem::screen_to_ray(viewport / 2.0, &selection_camera(), viewport);
+
+        assert_eq!(system.select_along_ray(&mut world, &ray), Some(near));
+        assert!(world.get_entity(near).unwrap().selected.is_some());
+        assert!(world.get_entity(far).unwrap().selected.is_none());
+
+        // Clicking empty space clears the selection
+        let miss = SelectionSystem::screen_to_ray(Vector2::new(0.0, 0.0), &selection_camera(), viewport);
+        assert_eq!(system.select_along_ray(&mut world, &miss), None);
+        assert!(world.entities().all(|e| e.selected.is_none()));
+    }
+
+    #[test]
+    fn test_disabled_entity_is_skipped_but_kept() {
+        use crate::ecs::components::{Health, Renderable, Velocity};
+
+        let mut world = World::new();
+        let id = world
+            .spawn()
+            .with_transform(Transform::new(Vector3::zero()))
+            .with_velocity(Velocity { linear: Vector3::new(1.0, 0.0, 0.0), ..Default::default() })
+            .with_renderable(Renderable::cube(Vector3::one(), Color::RED))
+            .with_health(Health::new(30.0))
+            .build();
+        assert!(RenderSystem::should_draw(world.get_entity(id).unwrap()));
+
+        assert!(world.set_enabled(id, false));
+        assert!(!world.set_enabled(id + 1, false));
+        MovementSystem.update(&mut world, 1.0);
+
+        // A ray straight through it selects nothing
+        let ray = Ray { position: Vector3::new(0.0, 0.0, 5.0), direction: Vector3::new(0.0, 0.0, -1.0) };
+        assert_eq!(SelectionSystem::new().select_along_ray(&mut world, &ray), None);
+
+        let entity = world.get_entity(id).unwrap();
+        assert!(!RenderSystem::should_draw(entity));
+        assert_eq!(entity.transform.unwrap().position, Vector3::zero());
+        assert_eq!(entity.health.unwrap().current, 30.0);
+        assert!(entity.velocity.is_some() && entity.renderable.is_some());
+
+        world.set_enabled(id, true);
+        assert_eq!(SelectionSystem::new().select_along_ray(&mut world, &ray), Some(id));
+        MovementSystem.update(&mut world, 1.0);
+        assert_eq!(world.get_entity(id).unwrap().transform.unwrap().position, Vector3::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_model_paths_load_once_across_frames() {
+        use crate::level::{EntityConfig, LevelConfig, LevelLoader};
+        use std::cell::Cell;
+        thread_local! {
+            static LOADS: Cell<usize> = const { Cell::new(0) };
+        }
+        fn fake_load(path: &str) -> Option<raylib::ffi::Model> {
+            LOADS.with(|loads| loads.set(loads.get() + 1));
+            // Only the crate exists; zeroed stands in for a loaded model
+            (path == "assets/crate.glb").then(|| unsafe { std::mem::zeroed() })
+        }
+        fn fake_unload(_: raylib::ffi::Model) {}
+
+        let mut level = LevelConfig::default();
+        for (name, path) in [("crate_a", "assets/crate.glb"), ("crate_b", "assets/crate.glb"), ("ghost", "assets/missing.glb")] {
+            let mut world = World::new();
+            let id = world
+                .spawn()
+                .with_name(name.to_string())
+                .with_transform(Transform::new(Vector3::zero()))
+                .with_renderable(Renderable { shape: RenderShape::Model { path: path.to_string() }, visible: true })
+                .build();
+            level.entities.push(EntityConfig::from_entity(world.get_entity(id).unwrap()));
+        }
+        let mut world = World::new();
+        LevelLoader::spawn_entities(&level, &mut world).unwrap();
+
+        let mut render_system = RenderSystem::new().with_model_cache(ModelCache::with_loader(fake_load, fake_unload));
+        for _frame in 0..3 {
+            render_system.preload_models(&world);
+        }
+
+        // Two distinct paths, each tried exactly once, including the missing one
+        assert_eq!(render_system.model_cache().model_count(), 2);
+        assert_eq!(LOADS.with(Cell::get), 2);
+    }
 }
@@ -1,14 +1,23 @@
 use serde::{Deserialize, Serialize};
 use raylib::prelude::*;
-use crate::ecs::components::{Transform as EcsTransform, Renderable, RenderShape, Velocity, Model, Rigidbody, Collider, ColliderShape};
+use super::migrate::CURRENT_LEVEL_VERSION;
+use crate::ecs::components::{Transform as EcsTransform, Renderable, RenderShape, Velocity, Model, Rigidbody, Collider, ColliderShape, Camera as EcsCamera, DEFAULT_FAR_CLIP, DEFAULT_NEAR_CLIP};
+use crate::ecs::entity::{Entity, World};
+use crate::terrain::TerrainConfig;
 
 /// Level configuration that can be loaded from TOML/JSON
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LevelConfig {
+    /// Format version, see `CURRENT_LEVEL_VERSION`; older files are migrated on load
+    #[serde(default = "default_level_version")]
+    pub version: u32,
     pub name: String,
     pub description: Option<String>,
     pub camera: CameraConfig,
     pub entities: Vec<EntityConfig>,
+    /// Generation settings of the level's terrain, if it has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub terrain: Option<TerrainConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +26,10 @@ pub struct CameraConfig {
     pub target: [f32; 3],
     pub up: [f32; 3],
     pub fov: f32,
+    #[serde(default = "default_near_clip")]
+    pub near: f32,
+    #[serde(default = "default_far_clip")]
+    pub far: f32,
 }
 
 impl Default for CameraConfig {
@@ -26,10 +39,34 @@ impl Default for CameraConfig {
             target: [0.0, 0.0, 0.0],
             up: [0.0, 1.0, 0.0],
             fov: 45.0,
+            near: DEFAULT_NEAR_CLIP,
+            far: DEFAULT_FAR_CLIP,
         }
     }
 }
 
+impl LevelConfig {
+    /// Snapshot the world's entities into a level config (ordered by entity id)
+    pub fn from_world(name: &str, camera: CameraConfig, world: &World) -> Self {
+        let mut entities: Vec<&Entity> = world.entities().collect();
+        entities.sort_by_key(|entity| entity.id);
+
+        Self {
+            version: CURRENT_LEVEL_VERSION,
+            name: name.to_string(),
+            description: None,
+            camera,
+            entities: entities.into_iter().map(EntityConfig::from_entity).collect(),
+            terrain: None,
+        }
+    }
+
+    pub fn with_terrain(mut self, terrain: TerrainConfig) -> Self {
+        self.terrain = Some(terrain);
+        self
+    }
+}
+
 impl CameraConfig {
     pub fn to_camera3d(&self) -> Camera3D {
         Camera3D::perspective(
@@ -51,10 +88,120 @@ pub struct EntityConfig {
     pub model: Option<ModelConfig>,
     pub rigidbody: Option<RigidbodyConfig>,
     pub collider: Option<ColliderConfig>,
+    /// Derive a collider from the renderable when no collider is given
+    #[serde(default)]
+    pub auto_collider: bool,
+    /// First-person camera attached to this entity
+    #[serde(default)]
+    pub camera: Option<EntityCameraConfig>,
+    /// Stable id used by `WorldState` to remember permanent changes
+    #[serde(default)]
+    pub persistent_id: Option<String>,
+    /// Whether this entity is a lootable container (chest)
+    #[serde(default)]
+    pub container: bool,
     pub entity_type: EntityType,
 }
 
+impl EntityConfig {
+    /// Capture an entity's serializable components
+    pub fn from_entity(entity: &Entity) -> Self {
+        let entity_type = if entity.is_player {
+            EntityType::Player
+        } else if entity.is_enemy {
+            EntityType::Enemy
+        } else if entity.rigidbody.is_some_and(|rb| rb.is_static()) {
+            EntityType::Static
+        } else {
+            EntityType::Prop
+        };
+
+        Self {
+            name: entity.name.as_ref().map(|name| name.0.clone()),
+            transform: TransformConfig::from_transform(&entity.transform.unwrap_or_default()),
+            renderable: entity.renderable.as_ref().map(RenderableConfig::from_renderable),
+            velocity: entity.velocity.as_ref().map(VelocityConfig::from_velocity),
+            health: entity.health.map(|health| health.max),
+            model: entity.model.as_ref().map(ModelConfig::from_model),
+            rigidbody: entity.rigidbody.as_ref().map(RigidbodyConfig::from_rigidbody),
+            collider: entity.collider.as_ref().map(ColliderConfig::from_collider),
+            auto_collider: false,
+            camera: entity.camera.as_ref().map(EntityCameraConfig::from_camera),
+            persistent_id: entity.persistent_id.as_ref().map(|id| id.0.clone()),
+            container: entity.container.is_some(),
+            entity_type,
+        }
+    }
+}
+
+/// Camera component settings that need to survive a save/load
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityCameraConfig {
+    #[serde(default = "default_camera_fov")]
+    pub fov: f32,
+    #[serde(default)]
+    pub yaw: f32,
+    #[serde(default)]
+    pub pitch: f32,
+    #[serde(default = "default_mouse_sensitivity")]
+    pub mouse_sensitivity: f32,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default = "default_true")]
+    pub active: bool,
+    #[serde(default = "default_near_clip")]
+    pub near: f32,
+    #[serde(default = "default_far_clip")]
+    pub far: f32,
+}
+
+fn default_camera_fov() -> f32 {
+    60.0
+}
+
+fn default_mouse_sensitivity() -> f32 {
+    0.1
+}
+
+fn default_level_version() -> u32 {
+    CURRENT_LEVEL_VERSION
+}
+
+fn default_near_clip() -> f32 {
+    DEFAULT_NEAR_CLIP
+}
+
+fn default_far_clip() -> f32 {
+    DEFAULT_FAR_CLIP
+}
+
+impl EntityCameraConfig {
+    pub fn from_camera(camera: &EcsCamera) -> Self {
+        Self {
+            fov: camera.fov,
+            yaw: camera.yaw,
+            pitch: camera.pitch,
+            mouse_sensitivity: camera.mouse_sensitivity,
+            priority: camera.priority,
+            active: camera.active,
+            near: camera.near,
+            far: camera.far,
+        }
+    }
+
+    pub fn to_camera(&self) -> EcsCamera {
+        let mut camera = EcsCamera::new(self.fov)
+            .with_sensitivity(self.mouse_sensitivity)
+            .with_priority(self.priority)
+            .with_active(self.active)
+            .with_clip_planes(self.near, self.far);
+        camera.yaw = self.yaw;
+        camera.pitch = self.pitch;
+        camera
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TransformConfig {
     pub position: [f32; 3],
     #[serde(default)]
@@ -68,6 +215,14 @@ fn default_scale() -> [f32; 3] {
 }
 
 impl TransformConfig {
+    pub fn from_transform(transform: &EcsTransform) -> Self {
+        Self {
+            position: [transform.position.x, transform.position.y, transform.position.z],
+            rotation: [transform.rotation.x, transform.rotation.y, transform.rotation.z],
+            scale: [transform.scale.x, transform.scale.y, transform.scale.z],
+        }
+    }
+
     pub fn to_transform(&self) -> EcsTransform {
         EcsTransform {
             position: Vector3::new(self.position[0], self.position[1], self.position[2]),
@@ -99,6 +254,25 @@ pub enum RenderableConfig {
 }
 
 impl RenderableConfig {
+    pub fn from_renderable(renderable: &Renderable) -> Self {
+        match &renderable.shape {
+            RenderShape::Cube { size, color } => RenderableConfig::Cube {
+                size: [size.x, size.y, size.z],
+                color: *color,
+            },
+            RenderShape::Sphere { radius, color } => RenderableConfig::Sphere {
+                radius: *radius,
+                color: *color,
+            },
+            RenderShape::Cylinder { radius, height, color } => RenderableConfig::Cylinder {
+                radius: *radius,
+                height: *height,
+                color: *color,
+            },
+            RenderShape::Model { path } => RenderableConfig::Model { path: path.clone() },
+        }
+    }
+
     pub fn to_renderable(&self) -> Renderable {
         let shape = match self {
             RenderableConfig::Cube { size, color } => RenderShape::Cube {
@@ -145,6 +319,15 @@ fn default_model_scale() -> f32 {
 }
 
 impl ModelConfig {
+    pub fn from_model(model: &Model) -> Self {
+        Self {
+            model_path: model.model_path.clone(),
+            texture_path: model.texture_path.clone(),
+            tint: [model.tint.r, model.tint.g, model.tint.b, model.tint.a],
+            scale: model.scale,
+        }
+    }
+
     pub fn to_model(&self) -> Model {
         let mut model = Model::new(self.model_path.clone());
         
@@ -168,6 +351,13 @@ pub struct VelocityConfig {
 }
 
 impl VelocityConfig {
+    pub fn from_velocity(velocity: &Velocity) -> Self {
+        Self {
+            linear: [velocity.linear.x, velocity.linear.y, velocity.linear.z],
+            angular: [velocity.angular.x, velocity.angular.y, velocity.angular.z],
+        }
+    }
+
     pub fn to_velocity(&self) -> Velocity {
         Velocity {
             linear: Vector3::new(self.linear[0], self.linear[1], self.linear[2]),
@@ -184,6 +374,9 @@ pub struct RigidbodyConfig {
     pub use_gravity: bool,
     #[serde(default = "default_drag")]
     pub drag: f32,
+    /// Moved by code rather than physics (platforms, doors)
+    #[serde(default)]
+    pub kinematic: bool,
 }
 
 fn default_mass() -> f32 {
@@ -199,7 +392,20 @@ fn default_drag() -> f32 {
 }
 
 impl RigidbodyConfig {
+    pub fn from_rigidbody(rigidbody: &Rigidbody) -> Self {
+        Self {
+            mass: rigidbody.mass,
+            use_gravity: rigidbody.use_gravity,
+            drag: rigidbody.drag,
+            kinematic: rigidbody.is_kinematic(),
+        }
+    }
+
     pub fn to_rigidbody(&self) -> Rigidbody {
+        if self.kinematic {
+            return Rigidbody::kinematic().with_drag(self.drag);
+        }
+
         Rigidbody::new(self.mass)
             .with_gravity(self.use_gravity)
             .with_drag(self.drag)
@@ -215,6 +421,14 @@ pub enum ColliderConfig {
 }
 
 impl ColliderConfig {
+    pub fn from_collider(collider: &Collider) -> Self {
+        match collider.shape {
+            ColliderShape::Box { size } => ColliderConfig::Box { size: [size.x, size.y, size.z] },
+            ColliderShape::Sphere { radius } => ColliderConfig::Sphere { radius },
+            ColliderShape::Capsule { radius, height } => ColliderConfig::Capsule { radius, height },
+        }
+    }
+
     pub fn to_collider(&self) -> Collider {
         match self {
             ColliderConfig::Box { size } => {
@@ -242,11 +456,78 @@ pub enum EntityType {
 impl Default for LevelConfig {
     fn default() -> Self {
         Self {
+            version: CURRENT_LEVEL_VERSION,
             name: "Default Level".to_string(),
             description: Some("A basic level".to_string()),
             camera: CameraConfig::default(),
             entities: vec![],
+            terrain: None,
         }
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::LevelLoader;
+
+    #[test]
+    fn test_player_camera_survives_serialization() {
+        let mut world = World::new();
+        world
+            .spawn()
+            .with_name("Crate".to_string())
+            .with_transform(EcsTransform::new(Vector3::new(3.0, 0.5, 0.0)))
+            .with_renderable(Renderable::cube(Vector3::one(), Color::BROWN))
+            .build();
+
+        let mut camera = EcsCamera::new(75.0).with_sensitivity(0.25);
+        camera.rotate(123.5, -32.25);
+        let player = world
+            .spawn()
+            .with_transform(EcsTransform::new(Vector3::new(1.0, 2.0, 3.0)))
+            .with_velocity(Velocity::default())
+            .with_camera(camera)
+            .as_player()
+            .build();
+        assert_eq!(world.active_camera(), Some(player));
+
+        let level = LevelConfig::from_world("Save", CameraConfig::default(), &world);
+        let json = serde_json::to_string(&level).unwrap();
+        let loaded: LevelConfig = serde_json::from_str(&json).unwrap();
+
+        let mut restored = World::new();
+        LevelLoader::spawn_entities(&loaded, &mut restored).unwrap();
+
+        let camera_id = restored.active_camera().expect("camera entity restored");
+        let entity = restored.get_entity(camera_id).unwrap();
+        assert!(entity.is_player);
+        assert_eq!(entity.transform.unwrap().position, Vector3::new(1.0, 2.0, 3.0));
+
+        let restored_camera = entity.camera.unwrap();
+        assert_eq!(restored_camera.yaw, camera.yaw);
+        assert_eq!(restored_camera.pitch, camera.pitch);
+        assert_eq!(restored_camera.fov, 75.0);
+        assert_eq!(restored_camera.mouse_sensitivity, 0.25);
+
+        // Player input drives the same entity the camera renders from
+        let players: Vec<_> = restored.entities().filter(|e| e.is_player).map(|e| e.id).collect();
+        assert_eq!(players, vec![camera_id]);
+    }
+
+    #[test]
+    fn test_clip_planes_round_trip() {
+        let config = CameraConfig { near: 0.5, far: 250.0, ..Default::default() };
+        let loaded: CameraConfig = serde_json::from_str(&serde_json::to_string(&config).unwrap()).unwrap();
+        assert_eq!((loaded.near, loaded.far), (0.5, 250.0));
+
+        // Older level files without clip planes keep raylib's defaults
+        let legacy: CameraConfig =
+            serde_json::from_str(r#"{"position":[0,1,0],"target":[0,0,0],"up":[0,1,0],"fov":60}"#).unwrap();
+        assert_eq!((legacy.near, legacy.far), (DEFAULT_NEAR_CLIP, DEFAULT_FAR_CLIP));
+
+        let camera = EcsCamera::new(60.0).with_clip_planes(0.1, 500.0);
+        let restored = EntityCameraConfig::from_camera(&camera).to_camera();
+        assert_eq!((restored.near, restored.far), (0.1, 500.0));
+    }
+}
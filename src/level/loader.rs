@@ -1,28 +1,37 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use super::config::*;
+use super::world_state::WorldState;
+use super::migrate::level_from_value;
 use crate::ecs::World;
-use crate::ecs::components::Health;
+use crate::ecs::entity::EntityId;
+use crate::ecs::components::{Collider, Container, Health};
+use crate::terrain::Terrain;
 
 pub struct LevelLoader;
 
 impl LevelLoader {
-    /// Load level from TOML file
+    /// Load level from TOML file, migrating older format versions
     pub fn load_from_toml<P: AsRef<Path>>(path: P) -> Result<LevelConfig, String> {
         let contents = fs::read_to_string(path)
             .map_err(|e| format!("Failed to read file: {}", e))?;
 
-        toml::from_str(&contents)
-            .map_err(|e| format!("Failed to parse TOML: {}", e))
+        let value: toml::Value = toml::from_str(&contents)
+            .map_err(|e| format!("Failed to parse TOML: {}", e))?;
+        let value = serde_json::to_value(value)
+            .map_err(|e| format!("Failed to convert TOML: {}", e))?;
+        level_from_value(value)
     }
 
-    /// Load level from JSON file
+    /// Load level from JSON file, migrating older format versions
     pub fn load_from_json<P: AsRef<Path>>(path: P) -> Result<LevelConfig, String> {
         let contents = fs::read_to_string(path)
             .map_err(|e| format!("Failed to read file: {}", e))?;
 
-        serde_json::from_str(&contents)
-            .map_err(|e| format!("Failed to parse JSON: {}", e))
+        let value: serde_json::Value = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+        level_from_value(value)
     }
 
     /// Save level to TOML file
@@ -44,47 +53,254 @@ impl LevelLoader {
     }
 
     /// Spawn entities from level config into the world
-    pub fn spawn_entities(level: &LevelConfig, world: &mut World) {
-        for entity_config in &level.entities {
-            let mut builder = world.spawn()
-                .with_transform(entity_config.transform.to_transform());
+    /// Returns the level's terrain if it configures one, or an error if its
+    /// terrain settings are invalid (nothing is spawned then)
+    pub fn spawn_entities(level: &LevelConfig, world: &mut World) -> Result<Option<Terrain>, String> {
+        Self::spawn_entities_with_state(level, world, &WorldState::default())
+    }
 
-            if let Some(name) = &entity_config.name {
-                builder = builder.with_name(name.clone());
-            }
+    /// Spawn entities, applying permanent changes recorded for this level
+    ///
+    /// The level name is the level id. Opened chests spawn open; defeated unique
+    /// enemies and collected unique items are skipped. Returns the level's
+    /// terrain, generated from its `terrain` settings, if it has one.
+    pub fn spawn_entities_with_state(
+        level: &LevelConfig,
+        world: &mut World,
+        state: &WorldState,
+    ) -> Result<Option<Terrain>, String> {
+        let terrain = Self::generate_terrain(level)?;
+        let level_id = level.name.as_str();
 
-            if let Some(renderable_config) = &entity_config.renderable {
-                builder = builder.with_renderable(renderable_config.to_renderable());
+        for entity_config in &level.entities {
+            if Self::is_removed(entity_config, level_id, state) {
+                continue;
             }
 
-            if let Some(velocity_config) = &entity_config.velocity {
-                builder = builder.with_velocity(velocity_config.to_velocity());
-            }
+            Self::spawn_entity(entity_config, level_id, world, state);
+        }
 
-            if let Some(health) = entity_config.health {
-                builder = builder.with_health(Health::new(health));
-            }
+        Ok(terrain)
+    }
 
-            if let Some(model_config) = &entity_config.model {
-                builder = builder.with_model(model_config.to_model());
-            }
+    /// Generate the terrain a level configures
+    /// Fails on settings `Terrain::generate` can't handle (see `TerrainConfig::validate`)
+    pub fn generate_terrain(level: &LevelConfig) -> Result<Option<Terrain>, String> {
+        let Some(config) = &level.terrain else {
+            return Ok(None);
+        };
+        config.validate().map_err(|e| format!("Invalid terrain in level '{}': {}", level.name, e))?;
+        Ok(Some(Terrain::generate(config.clone())))
+    }
 
-            if let Some(rigidbody_config) = &entity_config.rigidbody {
-                builder = builder.with_rigidbody(rigidbody_config.to_rigidbody());
-            }
+    /// Whether the world state says this entity is permanently gone
+    /// (a defeated unique enemy or a collected unique item)
+    pub(crate) fn is_removed(entity_config: &EntityConfig, level_id: &str, state: &WorldState) -> bool {
+        let Some(id) = &entity_config.persistent_id else {
+            return false;
+        };
+        match entity_config.entity_type {
+            EntityType::Enemy => state.is_unique_defeated(level_id, id),
+            _ => state.is_item_collected(level_id, id),
+        }
+    }
 
-            if let Some(collider_config) = &entity_config.collider {
-                builder = builder.with_collider(collider_config.to_collider());
-            }
+    /// Spawn a single configured entity
+    pub(crate) fn spawn_entity(entity_config: &EntityConfig, level_id: &str, world: &mut World, state: &WorldState) -> EntityId {
+        let mut builder = world.spawn()
+            .with_transform(entity_config.transform.to_transform());
+
+        if let Some(name) = &entity_config.name {
+            builder = builder.with_name(name.clone());
+        }
+
+        if let Some(renderable_config) = &entity_config.renderable {
+            builder = builder.with_renderable(renderable_config.to_renderable());
+        }
+
+        if let Some(velocity_config) = &entity_config.velocity {
+            builder = builder.with_velocity(velocity_config.to_velocity());
+        }
+
+        if let Some(health) = entity_config.health {
+            builder = builder.with_health(Health::new(health));
+        }
+
+        if let Some(model_config) = &entity_config.model {
+            builder = builder.with_model(model_config.to_model());
+        }
+
+        if let Some(rigidbody_config) = &entity_config.rigidbody {
+            builder = builder.with_rigidbody(rigidbody_config.to_rigidbody());
+        }
+
+        if let Some(collider_config) = &entity_config.collider {
+            builder = builder.with_collider(collider_config.to_collider());
+        } else if entity_config.auto_collider
+            && let Some(renderable_config) = &entity_config.renderable
+        {
+            builder = builder.with_collider(Collider::from_renderable(&renderable_config.to_renderable()));
+        }
+
+        if let Some(camera_config) = &entity_config.camera {
+            builder = builder.with_camera(camera_config.to_camera());
+        }
+
+        if let Some(id) = &entity_config.persistent_id {
+            builder = builder.with_persistent_id(id.clone());
+        }
+
+        if entity_config.container {
+            let is_open = entity_config
+                .persistent_id
+                .as_ref()
+                .is_some_and(|id| state.is_chest_opened(level_id, id));
+            builder = builder.with_container(Container { is_open });
+        }
+
+        match entity_config.entity_type {
+            EntityType::Player => builder = builder.as_player(),
+            EntityType::Enemy => builder = builder.as_enemy(),
+            _ => {}
+        }
+
+        builder.build()
+    }
+
+    /// Apply an edited level config to a running world
+    ///
+    /// Entities are matched by name: names only in `new` are spawned, names only in
+    /// `old` are despawned, and entities whose configured transform changed are moved.
+    /// Unnamed config entries and runtime entities (projectiles, the player spawned
+    /// by code, ...) are left alone.
+    pub fn apply_diff(old: &LevelConfig, new: &LevelConfig, world: &mut World) {
+        fn named(level: &LevelConfig) -> HashMap<&str, &EntityConfig> {
+            level
+                .entities
+                .iter()
+                .filter_map(|config| config.name.as_deref().map(|name| (name, config)))
+                .collect()
+        }
+        let old_entities = named(old);
+        let new_entities = named(new);
+        let find = |world: &World, name: &str| -> Vec<EntityId> {
+            world
+                .entities()
+                .filter(|entity| entity.name.as_ref().is_some_and(|n| n.0 == name))
+                .map(|entity| entity.id)
+                .collect()
+        };
 
-            match entity_config.entity_type {
-                EntityType::Player => builder = builder.as_player(),
-                EntityType::Enemy => builder = builder.as_enemy(),
-                _ => {}
+        for name in old_entities.keys().filter(|name| !new_entities.contains_key(*name)) {
+            for id in find(world, name) {
+                world.remove_entity(id);
             }
+        }
 
-            builder.build();
+        // Spawn in config order so entity ids stay predictable
+        for config in &new.entities {
+            let Some(name) = &config.name else {
+                continue;
+            };
+            match old_entities.get(name.as_str()) {
+                None => {
+                    Self::spawn_entity(config, &new.name, world, &WorldState::default());
+                }
+                Some(previous) if previous.transform != config.transform => {
+                    for id in find(world, name) {
+                        if let Some(entity) = world.get_entity_mut(id) {
+                            entity.transform = Some(config.transform.to_transform());
+                        }
+                    }
+                }
+                Some(_) => {}
+            }
         }
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::components::Transform;
+    use crate::ecs::examples::Projectile;
+    use crate::terrain::{NoiseMode, TerrainConfig};
+    use raylib::prelude::Vector3;
+
+    fn prop(name: &str, position: [f32; 3]) -> EntityConfig {
+        let mut world = World::new();
+        let id = world
+            .spawn()
+            .with_name(name.to_string())
+            .with_transform(Transform::new(Vector3::new(position[0], position[1], position[2])))
+            .build();
+        EntityConfig::from_entity(world.get_entity(id).unwrap())
+    }
+
+    fn level(entities: Vec<EntityConfig>) -> LevelConfig {
+        LevelConfig { entities, ..Default::default() }
+    }
+
+    fn position_of(world: &World, name: &str) -> Option<Vector3> {
+        world
+            .entities()
+            .find(|e| e.name.as_ref().is_some_and(|n| n.0 == name))
+            .and_then(|e| e.transform)
+            .map(|t| t.position)
+    }
+
+    #[test]
+    fn test_level_terrain_round_trips_and_generates() {
+        let config = TerrainConfig { width: 24, depth: 24, seed: 99, noise_mode: NoiseMode::Ridged, ..Default::default() };
+        let hilly = level(vec![prop("crate", [0.0, 0.0, 0.0])]).with_terrain(config.clone());
+
+        let json = serde_json::to_string(&hilly).unwrap();
+        let loaded: LevelConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.terrain, Some(config.clone()));
+        let toml = toml::to_string(&hilly).unwrap();
+        assert_eq!(toml::from_str::<LevelConfig>(&toml).unwrap().terrain, Some(config.clone()));
+
+        let mut world = World::new();
+        let terrain = LevelLoader::spawn_entities(&loaded, &mut world).unwrap().unwrap();
+        assert_eq!(terrain.heightmap, Terrain::generate(config).heightmap);
+        assert_eq!(world.entities().count(), 1);
+
+        // Levels without terrain settings (like older files) generate none
+        let partial: TerrainConfig = serde_json::from_str(r#"{ "seed": 5 }"#).unwrap();
+        assert_eq!(partial, TerrainConfig { seed: 5, ..Default::default() });
+        assert!(LevelLoader::spawn_entities(&level(vec![]), &mut World::new()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_invalid_level_terrain_is_an_error() {
+        let too_small = TerrainConfig { width: 1, ..Default::default() };
+        let flat_cells = TerrainConfig { cell_size: 0.0, ..Default::default() };
+        let no_frequency = TerrainConfig { noise_scale: -2.0, ..Default::default() };
+        for config in [too_small, flat_cells, no_frequency] {
+            let mut world = World::new();
+            let broken = level(vec![prop("crate", [0.0, 0.0, 0.0])]).with_terrain(config);
+            assert!(LevelLoader::spawn_entities(&broken, &mut world).is_err());
+            assert_eq!(world.entities().count(), 0);
+        }
+    }
+
+    #[test]
+    fn test_apply_diff_updates_named_entities_only() {
+        let old = level(vec![prop("crate", [0.0, 0.0, 0.0]), prop("barrel", [2.0, 0.0, 0.0])]);
+        let mut world = World::new();
+        LevelLoader::spawn_entities(&old, &mut world).unwrap();
+
+        // Runtime entity that isn't part of the level file
+        let projectile = world.spawn().with_transform(Transform::new(Vector3::new(0.0, 5.0, 0.0))).build();
+        world.get_entity_mut(projectile).unwrap().projectile = Some(Projectile::new(10.0));
+
+        let new = level(vec![prop("crate", [0.0, 0.0, 3.0]), prop("lamp", [5.0, 1.0, 0.0])]);
+        LevelLoader::apply_diff(&old, &new, &mut world);
+
+        assert_eq!(position_of(&world, "crate"), Some(Vector3::new(0.0, 0.0, 3.0)));
+        assert_eq!(position_of(&world, "lamp"), Some(Vector3::new(5.0, 1.0, 0.0)));
+        assert_eq!(position_of(&world, "barrel"), None);
+        assert!(world.get_entity(projectile).is_some_and(|e| e.projectile.is_some()));
+        assert_eq!(world.entities().count(), 3);
+    }
+}
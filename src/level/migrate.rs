@@ -0,0 +1,117 @@
+use serde_json::{Map, Value, json};
+use super::config::LevelConfig;
+use crate::ecs::components::{DEFAULT_FAR_CLIP, DEFAULT_NEAR_CLIP};
+
+/// Level/save format version written by this build
+///
+/// - 1: original format, no `version` field
+/// - 2: camera clip planes and persistent entity ids
+pub const CURRENT_LEVEL_VERSION: u32 = 2;
+
+/// Parse a level of any supported version, upgrading it to the current schema
+pub fn level_from_value(mut value: Value) -> Result<LevelConfig, String> {
+    migrate_level(&mut value)?;
+    serde_json::from_value(value).map_err(|e| format!("Failed to parse level: {}", e))
+}
+
+/// Upgrade raw level data in place, one version at a time
+pub fn migrate_level(value: &mut Value) -> Result<(), String> {
+    let level = value.as_object_mut().ok_or("Level data is not an object")?;
+    let mut version = match level.get("version") {
+        None => 1,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| format!("Invalid level version: {}", v))?,
+    };
+
+    if version < 1 {
+        return Err(format!("unsupported level version {}", version));
+    }
+    if version > CURRENT_LEVEL_VERSION {
+        return Err(format!(
+            "Level format version {} is newer than the supported version {}",
+            version, CURRENT_LEVEL_VERSION
+        ));
+    }
+
+    while version < CURRENT_LEVEL_VERSION {
+        match version {
+            1 => migrate_v1_to_v2(level),
+            _ => unreachable!("no migration from version {}", version),
+        }
+        version += 1;
+    }
+
+    level.insert("version".to_string(), json!(CURRENT_LEVEL_VERSION));
+    Ok(())
+}
+
+/// v2 added camera clip planes and persistence fields on entities
+fn migrate_v1_to_v2(level: &mut Map<String, Value>) {
+    if let Some(camera) = level.get_mut("camera").and_then(Value::as_object_mut) {
+        camera.entry("near").or_insert(json!(DEFAULT_NEAR_CLIP));
+        camera.entry("far").or_insert(json!(DEFAULT_FAR_CLIP));
+    }
+
+    if let Some(entities) = level.get_mut("entities").and_then(Value::as_array_mut) {
+        for entity in entities.iter_mut().filter_map(Value::as_object_mut) {
+            entity.entry("persistent_id").or_insert(Value::Null);
+            entity.entry("container").or_insert(json!(false));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V1_LEVEL: &str = r#"{
+        "name": "Old Save",
+        "description": null,
+        "camera": { "position": [0, 5, 5], "target": [0, 0, 0], "up": [0, 1, 0], "fov": 60 },
+        "entities": [
+            { "name": "Crate", "entity_type": "prop", "transform": { "position": [1, 0, 2], "rotation": [0, 0, 0], "scale": [1, 1, 1] } }
+        ]
+    }"#;
+
+    #[test]
+    fn test_v1_level_migrates_with_defaults() {
+        let mut value: Value = serde_json::from_str(V1_LEVEL).unwrap();
+        migrate_level(&mut value).unwrap();
+        assert_eq!(value["version"], json!(CURRENT_LEVEL_VERSION));
+        assert_eq!(value["entities"][0]["container"], json!(false));
+
+        let level = level_from_value(serde_json::from_str(V1_LEVEL).unwrap()).unwrap();
+        assert_eq!(level.version, CURRENT_LEVEL_VERSION);
+        assert_eq!(level.name, "Old Save");
+        assert_eq!((level.camera.near, level.camera.far), (DEFAULT_NEAR_CLIP, DEFAULT_FAR_CLIP));
+        assert_eq!(level.entities[0].persistent_id, None);
+        assert!(!level.entities[0].container);
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let mut value: Value = serde_json::from_str(V1_LEVEL).unwrap();
+        value["version"] = json!(CURRENT_LEVEL_VERSION + 1);
+
+        let error = level_from_value(value).unwrap_err();
+        assert!(error.contains("newer"), "{}", error);
+    }
+
+    #[test]
+    fn test_version_zero_is_rejected() {
+        let mut value: Value = serde_json::from_str(V1_LEVEL).unwrap();
+        value["version"] = json!(0);
+
+        assert_eq!(migrate_level(&mut value).unwrap_err(), "unsupported level version 0");
+    }
+
+    #[test]
+    fn test_current_version_round_trips() {
+        let level = LevelConfig::default();
+        let value = serde_json::to_value(&level).unwrap();
+        assert_eq!(value["version"], json!(CURRENT_LEVEL_VERSION));
+        assert_eq!(level_from_value(value).unwrap().name, level.name);
+    }
+}
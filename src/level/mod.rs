@@ -1,5 +1,13 @@
 pub mod config;
 pub mod loader;
+pub mod world_state;
+pub mod migrate;
+pub mod streaming;
+pub mod save_game;
 
 pub use config::*;
 pub use loader::*;
+pub use world_state::*;
+pub use migrate::*;
+pub use streaming::*;
+pub use save_game::*;
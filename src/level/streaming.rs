@@ -0,0 +1,170 @@
+use std::collections::{HashMap, HashSet};
+use raylib::prelude::Vector3;
+use super::config::{EntityType, LevelConfig};
+use super::loader::LevelLoader;
+use super::world_state::WorldState;
+use crate::ecs::World;
+use crate::ecs::entity::EntityId;
+
+/// Grid cell a level entity belongs to, in chunk units on the XZ plane
+pub type ChunkCoord = (i32, i32);
+
+/// Streams a large level's entities in and out around a focus point
+///
+/// Level entities are bucketed into square chunks by their configured position.
+/// Each `update` spawns chunks within `load_radius` of the focus (usually the
+/// player) and despawns the rest. Player entities from the level are spawned once
+/// and never unloaded, and entities created at runtime are never touched.
+/// Unloaded entities respawn from their config, so runtime changes to them
+/// (other than what `WorldState` records) are lost.
+pub struct LevelStreamer {
+    pub chunk_size: f32,
+    /// Chunks within this many chunks of the focus chunk stay loaded
+    pub load_radius: i32,
+    /// Indices into `LevelConfig::entities` for each chunk
+    chunks: HashMap<ChunkCoord, Vec<usize>>,
+    /// Level entities that ignore streaming (the player)
+    resident: Vec<usize>,
+    resident_spawned: bool,
+    loaded: HashMap<ChunkCoord, Vec<EntityId>>,
+}
+
+impl LevelStreamer {
+    pub fn new(level: &LevelConfig, chunk_size: f32, load_radius: i32) -> Self {
+        let mut chunks: HashMap<ChunkCoord, Vec<usize>> = HashMap::new();
+        let mut resident = Vec::new();
+
+        for (index, config) in level.entities.iter().enumerate() {
+            if matches!(config.entity_type, EntityType::Player) {
+                resident.push(index);
+            } else {
+                let [x, _, z] = config.transform.position;
+                chunks.entry(Self::chunk_at(chunk_size, Vector3::new(x, 0.0, z))).or_default().push(index);
+            }
+        }
+
+        Self {
+            chunk_size,
+            load_radius,
+            chunks,
+            resident,
+            resident_spawned: false,
+            loaded: HashMap::new(),
+        }
+    }
+
+    fn chunk_at(chunk_size: f32, position: Vector3) -> ChunkCoord {
+        (
+            (position.x / chunk_size).floor() as i32,
+            (position.z / chunk_size).floor() as i32,
+        )
+    }
+
+    /// Chunk containing a world position
+    pub fn chunk_of(&self, position: Vector3) -> ChunkCoord {
+        Self::chunk_at(self.chunk_size, position)
+    }
+
+    pub fn is_chunk_loaded(&self, chunk: ChunkCoord) -> bool {
+        self.loaded.contains_key(&chunk)
+    }
+
+    /// Load chunks near `focus` and unload those that fell out of range
+    pub fn update(&mut self, level: &LevelConfig, world: &mut World, focus: Vector3, state: &WorldState) {
+        let level_id = level.name.as_str();
+
+        if !self.resident_spawned {
+            for &index in &self.resident {
+                let config = &level.entities[index];
+                if !LevelLoader::is_removed(config, level_id, state) {
+                    LevelLoader::spawn_entity(config, level_id, world, state);
+                }
+            }
+            self.resident_spawned = true;
+        }
+
+        let (cx, cz) = self.chunk_of(focus);
+        let wanted: HashSet<ChunkCoord> = self
+            .chunks
+            .keys()
+            .filter(|(x, z)| (x - cx).abs() <= self.load_radius && (z - cz).abs() <= self.load_radius)
+            .copied()
+            .collect();
+
+        let stale: Vec<ChunkCoord> = self.loaded.keys().filter(|chunk| !wanted.contains(chunk)).copied().collect();
+        for chunk in stale {
+            for id in self.loaded.remove(&chunk).unwrap_or_default() {
+                world.remove_entity(id);
+            }
+        }
+
+        for chunk in wanted {
+            if self.loaded.contains_key(&chunk) {
+                continue;
+            }
+            let spawned = self.chunks[&chunk]
+                .iter()
+                .map(|&index| &level.entities[index])
+                .filter(|config| !LevelLoader::is_removed(config, level_id, state))
+                .map(|config| LevelLoader::spawn_entity(config, level_id, world, state))
+                .collect();
+            self.loaded.insert(chunk, spawned);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::components::Transform;
+    use crate::level::EntityConfig;
+
+    fn entity_at(name: &str, x: f32, z: f32, player: bool) -> EntityConfig {
+        let mut world = World::new();
+        let mut builder = world
+            .spawn()
+            .with_name(name.to_string())
+            .with_transform(Transform::new(Vector3::new(x, 0.0, z)));
+        if player {
+            builder = builder.as_player();
+        }
+        let id = builder.build();
+        EntityConfig::from_entity(world.get_entity(id).unwrap())
+    }
+
+    fn has(world: &World, name: &str) -> bool {
+        world.entities().any(|e| e.name.as_ref().is_some_and(|n| n.0 == name))
+    }
+
+    #[test]
+    fn test_streaming_follows_the_player() {
+        let level = LevelConfig {
+            entities: vec![
+                entity_at("hero", 0.0, 0.0, true),
+                entity_at("camp", 5.0, 5.0, false),
+                entity_at("ruins", 205.0, 5.0, false),
+            ],
+            ..Default::default()
+        };
+        let mut world = World::new();
+        let runtime = world.spawn().with_transform(Transform::new(Vector3::zero())).build();
+        let mut streamer = LevelStreamer::new(&level, 50.0, 1);
+        let state = WorldState::default();
+
+        streamer.update(&level, &mut world, Vector3::zero(), &state);
+        assert!(has(&world, "hero") && has(&world, "camp"));
+        assert!(!has(&world, "ruins"));
+
+        // Walk far east: the ruins stream in, the camp streams out
+        streamer.update(&level, &mut world, Vector3::new(210.0, 0.0, 0.0), &state);
+        assert!(has(&world, "ruins"));
+        assert!(!has(&world, "camp"));
+        assert!(!streamer.is_chunk_loaded((0, 0)));
+        assert!(streamer.is_chunk_loaded((4, 0)));
+
+        // The player and runtime entities are never unloaded
+        assert!(has(&world, "hero"));
+        assert!(world.get_entity(runtime).is_some());
+        assert_eq!(world.entities().filter(|e| e.is_player).count(), 1);
+    }
+}
@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Permanent changes to a single level
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LevelState {
+    #[serde(default)]
+    pub opened_chests: HashSet<String>,
+    #[serde(default)]
+    pub defeated_uniques: HashSet<String>,
+    #[serde(default)]
+    pub collected_items: HashSet<String>,
+}
+
+/// Permanent world changes that survive reloading a level, keyed by level id
+///
+/// Entities are matched by their `persistent_id` in the level config. Pass this to
+/// `LevelLoader::spawn_entities_with_state` so opened chests stay open and defeated
+/// unique enemies or picked-up unique items don't respawn. It is plain serde data,
+/// so it can be stored alongside the rest of a save.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorldState {
+    #[serde(default)]
+    pub levels: HashMap<String, LevelState>,
+}
+
+impl WorldState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Changes recorded for a level, if any
+    pub fn level(&self, level_id: &str) -> Option<&LevelState> {
+        self.levels.get(level_id)
+    }
+
+    fn level_mut(&mut self, level_id: &str) -> &mut LevelState {
+        self.levels.entry(level_id.to_string()).or_default()
+    }
+
+    pub fn mark_chest_opened(&mut self, level_id: &str, chest_id: &str) {
+        self.level_mut(level_id).opened_chests.insert(chest_id.to_string());
+    }
+
+    pub fn mark_unique_defeated(&mut self, level_id: &str, enemy_id: &str) {
+        self.level_mut(level_id).defeated_uniques.insert(enemy_id.to_string());
+    }
+
+    pub fn mark_item_collected(&mut self, level_id: &str, item_id: &str) {
+        self.level_mut(level_id).collected_items.insert(item_id.to_string());
+    }
+
+    pub fn is_chest_opened(&self, level_id: &str, chest_id: &str) -> bool {
+        self.level(level_id).is_some_and(|level| level.opened_chests.contains(chest_id))
+    }
+
+    pub fn is_unique_defeated(&self, level_id: &str, enemy_id: &str) -> bool {
+        self.level(level_id).is_some_and(|level| level.defeated_uniques.contains(enemy_id))
+    }
+
+    pub fn is_item_collected(&self, level_id: &str, item_id: &str) -> bool {
+        self.level(level_id).is_some_and(|level| level.collected_items.contains(item_id))
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize world state: {}", e))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Failed to parse world state: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecs::World;
+    use crate::ecs::components::{Container, Transform};
+    use crate::level::{CameraConfig, LevelConfig, LevelLoader};
+    use raylib::prelude::Vector3;
+
+    fn dungeon() -> LevelConfig {
+        let mut world = World::new();
+        for (id, x) in [("chest_a", 0.0), ("chest_b", 4.0)] {
+            world
+                .spawn()
+                .with_transform(Transform::new(Vector3::new(x, 0.0, 0.0)))
+                .with_persistent_id(id.to_string())
+                .with_container(Container::default())
+                .build();
+        }
+        world
+            .spawn()
+            .with_transform(Transform::new(Vector3::new(8.0, 0.0, 0.0)))
+            .with_persistent_id("ogre_king".to_string())
+            .as_enemy()
+            .build();
+        LevelConfig::from_world("dungeon", CameraConfig::default(), &world)
+    }
+
+    fn chest_open(world: &World, chest_id: &str) -> Option<bool> {
+        world
+            .entities()
+            .find(|e| e.persistent_id.as_ref().is_some_and(|id| id.0 == chest_id))
+            .and_then(|e| e.container)
+            .map(|container| container.is_open)
+    }
+
+    #[test]
+    fn test_opened_chest_stays_open_after_respawn() {
+        let level = dungeon();
+        let mut state = WorldState::new();
+        state.mark_chest_opened("dungeon", "chest_a");
+
+        // Survives being saved and loaded
+        let state = WorldState::from_json(&state.to_json().unwrap()).unwrap();
+
+        let mut world = World::new();
+        LevelLoader::spawn_entities_with_state(&level, &mut world, &state).unwrap();
+        assert_eq!(chest_open(&world, "chest_a"), Some(true));
+        assert_eq!(chest_open(&world, "chest_b"), Some(false));
+
+        // Other levels are unaffected
+        assert!(!state.is_chest_opened("crypt", "chest_a"));
+    }
+
+    #[test]
+    fn test_defeated_unique_does_not_respawn() {
+        let level = dungeon();
+        let mut state = WorldState::new();
+
+        let mut world = World::new();
+        LevelLoader::spawn_entities_with_state(&level, &mut world, &state).unwrap();
+        assert_eq!(world.enemies().count(), 1);
+
+        state.mark_unique_defeated("dungeon", "ogre_king");
+        let mut world = World::new();
+        LevelLoader::spawn_entities_with_state(&level, &mut world, &state).unwrap();
+        assert_eq!(world.enemies().count(), 0);
+        assert_eq!(world.entities().count(), 2);
+    }
+}
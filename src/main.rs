@@ -8,11 +8,14 @@ use level::*;
 use rpg::*;
 use terrain::*;
 
-// Import ECS types explicitly to avoid conflicts with Raylib types
-use ecs::entity::World;
-use ecs::components::{Transform as EcsTransform, Camera as EcsCamera};
-use ecs::systems::*;
-use ecs::physics::{PhysicsSystem, CollisionSystem, TerrainCollisionSystem};
+// The ECS prelude renames the types that clash with raylib; World is named
+// explicitly because the rpg module has its own
+use ecs::prelude::*;
+use ecs::World;
+use std::path::Path;
+
+/// Optional UI font; raylib's default font is used when it's missing
+const UI_FONT_PATH: &str = "assets/fonts/ui.ttf";
 
 fn main() {
     // Initialize window
@@ -23,6 +26,7 @@ fn main() {
 
     rl.set_target_fps(60);
     rl.disable_cursor(); // Lock cursor for first-person controls
+    let mut game_mode = GameMode::Playing;
 
     // Create ECS world
     let mut world = World::new();
@@ -31,11 +35,13 @@ fn main() {
     let level = LevelLoader::load_from_json("levels/sample.json").unwrap();
 
     // Spawn entities from level
-    LevelLoader::spawn_entities(&level, &mut world);
+    let level_terrain = LevelLoader::spawn_entities(&level, &mut world).unwrap();
 
-    // Create a camera entity attached to the player if player exists,
-    // otherwise create a standalone camera entity
-    let camera_entity_id = if let Some(player) = world.entities_mut().find(|e| e.is_player) {
+    // Use a camera saved with the level, otherwise attach one to the player if
+    // one exists, otherwise create a standalone camera entity
+    let camera_entity_id = if let Some(camera_id) = world.active_camera() {
+        camera_id
+    } else if let Some(player) = world.entities_mut().find(|e| e.is_player) {
         // Add camera to existing player
         player.camera = Some(EcsCamera::default());
         player.id
@@ -51,7 +57,7 @@ fn main() {
             .build()
     };
 
-    // Generate terrain first (before positioning entities)
+    // Use the level's terrain, or generate the default one (before positioning entities)
     println!("Generating terrain...");
     let terrain_config = TerrainConfig {
         width: 150,
@@ -63,8 +69,12 @@ fn main() {
         lacunarity: 2.0,
         noise_scale: 30.0,
         seed: 12345,
+        noise_mode: NoiseMode::Fractal,
+        moisture_scale: 60.0,
+        moisture_seed: 54321,
     };
-    let terrain = Terrain::generate(terrain_config);
+    let mut terrain = level_terrain.unwrap_or_else(|| Terrain::generate(terrain_config));
+    terrain.upload(&thread);
     println!("Terrain generated: {}x{} vertices", terrain.config.width, terrain.config.depth);
 
     // Position player at correct terrain height
@@ -76,14 +86,14 @@ fn main() {
         }
     }
 
-    // Create systems
-    let mut movement_system = MovementSystem;
-    let mut physics_system = PhysicsSystem::default();
-    let mut collision_system = CollisionSystem::new();
-    let terrain_collision_system = TerrainCollisionSystem::new();
-    let player_input_system = PlayerInputSystem;
-    let first_person_camera_system = FirstPersonCameraSystem;
+    // Create systems (gameplay systems live in the Simulation below)
     let mut render_system = RenderSystem::new();
+    render_system.preload_models(&world);
+    if !render_system.load_instance_shader("resources/shaders/instancing.vs", "resources/shaders/instancing.fs") {
+        println!("Instancing shader unavailable, drawing batches a mesh at a time");
+    }
+    let selection_system = SelectionSystem::new();
+    let mut combat_text = CombatTextSystem::new();
 
     // Create RPG player
     let mut rpg_player = Player::new("Hero", PlayerClass::Warrior);
@@ -106,14 +116,42 @@ fn main() {
     rpg_player.add_exp(250);
 
     // Add a status effect for demo
-    rpg_player.status_effects.push(TimedStatusEffect::new(
+    rpg_player.add_status_effect(TimedStatusEffect::new(
         StatusEffect::Blessed,
         15.0,
         5,
     ));
 
+    // Warrior skills on the hotbar
+    let skill_db = SkillDatabase::with_starter_skills();
+    let mut player_skills = PlayerSkills::new();
+    player_skills.learn_skill(SkillId(1)); // Power Strike
+    player_skills.learn_skill(SkillId(2)); // Whirlwind
+    player_skills.learn_skill(SkillId(100)); // Heal
+
     // Create RPG UI
     let mut rpg_ui = RpgUI::new();
+    if Path::new(UI_FONT_PATH).exists()
+        && let Err(e) = rpg_ui.load_font(&mut rl, &thread, UI_FONT_PATH)
+    {
+        println!("{}", e);
+    }
+
+    // Fog of war covering the terrain
+    let (terrain_min, terrain_max) = terrain.world_bounds();
+    let mut fog_of_war = FogOfWar::new(
+        terrain_min,
+        terrain_max,
+        4.0,
+        20.0,
+    );
+
+    // The world, terrain and gameplay systems advance together one tick per frame
+    let mut sim = Simulation::new(world).with_terrain(terrain);
+
+    // Controls; rebind with key_bindings.rebind(action, key)
+    let key_bindings = KeyBindings::default();
+    let input_config = InputConfig::default();
 
     // Debug flags
     let mut show_bounding_boxes = false;
@@ -122,10 +160,14 @@ fn main() {
     println!("  WASD - Move forward/backward/strafe");
     println!("  Space - Move up");
     println!("  Left Shift - Move down");
+    println!("  Left Ctrl - Sprint");
     println!("  Mouse - Look around (first-person)");
+    println!("  Left Click - Select target");
     println!("  I - Toggle Inventory");
     println!("  C - Toggle Character Sheet");
     println!("  B - Toggle Bounding Boxes");
+    println!("  M - Toggle Map");
+    println!("  1-5 - Use Skill");
     println!("  ESC - Exit");
 
     // Game loop
@@ -134,38 +176,114 @@ fn main() {
 
         // Update RPG player
         rpg_player.update(delta_time);
+        player_skills.update(delta_time);
+        // The UI redraws from scratch every frame, so it doesn't need change events yet
+        rpg_player.take_item_events();
 
         // Handle UI input
-        if rl.is_key_pressed(KeyboardKey::KEY_I) {
+        if is_action_pressed(&key_bindings, InputAction::ToggleInventory, &rl) {
             rpg_ui.show_inventory = !rpg_ui.show_inventory;
         }
-        if rl.is_key_pressed(KeyboardKey::KEY_C) {
+        if is_action_pressed(&key_bindings, InputAction::ToggleCharacterSheet, &rl) {
             rpg_ui.show_character_sheet = !rpg_ui.show_character_sheet;
         }
-        if rl.is_key_pressed(KeyboardKey::KEY_B) {
+        if is_action_pressed(&key_bindings, InputAction::ToggleBoundingBoxes, &rl) {
             show_bounding_boxes = !show_bounding_boxes;
         }
+        if is_action_pressed(&key_bindings, InputAction::ToggleFrustumCulling, &rl) {
+            render_system.set_frustum_culling(!render_system.frustum_culling());
+        }
+        if is_action_pressed(&key_bindings, InputAction::ToggleMap, &rl) {
+            rpg_ui.show_map = !rpg_ui.show_map;
+        }
+
+        // Cast hotbar skills; enemies live in the ECS, so only self-targeted skills
+        // can be cast from here (others are refused without using mana or cooldown)
+        for (slot, action) in InputAction::SKILL_SLOTS.into_iter().enumerate() {
+            if !is_action_pressed(&key_bindings, action, &rl) {
+                continue;
+            }
+            let Some(skill) = player_skills.slot_for_hotkey(slot + 1).and_then(|id| skill_db.get(id)) else {
+                continue;
+            };
+            if !player_skills.is_skill_ready(skill.id) {
+                continue;
+            }
+            match rpg_player.cast_self_skill(skill) {
+                Ok(()) => {
+                    player_skills.use_skill(skill.id, skill);
+                }
+                Err(e) => println!("{}", e),
+            }
+        }
+
+        // Click to equip/use, drag to rearrange
+        if rpg_ui.show_inventory {
+            let action = rpg_ui.handle_inventory_input(
+                &rpg_player.inventory,
+                &item_db,
+                rl.get_mouse_position(),
+                rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT),
+                rl.is_mouse_button_released(MouseButton::MOUSE_BUTTON_LEFT),
+            );
+            let result = match action {
+                Some(InventoryAction::Equip(slot)) => rpg_player.equip_item(slot, &item_db),
+                Some(InventoryAction::Use(slot)) => rpg_player
+                    .use_consumable(slot, &item_db)
+                    .map(|message| println!("{}", message))
+                    .map_err(|e| e.to_string()),
+                Some(InventoryAction::Move { from, to }) => {
+                    rpg_player.inventory.swap_slots(from, to);
+                    Ok(())
+                }
+                None => Ok(()),
+            };
+            if let Err(e) = result {
+                println!("{}", e);
+            }
+        }
+
+        // Free the cursor while a panel is open, recapture on close
+        let wanted_mode = if rpg_ui.any_panel_open() {
+            GameMode::Menu
+        } else {
+            GameMode::Playing
+        };
+        game_mode.apply(wanted_mode, &mut rl);
 
         // Update systems
-        first_person_camera_system.update(&mut world, &rl);
-        player_input_system.update(&mut world, &rl);
-        movement_system.update(&mut world, delta_time);
-        physics_system.update(&mut world, delta_time);
-        terrain_collision_system.apply_terrain_collision(&mut world, &terrain);
-        collision_system.update(&mut world, delta_time);
-
-        // Get camera from entity
-        let camera3d = if let Some(camera_entity) = world.get_entity(camera_entity_id) {
+        sim.mode = game_mode;
+        sim.tick(InputState::from_raylib_with_config(&rl, &input_config, &key_bindings), delta_time);
+        let Simulation { world, terrain, collision, .. } = &mut sim;
+
+        // Reveal fog of war around the player
+        let player_pos = world
+            .entities()
+            .find(|e| e.is_player)
+            .and_then(|e| e.transform.as_ref())
+            .map(|t| t.position);
+        if let Some(pos) = player_pos {
+            fog_of_war.update(pos);
+        }
+
+        // Damage and healing numbers
+        combat_text.update(world, delta_time);
+
+        // Get camera from the highest-priority active camera entity, falling back to the startup one
+        let view_entity_id = world.active_camera().unwrap_or(camera_entity_id);
+        let (camera3d, near_clip, far_clip) = if let Some(camera_entity) = world.get_entity(view_entity_id) {
             if let (Some(transform), Some(camera)) = (&camera_entity.transform, &camera_entity.camera) {
-                camera.to_camera3d(transform.position)
+                (camera.to_camera3d(transform.position), camera.near, camera.far)
             } else {
                 // Fallback camera if components are missing
-                level.camera.to_camera3d()
+                (level.camera.to_camera3d(), level.camera.near, level.camera.far)
             }
         } else {
             // Fallback camera if entity was removed
-            level.camera.to_camera3d()
+            (level.camera.to_camera3d(), level.camera.near, level.camera.far)
         };
+        let aspect = rl.get_screen_width() as f32 / rl.get_screen_height() as f32;
+        selection_system.update(world, &rl, &camera3d, game_mode);
 
         // Render
         let mut d = rl.begin_drawing(&thread);
@@ -173,44 +291,90 @@ fn main() {
         d.clear_background(Color::RAYWHITE);
 
         {
+            RenderSystem::apply_clip_planes(near_clip, far_clip);
             let mut d3 = d.begin_mode3D(camera3d);
+            render_system.set_camera(&camera3d, aspect, near_clip, far_clip);
 
             // Render terrain
-            terrain.render(&mut d3);
+            if let Some(terrain) = terrain {
+                terrain.render(&mut d3);
+            }
 
             // Render all entities
 <<<<<<< HEAD
-            render_system.render(&world, &mut d3);
+            render_system.render(world, &mut d3);
 =======
-            render_system.render(&world, &mut d3, show_bounding_boxes);
+            render_system.render(world, &mut d3, show_bounding_boxes);
 
             // Draw grid
             d3.draw_grid(20, 1.0);
 >>>>>>> 53ede003f87fa45e85441f5a720417c7369688d7
+
+            SelectionSystem::render(world, &mut d3);
         }
 
+        combat_text.render(&mut d, camera3d);
+
         // Draw UI
         d.draw_fps(10, 10);
+        if show_bounding_boxes {
+            let stats = collision.stats();
+            d.draw_text(
+                &format!(
+                    "pairs {} / broadphase {} / resolved {}",
+                    stats.pairs_tested, stats.broadphase_pairs, stats.collisions_resolved
+                ),
+                10,
+                30,
+                16,
+                Color::LIGHTGRAY,
+            );
+            d.draw_text(
+                &format!(
+                    "culled {} / batch draw calls {}",
+                    render_system.culled_count(),
+                    render_system.batch_draw_calls()
+                ),
+                10,
+                50,
+                16,
+                Color::LIGHTGRAY,
+            );
+        }
 
         // Draw RPG HUD - always visible
-        RpgUI::draw_player_hud(&mut d, &rpg_player);
+        rpg_ui.render_player_hud(&mut d, &rpg_player);
 
         // Draw status effects in top right
         if rpg_ui.show_status_effects {
-            RpgUI::draw_status_effects(&mut d, &rpg_player, 950, 10);
+            rpg_ui.render_status_effects(&mut d, &rpg_player, 950, 10);
         }
 
         // Draw quick stats at bottom
-        RpgUI::draw_quick_stats(&mut d, &rpg_player, 10, 650);
+        rpg_ui.render_quick_stats(&mut d, &rpg_player, 10, 650);
+
+        // Skill hotbar along the bottom
+        rpg_ui.render_skill_bar(&mut d, &player_skills, &skill_db, rpg_player.stats.current_mana, 450, 650);
+
+        // Draw the minimap and explored map in the bottom right
+        if rpg_ui.show_map
+            && let Some(pos) = player_pos
+        {
+            rpg_ui.render_fog_map(&mut d, &fog_of_war, pos, 1100, 520, 160);
+            if let Some(terrain) = terrain.as_ref() {
+                rpg_ui.render_minimap(&mut d, world, pos, terrain, MapView::new(1100, 330, 160));
+            }
+        }
 
         // Draw inventory panel if toggled
         if rpg_ui.show_inventory {
-            RpgUI::draw_inventory(&mut d, &rpg_player.inventory, &item_db);
+            let mouse = d.get_mouse_position();
+            rpg_ui.render_inventory(&mut d, &rpg_player.inventory, &item_db, mouse);
         }
 
         // Draw character sheet if toggled
         if rpg_ui.show_character_sheet {
-            RpgUI::draw_character_sheet(&mut d, &rpg_player);
+            rpg_ui.render_character_sheet(&mut d, &rpg_player);
         }
 
         // Help text
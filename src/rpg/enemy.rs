@@ -86,6 +86,10 @@ pub struct Enemy {
     pub spawn_position: Position,
     pub target_entity: Option<EntityId>,
     pub patrol_timer: f32,
+    /// Fixed patrol route; when empty, Patrol enemies wander to random points near spawn
+    pub patrol_waypoints: Vec<Position>,
+    /// Index of the waypoint currently being walked to
+    pub current_waypoint: usize,
     pub status_effects: Vec<TimedStatusEffect>,
 }
 
@@ -103,12 +107,29 @@ impl Enemy {
             spawn_position: position,
             target_entity: None,
             patrol_timer: 0.0,
+            patrol_waypoints: Vec::new(),
+            current_waypoint: 0,
             status_effects: Vec::new(),
         }
     }
 
+    /// Patrol a fixed looping route instead of random points (mirrors the ecs PatrolPath)
+    pub fn with_patrol_waypoints(mut self, waypoints: Vec<Position>) -> Self {
+        self.patrol_waypoints = waypoints;
+        self.current_waypoint = 0;
+        self
+    }
+
     /// Update enemy AI and state
-    pub fn update(&mut self, delta_time: f32, player_pos: &Position, def: &EnemyDef, my_pos: &Position) -> Option<EnemyAction> {
+    /// Random choices (patrol targets) are drawn from `rng` so runs are reproducible
+    pub fn update(
+        &mut self,
+        delta_time: f32,
+        player_pos: &Position,
+        def: &EnemyDef,
+        my_pos: &Position,
+        rng: &mut GameRng,
+    ) -> Option<EnemyAction> {
         // Update status effects
         self.update_status_effects(delta_time);
 
@@ -160,15 +181,11 @@ impl Enemy {
                     }
                 } else {
                     self.state = EnemyState::Patrolling;
-                    if self.patrol_timer <= 0.0 {
+                    if !self.patrol_waypoints.is_empty() {
+                        Some(EnemyAction::MoveTowards(self.next_patrol_waypoint(my_pos)))
+                    } else if self.patrol_timer <= 0.0 {
                         self.patrol_timer = 3.0;
-                        // Pick random point near spawn
-                        let mut rng = rand::thread_rng();
-                        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
-                        let distance = rng.gen_range(0.0..def.patrol_range);
-                        let target_x = self.spawn_position.x + angle.cos() * distance;
-                        let target_y = self.spawn_position.y + angle.sin() * distance;
-                        Some(EnemyAction::MoveTowards(raylib::prelude::Vector2::new(target_x, target_y)))
+                        Some(EnemyAction::MoveTowards(self.random_patrol_target(def, rng)))
                     } else {
                         None
                     }
@@ -234,6 +251,27 @@ impl Enemy {
         }
     }
 
+    /// Distance at which a patrol waypoint counts as reached
+    const WAYPOINT_REACHED_DISTANCE: f32 = 8.0;
+
+    /// Current fixed waypoint, advancing (and looping) once it's reached
+    fn next_patrol_waypoint(&mut self, my_pos: &Position) -> raylib::prelude::Vector2 {
+        let count = self.patrol_waypoints.len();
+        if my_pos.distance_to(&self.patrol_waypoints[self.current_waypoint % count]) < Self::WAYPOINT_REACHED_DISTANCE {
+            self.current_waypoint = (self.current_waypoint + 1) % count;
+        }
+        self.patrol_waypoints[self.current_waypoint % count].as_vector2()
+    }
+
+    /// Random point within patrol range of the spawn position
+    fn random_patrol_target(&self, def: &EnemyDef, rng: &mut GameRng) -> raylib::prelude::Vector2 {
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        let distance = rng.gen_range(0.0..def.patrol_range);
+        let target_x = self.spawn_position.x + angle.cos() * distance;
+        let target_y = self.spawn_position.y + angle.sin() * distance;
+        raylib::prelude::Vector2::new(target_x, target_y)
+    }
+
     /// Update status effects
     fn update_status_effects(&mut self, delta_time: f32) {
         let mut effects_to_remove = Vec::new();
@@ -246,7 +284,7 @@ impl Enemy {
             if effect.should_tick(delta_time) {
                 match effect.effect {
                     StatusEffect::Poisoned | StatusEffect::Burning => {
-                        self.stats.take_damage(effect.power);
+                        self.stats.take_dot_damage(effect.power);
                     }
                     StatusEffect::Regenerating => {
                         self.stats.heal(effect.power);
@@ -266,9 +304,9 @@ impl Enemy {
         self.status_effects.iter().any(|e| e.effect == effect)
     }
 
-    /// Add a status effect
+    /// Add a status effect, following its `StackPolicy`
     pub fn add_status_effect(&mut self, effect: TimedStatusEffect) {
-        self.status_effects.push(effect);
+        apply_status_effect(&mut self.status_effects, effect);
     }
 
     /// Take damage and return if still alive
@@ -392,3 +430,60 @@ impl Default for EnemyDatabase {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patrol_def() -> EnemyDef {
+        EnemyDef::new(2, "Skeleton", 1).with_behavior(AIBehavior::Patrol).with_aggro_range(50.0)
+    }
+
+    fn move_target(action: Option<EnemyAction>) -> raylib::prelude::Vector2 {
+        match action {
+            Some(EnemyAction::MoveTowards(target)) => target,
+            other => panic!("expected a move, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_seeded_patrol_targets_match() {
+        let def = patrol_def();
+        let spawn = Position::new(100.0, 100.0);
+        let far_player = Position::new(1000.0, 1000.0);
+
+        let mut enemy_a = Enemy::from_def(EntityId(1), &def, spawn);
+        let mut enemy_b = Enemy::from_def(EntityId(2), &def, spawn);
+        let mut rng_a = GameRng::new(42);
+        let mut rng_b = GameRng::new(42);
+
+        for _ in 0..3 {
+            let a = move_target(enemy_a.update(3.0, &far_player, &def, &spawn, &mut rng_a));
+            let b = move_target(enemy_b.update(3.0, &far_player, &def, &spawn, &mut rng_b));
+            assert_eq!(a, b);
+            assert!(spawn.distance_to(&Position::from(a)) <= def.patrol_range);
+        }
+    }
+
+    #[test]
+    fn test_fixed_waypoints_visited_in_order() {
+        let def = patrol_def();
+        let far_player = Position::new(1000.0, 1000.0);
+        let waypoints = vec![Position::new(0.0, 0.0), Position::new(50.0, 0.0), Position::new(50.0, 50.0)];
+        let mut enemy = Enemy::from_def(EntityId(1), &def, Position::new(0.0, 0.0))
+            .with_patrol_waypoints(waypoints.clone());
+        let mut rng = GameRng::new(7);
+
+        // Standing on the first waypoint heads for the second, and so on, looping back
+        let mut position = waypoints[0];
+        let mut visited = Vec::new();
+        for _ in 0..4 {
+            let target = move_target(enemy.update(0.016, &far_player, &def, &position, &mut rng));
+            visited.push(target);
+            position = Position::from(target);
+        }
+
+        let expected: Vec<_> = [1, 2, 0, 1].iter().map(|&i| waypoints[i].as_vector2()).collect();
+        assert_eq!(visited, expected);
+    }
+}
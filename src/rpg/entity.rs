@@ -3,6 +3,7 @@
 
 use std::collections::HashMap;
 use raylib::prelude::*;
+use crate::ecs::aabb::Aabb2;
 
 /// Unique identifier for entities
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
@@ -117,6 +118,14 @@ impl Sprite {
             source_rect: None,
         }
     }
+
+    /// Screen-space bounds of the sprite drawn at `position` (its top-left corner)
+    pub fn bounds(&self, position: &Position) -> Aabb2 {
+        Aabb2::new(
+            position.as_vector2(),
+            Vector2::new(position.x + self.width, position.y + self.height),
+        )
+    }
 }
 
 /// Simple component storage system
@@ -221,6 +230,15 @@ impl World {
         }
     }
 
+    /// Entities whose sprite bounds overlap `area`
+    pub fn entities_in_area(&self, area: &Aabb2) -> Vec<EntityId> {
+        self.entities_with_sprite()
+            .into_iter()
+            .filter(|(_, position, sprite)| sprite.bounds(position).intersects(area))
+            .map(|(id, _, _)| id)
+            .collect()
+    }
+
     /// Get all entities with position and sprite (for rendering)
     pub fn entities_with_sprite(&self) -> Vec<(EntityId, &Position, &Sprite)> {
         self.sprites
@@ -0,0 +1,161 @@
+/// Fog of War
+/// Tracks which parts of the world the player has explored
+
+use raylib::prelude::*;
+
+/// Grid of explored cells covering a rectangular area of the world (X/Z plane)
+#[derive(Debug, Clone)]
+pub struct FogOfWar {
+    pub origin: Vector2,     // World X/Z of the minimum corner of cell (0, 0)
+    pub cell_size: f32,
+    pub width: usize,        // Cells along X
+    pub depth: usize,        // Cells along Z
+    pub reveal_radius: f32,  // World-space radius revealed around the player
+    explored: Vec<bool>,
+}
+
+impl FogOfWar {
+    /// Create a fully hidden grid covering `min` to `max` (world X/Z)
+    pub fn new(min: Vector2, max: Vector2, cell_size: f32, reveal_radius: f32) -> Self {
+        let width = ((max.x - min.x) / cell_size).ceil().max(1.0) as usize;
+        let depth = ((max.y - min.y) / cell_size).ceil().max(1.0) as usize;
+
+        Self {
+            origin: min,
+            cell_size,
+            width,
+            depth,
+            reveal_radius,
+            explored: vec![false; width * depth],
+        }
+    }
+
+    /// Convert a world position to a cell, or None if outside the grid
+    pub fn world_to_cell(&self, world_x: f32, world_z: f32) -> Option<(usize, usize)> {
+        let cx = ((world_x - self.origin.x) / self.cell_size).floor();
+        let cz = ((world_z - self.origin.y) / self.cell_size).floor();
+
+        if cx < 0.0 || cz < 0.0 || cx >= self.width as f32 || cz >= self.depth as f32 {
+            return None;
+        }
+
+        Some((cx as usize, cz as usize))
+    }
+
+    /// World X/Z of the center of a cell
+    pub fn cell_center(&self, cx: usize, cz: usize) -> Vector2 {
+        Vector2::new(
+            self.origin.x + (cx as f32 + 0.5) * self.cell_size,
+            self.origin.y + (cz as f32 + 0.5) * self.cell_size,
+        )
+    }
+
+    /// Reveal cells around the player's position
+    pub fn update(&mut self, player_pos: Vector3) {
+        self.reveal(player_pos.x, player_pos.z, self.reveal_radius);
+    }
+
+    /// Reveal every cell whose center lies within `radius` of a world position
+    pub fn reveal(&mut self, world_x: f32, world_z: f32, radius: f32) {
+        let min_x = ((world_x - radius - self.origin.x) / self.cell_size).floor().max(0.0) as usize;
+        let min_z = ((world_z - radius - self.origin.y) / self.cell_size).floor().max(0.0) as usize;
+        let max_x = ((world_x + radius - self.origin.x) / self.cell_size).ceil().max(0.0) as usize;
+        let max_z = ((world_z + radius - self.origin.y) / self.cell_size).ceil().max(0.0) as usize;
+
+        let radius_sq = radius * radius;
+
+        for cx in min_x..max_x.min(self.width) {
+            for cz in min_z..max_z.min(self.depth) {
+                let center = self.cell_center(cx, cz);
+                let dx = center.x - world_x;
+                let dz = center.y - world_z;
+
+                if dx * dx + dz * dz <= radius_sq {
+                    self.explored[cz * self.width + cx] = true;
+                }
+            }
+        }
+    }
+
+    /// Check whether a cell has been explored
+    pub fn is_explored(&self, cx: usize, cz: usize) -> bool {
+        cx < self.width && cz < self.depth && self.explored[cz * self.width + cx]
+    }
+
+    /// Check whether a world position has been explored
+    pub fn is_explored_at(&self, world_x: f32, world_z: f32) -> bool {
+        self.world_to_cell(world_x, world_z)
+            .map(|(cx, cz)| self.is_explored(cx, cz))
+            .unwrap_or(false)
+    }
+
+    /// Fraction of the map explored (0.0 - 1.0)
+    pub fn explored_fraction(&self) -> f32 {
+        let count = self.explored.iter().filter(|e| **e).count();
+        count as f32 / self.explored.len() as f32
+    }
+
+    /// Hide everything again
+    pub fn reset(&mut self) {
+        self.explored.fill(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_fog() -> FogOfWar {
+        FogOfWar::new(Vector2::new(-50.0, -50.0), Vector2::new(50.0, 50.0), 2.0, 6.0)
+    }
+
+    #[test]
+    fn test_world_to_cell() {
+        let fog = test_fog();
+        assert_eq!(fog.width, 50);
+        assert_eq!(fog.depth, 50);
+
+        assert_eq!(fog.world_to_cell(-50.0, -50.0), Some((0, 0)));
+        assert_eq!(fog.world_to_cell(0.0, 0.0), Some((25, 25)));
+        assert_eq!(fog.world_to_cell(-1.0, 3.5), Some((24, 26)));
+        assert_eq!(fog.world_to_cell(49.9, 49.9), Some((49, 49)));
+        assert_eq!(fog.world_to_cell(50.0, 0.0), None);
+        assert_eq!(fog.world_to_cell(-50.1, 0.0), None);
+
+        let center = fog.cell_center(25, 25);
+        assert_eq!(fog.world_to_cell(center.x, center.y), Some((25, 25)));
+    }
+
+    #[test]
+    fn test_reveal_within_radius() {
+        let mut fog = test_fog();
+        assert!(!fog.is_explored_at(0.0, 0.0));
+
+        fog.update(Vector3::new(0.0, 10.0, 0.0));
+
+        assert!(fog.is_explored_at(0.0, 0.0));
+        assert!(fog.is_explored_at(4.0, 0.0));
+        assert!(fog.is_explored_at(0.0, -4.0));
+        // Just outside the radius and far away stay hidden
+        assert!(!fog.is_explored_at(8.0, 0.0));
+        assert!(!fog.is_explored_at(40.0, 40.0));
+    }
+
+    #[test]
+    fn test_moving_reveals_path() {
+        let mut fog = test_fog();
+        for step in 0..10 {
+            fog.update(Vector3::new(-40.0 + step as f32 * 4.0, 0.0, 0.0));
+        }
+
+        assert!(fog.is_explored_at(-40.0, 0.0));
+        assert!(fog.is_explored_at(-4.0, 0.0));
+        assert!(!fog.is_explored_at(-20.0, 30.0));
+
+        let fraction = fog.explored_fraction();
+        assert!(fraction > 0.0 && fraction < 0.2);
+
+        fog.reset();
+        assert_eq!(fog.explored_fraction(), 0.0);
+    }
+}
@@ -3,9 +3,10 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use super::stats::Stats;
 
-/// Types of items in the game
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Types of items in the game (declaration order is the inventory sort order)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum ItemType {
     Weapon,
     Armor,
@@ -79,6 +80,51 @@ pub struct StatModifiers {
     pub dodge_chance: f32,
     pub crit_chance: f32,
     pub move_speed: f32,
+    /// Percentage bonuses, applied after all flat modifiers
+    #[serde(default)]
+    pub percent: PercentModifiers,
+}
+
+/// Percentage stat bonuses (0.5 = +50%)
+///
+/// Order of operations when computing effective stats:
+/// 1. base stats are recalculated from attributes
+/// 2. flat `StatModifiers` from every source are added
+/// 3. percentages from every source are summed, then each stat is multiplied by
+///    `1 + total`, so two +50% items give +100% rather than +125%
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct PercentModifiers {
+    pub max_health: f32,
+    pub max_mana: f32,
+    pub physical_damage: f32,
+    pub magic_damage: f32,
+    pub defense: f32,
+    pub move_speed: f32,
+}
+
+impl PercentModifiers {
+    /// Sum of two sets of bonuses (percentages stack additively)
+    pub fn combined(&self, other: &PercentModifiers) -> Self {
+        Self {
+            max_health: self.max_health + other.max_health,
+            max_mana: self.max_mana + other.max_mana,
+            physical_damage: self.physical_damage + other.physical_damage,
+            magic_damage: self.magic_damage + other.magic_damage,
+            defense: self.defense + other.defense,
+            move_speed: self.move_speed + other.move_speed,
+        }
+    }
+
+    /// Multiply the post-flat stats by these bonuses
+    pub fn apply_to(&self, stats: &mut Stats) {
+        let scale = |value: i32, percent: f32| (value as f32 * (1.0 + percent)).round() as i32;
+        stats.max_health = scale(stats.max_health, self.max_health);
+        stats.max_mana = scale(stats.max_mana, self.max_mana);
+        stats.physical_damage = scale(stats.physical_damage, self.physical_damage);
+        stats.magic_damage = scale(stats.magic_damage, self.magic_damage);
+        stats.defense = scale(stats.defense, self.defense);
+        stats.move_speed *= 1.0 + self.move_speed;
+    }
 }
 
 impl StatModifiers {
@@ -86,6 +132,43 @@ impl StatModifiers {
         Self::default()
     }
 
+    /// Sum of two sets of modifiers
+    pub fn combined(&self, other: &StatModifiers) -> Self {
+        Self {
+            strength: self.strength + other.strength,
+            dexterity: self.dexterity + other.dexterity,
+            intelligence: self.intelligence + other.intelligence,
+            vitality: self.vitality + other.vitality,
+            luck: self.luck + other.luck,
+            max_health: self.max_health + other.max_health,
+            max_mana: self.max_mana + other.max_mana,
+            physical_damage: self.physical_damage + other.physical_damage,
+            magic_damage: self.magic_damage + other.magic_damage,
+            defense: self.defense + other.defense,
+            dodge_chance: self.dodge_chance + other.dodge_chance,
+            crit_chance: self.crit_chance + other.crit_chance,
+            move_speed: self.move_speed + other.move_speed,
+            percent: self.percent.combined(&other.percent),
+        }
+    }
+
+    /// Add the flat modifiers onto a set of stats (percentages are not applied)
+    pub fn add_to(&self, stats: &mut Stats) {
+        stats.strength += self.strength;
+        stats.dexterity += self.dexterity;
+        stats.intelligence += self.intelligence;
+        stats.vitality += self.vitality;
+        stats.luck += self.luck;
+        stats.max_health += self.max_health;
+        stats.max_mana += self.max_mana;
+        stats.physical_damage += self.physical_damage;
+        stats.magic_damage += self.magic_damage;
+        stats.defense += self.defense;
+        stats.dodge_chance += self.dodge_chance;
+        stats.crit_chance += self.crit_chance;
+        stats.move_speed += self.move_speed;
+    }
+
     /// Create modifiers with scaled stats based on rarity
     pub fn scaled(base_stats: StatModifiers, rarity: Rarity) -> Self {
         let mult = rarity.stat_multiplier();
@@ -103,6 +186,14 @@ impl StatModifiers {
             dodge_chance: base_stats.dodge_chance * mult,
             crit_chance: base_stats.crit_chance * mult,
             move_speed: base_stats.move_speed * mult,
+            percent: PercentModifiers {
+                max_health: base_stats.percent.max_health * mult,
+                max_mana: base_stats.percent.max_mana * mult,
+                physical_damage: base_stats.percent.physical_damage * mult,
+                magic_damage: base_stats.percent.magic_damage * mult,
+                defense: base_stats.percent.defense * mult,
+                move_speed: base_stats.percent.move_speed * mult,
+            },
         }
     }
 }
@@ -178,7 +269,7 @@ impl ItemDef {
 }
 
 /// An instance of an item in someone's inventory
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ItemStack {
     pub item_id: ItemId,
     pub quantity: u32,
@@ -194,12 +285,63 @@ impl ItemStack {
     }
 }
 
+/// Currency types a player can hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Currency {
+    Gold,
+    Gems,
+}
+
+/// Balances for every currency (missing entries are zero)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Currencies {
+    balances: HashMap<Currency, i32>,
+}
+
+impl Currencies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn balance(&self, currency: Currency) -> i32 {
+        self.balances.get(&currency).copied().unwrap_or(0)
+    }
+
+    /// Add (or with a negative amount, remove) currency; balances never go below zero
+    pub fn add(&mut self, currency: Currency, amount: i32) {
+        let balance = (self.balance(currency) + amount).max(0);
+        self.balances.insert(currency, balance);
+    }
+
+    /// Try to spend currency, returns true if there was enough
+    pub fn spend(&mut self, currency: Currency, amount: i32) -> bool {
+        if amount < 0 || self.balance(currency) < amount {
+            return false;
+        }
+        self.balances.insert(currency, self.balance(currency) - amount);
+        true
+    }
+}
+
+/// Change notification so UIs and stat recalculation only react when needed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemEvent {
+    /// Items were added to or removed from the inventory
+    InventoryChanged,
+    /// The item in this equipment slot changed
+    EquipmentChanged { slot: EquipSlot },
+}
+
 /// Inventory system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Inventory {
     pub max_slots: usize,
     pub items: Vec<Option<ItemStack>>,
-    pub gold: i32,
+    #[serde(default)]
+    pub currencies: Currencies,
+    /// Pending change events, drained with `take_events`
+    #[serde(skip)]
+    events: Vec<ItemEvent>,
 }
 
 impl Inventory {
@@ -207,13 +349,27 @@ impl Inventory {
         Self {
             max_slots,
             items: vec![None; max_slots],
-            gold: 0,
+            currencies: Currencies::new(),
+            events: Vec::new(),
         }
     }
 
+    /// Take the change events emitted since the last call
+    pub fn take_events(&mut self) -> Vec<ItemEvent> {
+        std::mem::take(&mut self.events)
+    }
+
     /// Try to add an item to inventory
     /// Returns the number of items that couldn't be added (0 if all added successfully)
-    pub fn add_item(&mut self, item_def: &ItemDef, mut quantity: u32) -> u32 {
+    pub fn add_item(&mut self, item_def: &ItemDef, quantity: u32) -> u32 {
+        let remaining = self.add_item_quiet(item_def, quantity);
+        if remaining < quantity {
+            self.events.push(ItemEvent::InventoryChanged);
+        }
+        remaining
+    }
+
+    fn add_item_quiet(&mut self, item_def: &ItemDef, mut quantity: u32) -> u32 {
         // First try to stack with existing items
         for slot in &mut self.items {
             if let Some(stack) = slot {
@@ -247,7 +403,15 @@ impl Inventory {
 
     /// Remove an item from inventory by slot index
     pub fn remove_item(&mut self, slot: usize, quantity: u32) -> Option<ItemStack> {
-        if slot >= self.items.len() {
+        let removed = self.remove_item_quiet(slot, quantity);
+        if removed.is_some() {
+            self.events.push(ItemEvent::InventoryChanged);
+        }
+        removed
+    }
+
+    fn remove_item_quiet(&mut self, slot: usize, quantity: u32) -> Option<ItemStack> {
+        if slot >= self.items.len() || quantity == 0 {
             return None;
         }
 
@@ -265,6 +429,107 @@ impl Inventory {
         }
     }
 
+    /// Merge partial stacks of the same item, up to each item's `max_stack`
+    ///
+    /// Stack sizes come from the item database; items it doesn't know are left
+    /// as they are. Merged stacks keep the position of their first slot and
+    /// empty slots move to the end.
+    pub fn consolidate(&mut self, db: &ItemDatabase) {
+        let before = self.items.clone();
+        let mut merged: Vec<ItemStack> = Vec::with_capacity(self.items.len());
+
+        for stack in self.items.iter_mut().filter_map(Option::take) {
+            let Some(def) = db.get(stack.item_id) else {
+                merged.push(stack);
+                continue;
+            };
+            let mut quantity = stack.quantity;
+            for existing in merged.iter_mut().filter(|s| s.item_id == stack.item_id) {
+                let amount = quantity.min(def.max_stack.saturating_sub(existing.quantity));
+                existing.quantity += amount;
+                quantity -= amount;
+            }
+            if quantity > 0 {
+                merged.push(ItemStack::new(stack.item_id, quantity));
+            }
+        }
+
+        self.replace_slots(before, merged);
+    }
+
+    /// Order slots by item type, then rarity, then name, with empty slots last
+    ///
+    /// Stacks of the same item keep the fuller stack first. Items missing from the
+    /// database sort after known items. Call `consolidate` first to also merge stacks.
+    pub fn sort(&mut self, db: &ItemDatabase) {
+        let before = self.items.clone();
+        let mut stacks: Vec<ItemStack> = self.items.iter_mut().filter_map(Option::take).collect();
+        stacks.sort_by(|a, b| {
+            let key = |stack: &ItemStack| {
+                db.get(stack.item_id)
+                    .map(|def| (def.item_type, def.rarity, def.name.clone()))
+            };
+            match (key(a), key(b)) {
+                (Some(ka), Some(kb)) => ka.cmp(&kb),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            }
+            .then(a.item_id.0.cmp(&b.item_id.0))
+            .then(b.quantity.cmp(&a.quantity))
+        });
+
+        self.replace_slots(before, stacks);
+    }
+
+    /// Swap the contents of two slots (either may be empty)
+    /// Returns false if either slot is out of range
+    pub fn swap_slots(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.items.len() || to >= self.items.len() {
+            return false;
+        }
+        if self.items[from] != self.items[to] {
+            self.items.swap(from, to);
+            self.events.push(ItemEvent::InventoryChanged);
+        }
+        true
+    }
+
+    /// Lay `stacks` out from the first slot, emitting a change event if the
+    /// slots differ from `before`
+    fn replace_slots(&mut self, before: Vec<Option<ItemStack>>, stacks: Vec<ItemStack>) {
+        let len = self.items.len();
+        self.items = stacks.into_iter().map(Some).collect();
+        self.items.resize(len, None);
+        if self.items != before {
+            self.events.push(ItemEvent::InventoryChanged);
+        }
+    }
+
+    /// Remove `quantity` of an item across all slots
+    /// Returns false and changes nothing if there aren't enough
+    pub fn take_item(&mut self, item_id: ItemId, quantity: u32) -> bool {
+        if self.count_item(item_id) < quantity {
+            return false;
+        }
+
+        let mut remaining = quantity;
+        for slot in 0..self.items.len() {
+            if remaining == 0 {
+                break;
+            }
+            if self.items[slot].as_ref().is_some_and(|stack| stack.item_id == item_id)
+                && let Some(taken) = self.remove_item_quiet(slot, remaining)
+            {
+                remaining -= taken.quantity;
+            }
+        }
+        if quantity > 0 {
+            self.events.push(ItemEvent::InventoryChanged);
+        }
+        true
+    }
+
     /// Get item at slot
     pub fn get_item(&self, slot: usize) -> Option<&ItemStack> {
         self.items.get(slot).and_then(|s| s.as_ref())
@@ -285,19 +550,19 @@ impl Inventory {
         self.items.iter().any(|slot| slot.is_none())
     }
 
+    /// Current gold balance
+    pub fn gold(&self) -> i32 {
+        self.currencies.balance(Currency::Gold)
+    }
+
     /// Add gold
     pub fn add_gold(&mut self, amount: i32) {
-        self.gold = (self.gold + amount).max(0);
+        self.currencies.add(Currency::Gold, amount);
     }
 
     /// Try to spend gold, returns true if successful
     pub fn spend_gold(&mut self, amount: i32) -> bool {
-        if self.gold >= amount {
-            self.gold -= amount;
-            true
-        } else {
-            false
-        }
+        self.currencies.spend(Currency::Gold, amount)
     }
 }
 
@@ -305,23 +570,40 @@ impl Inventory {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Equipment {
     pub slots: HashMap<EquipSlot, ItemId>,
+    /// Pending change events, drained with `take_events`
+    #[serde(skip)]
+    events: Vec<ItemEvent>,
 }
 
 impl Equipment {
     pub fn new() -> Self {
         Self {
             slots: HashMap::new(),
+            events: Vec::new(),
         }
     }
 
+    /// Take the change events emitted since the last call
+    pub fn take_events(&mut self) -> Vec<ItemEvent> {
+        std::mem::take(&mut self.events)
+    }
+
     /// Equip an item to a slot
     pub fn equip(&mut self, slot: EquipSlot, item_id: ItemId) -> Option<ItemId> {
-        self.slots.insert(slot, item_id)
+        let previous = self.slots.insert(slot, item_id);
+        if previous != Some(item_id) {
+            self.events.push(ItemEvent::EquipmentChanged { slot });
+        }
+        previous
     }
 
     /// Unequip an item from a slot
     pub fn unequip(&mut self, slot: EquipSlot) -> Option<ItemId> {
-        self.slots.remove(&slot)
+        let removed = self.slots.remove(&slot);
+        if removed.is_some() {
+            self.events.push(ItemEvent::EquipmentChanged { slot });
+        }
+        removed
     }
 
     /// Get equipped item in a slot
@@ -363,6 +645,20 @@ impl ItemDatabase {
         self.items.get(&id)
     }
 
+    /// All items of a type, ordered by id
+    pub fn items_by_type(&self, item_type: ItemType) -> Vec<&ItemDef> {
+        let mut items: Vec<&ItemDef> = self.items.values().filter(|item| item.item_type == item_type).collect();
+        items.sort_by_key(|item| item.id.0);
+        items
+    }
+
+    /// All items of a rarity, ordered by id
+    pub fn items_by_rarity(&self, rarity: Rarity) -> Vec<&ItemDef> {
+        let mut items: Vec<&ItemDef> = self.items.values().filter(|item| item.rarity == rarity).collect();
+        items.sort_by_key(|item| item.id.0);
+        items
+    }
+
     /// Create a database with some starter items
     pub fn with_starter_items() -> Self {
         let mut db = Self::new();
@@ -464,3 +760,126 @@ impl Default for ItemDatabase {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overspending_gold_fails() {
+        let mut inventory = Inventory::new(4);
+        inventory.add_gold(50);
+
+        assert!(!inventory.spend_gold(80));
+        assert_eq!(inventory.gold(), 50);
+        assert!(inventory.spend_gold(50));
+        assert_eq!(inventory.gold(), 0);
+        assert!(!inventory.spend_gold(-10));
+    }
+
+    #[test]
+    fn test_currencies_are_independent() {
+        let mut inventory = Inventory::new(4);
+        inventory.add_gold(100);
+        inventory.currencies.add(Currency::Gems, 3);
+
+        assert!(inventory.currencies.spend(Currency::Gems, 2));
+        assert_eq!(inventory.currencies.balance(Currency::Gems), 1);
+        assert!(!inventory.currencies.spend(Currency::Gems, 5));
+
+        // Gold accessor reflects the currency map
+        assert_eq!(inventory.gold(), 100);
+        assert!(inventory.currencies.spend(Currency::Gold, 30));
+        assert_eq!(inventory.gold(), 70);
+        inventory.add_gold(-100);
+        assert_eq!(inventory.currencies.balance(Currency::Gold), 0);
+    }
+
+    #[test]
+    fn test_add_item_emits_one_event() {
+        let db = ItemDatabase::with_starter_items();
+        let potion = db.get(ItemId(1)).unwrap();
+        let mut inventory = Inventory::new(4);
+
+        // Spills over into more than one slot but is still a single change
+        inventory.add_item(potion, potion.max_stack + 1);
+        assert_eq!(inventory.take_events(), vec![ItemEvent::InventoryChanged]);
+        assert!(inventory.take_events().is_empty());
+
+        inventory.add_item(potion, 0);
+        inventory.remove_item(0, 0);
+        inventory.remove_item(3, 1);
+        assert!(inventory.take_events().is_empty());
+
+        inventory.remove_item(0, 1);
+        assert_eq!(inventory.take_events(), vec![ItemEvent::InventoryChanged]);
+    }
+
+    #[test]
+    fn test_swap_slots_reorders_items() {
+        let db = ItemDatabase::with_starter_items();
+        let mut inventory = Inventory::new(4);
+        inventory.add_item(db.get(ItemId(2)).unwrap(), 1);
+        inventory.add_item(db.get(ItemId(100)).unwrap(), 3);
+        inventory.take_events();
+
+        assert!(inventory.swap_slots(0, 1));
+        assert_eq!(inventory.items[0], Some(ItemStack::new(ItemId(100), 3)));
+        assert_eq!(inventory.items[1], Some(ItemStack::single(ItemId(2))));
+        assert_eq!(inventory.take_events(), vec![ItemEvent::InventoryChanged]);
+
+        // Moving onto an empty slot leaves the old one empty
+        assert!(inventory.swap_slots(1, 3));
+        assert_eq!(inventory.items[1], None);
+        assert_eq!(inventory.items[3], Some(ItemStack::single(ItemId(2))));
+        assert!(!inventory.swap_slots(0, 4));
+    }
+
+    #[test]
+    fn test_consolidate_and_sort_fragmented_inventory() {
+        let db = ItemDatabase::with_starter_items();
+        let mut inventory = Inventory::new(8);
+        inventory.items = vec![
+            Some(ItemStack::new(ItemId(101), 30)),
+            None,
+            Some(ItemStack::new(ItemId(100), 60)),
+            Some(ItemStack::new(ItemId(2), 1)),
+            Some(ItemStack::new(ItemId(100), 50)),
+            None,
+            Some(ItemStack::new(ItemId(101), 5)),
+            Some(ItemStack::new(ItemId(1), 1)),
+        ];
+
+        inventory.consolidate(&db);
+        assert_eq!(inventory.count_item(ItemId(100)), 110);
+        assert_eq!(inventory.count_item(ItemId(101)), 35);
+        assert_eq!(inventory.take_events(), vec![ItemEvent::InventoryChanged]);
+
+        inventory.sort(&db);
+        let slots: Vec<_> = inventory
+            .items
+            .iter()
+            .map(|slot| slot.as_ref().map(|stack| (stack.item_id.0, stack.quantity)))
+            .collect();
+        assert_eq!(
+            slots,
+            vec![
+                // Weapons by rarity, then consumables by name with full stacks first
+                Some((1, 1)),
+                Some((2, 1)),
+                Some((100, 99)),
+                Some((100, 11)),
+                Some((101, 35)),
+                None,
+                None,
+                None,
+            ]
+        );
+        assert_eq!(inventory.take_events(), vec![ItemEvent::InventoryChanged]);
+
+        // Already tidy: nothing changes
+        inventory.consolidate(&db);
+        inventory.sort(&db);
+        assert!(inventory.take_events().is_empty());
+    }
+}
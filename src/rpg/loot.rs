@@ -81,9 +81,12 @@ impl Chest {
     }
 
     /// Generate loot for this chest
-    pub fn generate_loot(&mut self, _item_db: &ItemDatabase, player_level: i32) {
-        let mut rng = rand::thread_rng();
+    pub fn generate_loot(&mut self, item_db: &ItemDatabase, player_level: i32) {
+        self.generate_loot_with_rng(item_db, player_level, &mut rand::thread_rng());
+    }
 
+    /// Generate loot using the given random source (seed it for reproducible chests)
+    pub fn generate_loot_with_rng(&mut self, item_db: &ItemDatabase, player_level: i32, rng: &mut impl Rng) {
         // Generate gold
         let (min_gold, max_gold) = self.chest_type.gold_range();
         self.gold = rng.gen_range(min_gold..=max_gold);
@@ -93,26 +96,17 @@ impl Chest {
         let item_count = rng.gen_range(min_items..=max_items);
 
         for _ in 0..item_count {
-            if let Some(item_stack) = self.generate_random_item(_item_db, player_level, &mut rng) {
+            if let Some(item_stack) = self.generate_random_item(item_db, player_level, rng) {
                 self.contents.push(item_stack);
             }
         }
     }
 
-    /// Generate a random item appropriate for player level
-    fn generate_random_item(
-        &self,
-        _item_db: &ItemDatabase,
-        _player_level: i32,
-        rng: &mut impl Rng,
-    ) -> Option<ItemStack> {
-        // For starter implementation, just pick from available items
-        // In a full game, you'd have a weighted loot table
-
+    /// Roll a rarity for one item, better chests roll higher
+    fn roll_rarity(&self, rng: &mut impl Rng) -> Rarity {
         let quality = self.chest_type.quality_multiplier();
 
-        // Determine rarity based on chest quality
-        let rarity = if rng.r#gen::<f32>() < quality * 0.1 {
+        if rng.r#gen::<f32>() < quality * 0.1 {
             Rarity::Legendary
         } else if rng.r#gen::<f32>() < quality * 0.2 {
             Rarity::Epic
@@ -122,53 +116,39 @@ impl Chest {
             Rarity::Uncommon
         } else {
             Rarity::Common
-        };
-
-        // Pick a random item type
-        let item_type = match rng.gen_range(0..5) {
-            0 => ItemType::Weapon,
-            1 => ItemType::Armor,
-            2 => ItemType::Accessory,
-            3 => ItemType::Consumable,
-            _ => ItemType::Material,
-        };
-
-        // For demonstration, return health/mana potions for consumables
-        // and some starter items for equipment
-        match item_type {
-            ItemType::Consumable => {
-                let item_id = if rng.r#gen::<bool>() {
-                    ItemId(100) // Health potion
-                } else {
-                    ItemId(101) // Mana potion
-                };
-                Some(ItemStack::new(item_id, rng.gen_range(1..=3)))
-            }
-            ItemType::Weapon => {
-                // Pick weapon based on rarity
-                let item_id = match rarity {
-                    Rarity::Common | Rarity::Uncommon => ItemId(1), // Rusty sword
-                    Rarity::Rare => ItemId(2),                      // Iron sword
-                    Rarity::Epic | Rarity::Legendary => ItemId(3),  // Magic staff
-                };
-                Some(ItemStack::single(item_id))
-            }
-            ItemType::Armor => {
-                // Pick armor
-                let item_id = if rarity >= Rarity::Rare {
-                    ItemId(11) // Iron helmet
-                } else {
-                    ItemId(10) // Leather armor
-                };
-                Some(ItemStack::single(item_id))
-            }
-            _ => {
-                // Default to health potion
-                Some(ItemStack::new(ItemId(100), 1))
-            }
         }
     }
 
+    /// Generate a random item appropriate for player level
+    ///
+    /// Picks uniformly among the database items of the rolled rarity that the
+    /// player is high enough level to use. If there are none, the next lower
+    /// rarity is tried. Quest items never drop from chests.
+    fn generate_random_item(
+        &self,
+        item_db: &ItemDatabase,
+        player_level: i32,
+        rng: &mut impl Rng,
+    ) -> Option<ItemStack> {
+        let rolled = self.roll_rarity(rng);
+
+        let candidates = [Rarity::Legendary, Rarity::Epic, Rarity::Rare, Rarity::Uncommon, Rarity::Common]
+            .into_iter()
+            .filter(|rarity| *rarity <= rolled)
+            .map(|rarity| {
+                item_db
+                    .items_by_rarity(rarity)
+                    .into_iter()
+                    .filter(|item| item.required_level <= player_level && item.item_type != ItemType::QuestItem)
+                    .collect::<Vec<_>>()
+            })
+            .find(|items| !items.is_empty())?;
+
+        let item = candidates[rng.gen_range(0..candidates.len())];
+        let quantity = rng.gen_range(1..=item.max_stack.clamp(1, 3));
+        Some(ItemStack::new(item.id, quantity))
+    }
+
     /// Open the chest and return its contents
     pub fn open(&mut self) -> (Vec<ItemStack>, i32) {
         self.is_open = true;
@@ -199,14 +179,28 @@ impl LootDrop {
         }
     }
 
+    /// Extra drop chance per point of player luck (+2% of the base chance)
+    pub const LUCK_DROP_BONUS: f32 = 0.02;
+
+    /// Drop chance after applying player luck, capped at 1.0
+    /// Negative luck can lower the chance, but guaranteed drops always stay guaranteed
+    pub fn luck_scaled_chance(chance: f32, player_luck: i32) -> f32 {
+        if chance >= 1.0 {
+            return 1.0;
+        }
+        let factor = (1.0 + player_luck as f32 * Self::LUCK_DROP_BONUS).max(0.0);
+        (chance * factor).clamp(0.0, 1.0)
+    }
+
     /// Generate loot from a defeated enemy
     pub fn from_enemy(
         position: Position,
         enemy_def: &EnemyDef,
-        item_db: &ItemDatabase,
+        _item_db: &ItemDatabase,
+        player_luck: i32,
+        rng: &mut GameRng,
     ) -> Self {
         let mut drop = Self::new(position);
-        let mut rng = rand::thread_rng();
 
         // Generate gold
         let (min_gold, max_gold) = enemy_def.gold_reward;
@@ -214,7 +208,7 @@ impl LootDrop {
 
         // Roll for item drops
         for (item_id, chance) in &enemy_def.loot_table {
-            if rng.r#gen::<f32>() < *chance {
+            if rng.r#gen::<f32>() < Self::luck_scaled_chance(*chance, player_luck) {
                 drop.items.push(ItemStack::single(*item_id));
             }
         }
@@ -314,8 +308,10 @@ impl ChestManager {
         position: Position,
         enemy_def: &EnemyDef,
         item_db: &ItemDatabase,
+        player_luck: i32,
+        rng: &mut GameRng,
     ) {
-        let loot = LootDrop::from_enemy(position, enemy_def, item_db);
+        let loot = LootDrop::from_enemy(position, enemy_def, item_db, player_luck, rng);
         if !loot.is_empty() {
             self.loot_drops.push(loot);
         }
@@ -353,6 +349,18 @@ impl ChestManager {
         pickups
     }
 
+    /// Open a chest without collecting it (e.g. restoring a save)
+    /// Returns false if there is no chest for the entity
+    pub fn mark_opened(&mut self, entity_id: EntityId) -> bool {
+        match self.chests.iter_mut().find(|chest| chest.entity_id == entity_id) {
+            Some(chest) => {
+                chest.open();
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Get all chests
     pub fn get_chests(&self) -> &[Chest] {
         &self.chests
@@ -451,3 +459,60 @@ impl Default for LootTable {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drops_in(rolls: usize, chance: f32, luck: i32, seed: u64) -> usize {
+        let def = EnemyDef::new(1, "Goblin", 1).with_loot(ItemId(100), chance);
+        let db = ItemDatabase::new();
+        let mut rng = GameRng::new(seed);
+        (0..rolls)
+            .filter(|_| !LootDrop::from_enemy(Position::new(0.0, 0.0), &def, &db, luck, &mut rng).items.is_empty())
+            .count()
+    }
+
+    #[test]
+    fn test_luck_increases_drop_rate() {
+        let unlucky = drops_in(2000, 0.2, 0, 7);
+        let lucky = drops_in(2000, 0.2, 50, 7);
+
+        // 20% base vs 40% with +100% luck bonus
+        assert!((300..500).contains(&unlucky), "unlucky drops: {}", unlucky);
+        assert!((700..900).contains(&lucky), "lucky drops: {}", lucky);
+        assert_eq!(LootDrop::luck_scaled_chance(0.8, 50), 1.0);
+    }
+
+    #[test]
+    fn test_guaranteed_drop_ignores_luck() {
+        for luck in [-100, 0, 100] {
+            assert_eq!(drops_in(200, 1.0, luck, 3), 200);
+        }
+    }
+
+    #[test]
+    fn test_legendary_chest_drops_registered_epic_weapon() {
+        let mut db = ItemDatabase::with_starter_items();
+        db.register(
+            ItemDef::new(500, "Dragonbone Blade", ItemType::Weapon)
+                .with_rarity(Rarity::Epic)
+                .with_equip_slot(EquipSlot::Weapon)
+                .with_required_level(10),
+        );
+        assert!(db.items_by_type(ItemType::Weapon).iter().any(|item| item.id == ItemId(500)));
+        let mut rng = GameRng::new(11);
+
+        let drops_custom = |level: i32, rng: &mut GameRng| {
+            (0..20).any(|_| {
+                let mut chest = Chest::new(EntityId(0), ChestType::Legendary, Position::new(0.0, 0.0));
+                chest.generate_loot_with_rng(&db, level, rng);
+                chest.contents.iter().any(|stack| stack.item_id == ItemId(500))
+            })
+        };
+
+        // Too low level for the blade, high enough once levelled up
+        assert!(!drops_custom(5, &mut rng));
+        assert!(drops_custom(10, &mut rng));
+    }
+}
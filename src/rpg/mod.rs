@@ -10,6 +10,9 @@ pub mod story;
 pub mod loot;
 pub mod entity;
 pub mod ui;
+pub mod shop;
+pub mod fog;
+pub mod rng;
 
 pub use stats::*;
 pub use items::*;
@@ -20,3 +23,6 @@ pub use story::*;
 pub use loot::*;
 pub use entity::*;
 pub use ui::*;
+pub use shop::*;
+pub use fog::*;
+pub use rng::*;
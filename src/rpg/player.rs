@@ -4,13 +4,23 @@
 use super::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
 
 /// Player character
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Player {
     pub entity_id: Option<EntityId>,
     pub name: String,
+    #[serde(default)]
+    pub class: PlayerClass,
+    /// Effective stats: base attributes plus the equipment bonus
+    /// Rebuilt from `base_stats` whenever either changes, so don't edit the attributes here directly
     pub stats: Stats,
+    /// Class attributes plus level-up growth, without any equipment
+    /// Missing from saves made before equipment was tracked separately
+    #[serde(default)]
+    base_stats: Option<Stats>,
     pub level: Level,
     pub inventory: Inventory,
     pub equipment: Equipment,
@@ -18,6 +28,27 @@ pub struct Player {
     pub status_effects: Vec<TimedStatusEffect>,
     pub quest_flags: HashMap<String, bool>,
     pub current_checkpoint: String,
+    /// Remaining "potion sickness" per consumable category, in seconds
+    #[serde(default)]
+    pub consumable_cooldowns: HashMap<ConsumableCategory, f32>,
+    /// Combined modifiers of everything equipped, reapplied whenever stats are rebuilt
+    #[serde(default)]
+    pub equipment_bonus: StatModifiers,
+    /// Stat points earned from leveling but not yet allocated
+    #[serde(default)]
+    pub unspent_stat_points: i32,
+    /// How attributes grow on level up
+    #[serde(default)]
+    pub level_up_growth: LevelUpGrowth,
+    /// Stat buffs from skills, kept on top of base stats like the equipment bonus
+    #[serde(default)]
+    pub skill_buffs: Vec<SkillBuff>,
+    /// Combined modifiers of the active status effects, as of the last recompute
+    #[serde(skip)]
+    status_bonus: StatModifiers,
+    /// Fractional health/mana regenerated but not applied yet
+    #[serde(skip)]
+    regen_progress: (f32, f32),
 }
 
 impl Player {
@@ -36,6 +67,8 @@ impl Player {
         Self {
             entity_id: None,
             name: name.to_string(),
+            class,
+            base_stats: Some(stats.clone()),
             stats,
             level: Level::new(),
             inventory: Inventory::new(20), // 20 inventory slots
@@ -44,9 +77,22 @@ impl Player {
             status_effects: Vec::new(),
             quest_flags: HashMap::new(),
             current_checkpoint: "start".to_string(),
+            consumable_cooldowns: HashMap::new(),
+            equipment_bonus: StatModifiers::default(),
+            unspent_stat_points: 0,
+            level_up_growth: LevelUpGrowth::default(),
+            skill_buffs: Vec::new(),
+            status_bonus: StatModifiers::default(),
+            regen_progress: (0.0, 0.0),
         }
     }
 
+    /// Set how attributes grow on level up
+    pub fn with_level_up_growth(mut self, growth: LevelUpGrowth) -> Self {
+        self.level_up_growth = growth;
+        self
+    }
+
     /// Update player state (status effects, etc.)
     pub fn update(&mut self, delta_time: f32) {
         // Update status effects
@@ -61,7 +107,7 @@ impl Player {
             if effect.should_tick(delta_time) {
                 match effect.effect {
                     StatusEffect::Poisoned | StatusEffect::Burning => {
-                        self.stats.take_damage(effect.power);
+                        self.stats.take_dot_damage(effect.power);
                     }
                     StatusEffect::Regenerating => {
                         self.stats.heal(effect.power);
@@ -75,9 +121,61 @@ impl Player {
         for i in effects_to_remove.into_iter().rev() {
             self.status_effects.remove(i);
         }
+
+        self.refresh_status_bonus();
+        self.regenerate(delta_time);
+
+        // Tick down consumable cooldowns
+        for remaining in self.consumable_cooldowns.values_mut() {
+            *remaining -= delta_time;
+        }
+        self.consumable_cooldowns.retain(|_, remaining| *remaining > 0.0);
+
+        // Expire timed skill buffs
+        let buff_count = self.skill_buffs.len();
+        for buff in &mut self.skill_buffs {
+            if let Some(remaining) = &mut buff.remaining {
+                *remaining -= delta_time;
+            }
+        }
+        self.skill_buffs.retain(|buff| buff.remaining.is_none_or(|remaining| remaining > 0.0));
+        if self.skill_buffs.len() != buff_count {
+            self.refresh_stats();
+        }
+    }
+
+    /// Regenerate health and mana, applying whole points as they accumulate
+    /// Paused while poisoned or cursed
+    fn regenerate(&mut self, delta_time: f32) {
+        if self.has_status_effect(StatusEffect::Poisoned) || self.has_status_effect(StatusEffect::Cursed) {
+            return;
+        }
+
+        let (health, mana) = &mut self.regen_progress;
+        *health = if self.stats.current_health < self.stats.max_health {
+            *health + self.stats.health_regen_per_sec * delta_time
+        } else {
+            0.0
+        };
+        *mana = if self.stats.current_mana < self.stats.max_mana {
+            *mana + self.stats.mana_regen_per_sec * delta_time
+        } else {
+            0.0
+        };
+
+        if *health >= 1.0 {
+            self.stats.heal(health.floor() as i32);
+            *health = health.fract();
+        }
+        if *mana >= 1.0 {
+            self.stats.restore_mana(mana.floor() as i32);
+            *mana = mana.fract();
+        }
     }
 
     /// Add experience and handle level ups
+    /// Every exp source (quests, dialogue, kills) should go through here so
+    /// level-up stat gains are never skipped
     pub fn add_exp(&mut self, exp: i32) -> Option<i32> {
         if let Some(new_level) = self.level.add_exp(exp) {
             // Level up! Grant stat increases
@@ -90,20 +188,48 @@ impl Player {
 
     /// Handle level up - increase stats
     fn on_level_up(&mut self, new_level: i32) {
-        // Grant 5 stat points per level (distributed based on class tendencies)
-        // For now, just give balanced increases
-        self.stats.strength += 1;
-        self.stats.dexterity += 1;
-        self.stats.intelligence += 1;
-        self.stats.vitality += 1;
-        self.stats.luck += 1;
-
-        self.stats.recalculate();
+        match self.level_up_growth {
+            LevelUpGrowth::StatPoints(points) => self.unspent_stat_points += points,
+            LevelUpGrowth::AutoDistribute => {
+                let growth = self.class.growth_weights();
+                let base = self.base_stats_mut();
+                for (attribute, gain) in growth {
+                    *base.attribute_mut(attribute) += gain;
+                }
+            }
+        }
+
+        self.refresh_stats();
         self.stats.restore_full(); // Fully heal on level up
 
         println!("Level up! Now level {}", new_level);
     }
 
+    /// Spend unspent stat points on an attribute
+    pub fn allocate_stat(&mut self, attribute: Attribute, amount: i32) -> Result<(), String> {
+        if amount <= 0 {
+            return Err("Must allocate at least one point".to_string());
+        }
+        if amount > self.unspent_stat_points {
+            return Err(format!(
+                "Not enough stat points ({} available)",
+                self.unspent_stat_points
+            ));
+        }
+
+        self.unspent_stat_points -= amount;
+        *self.base_stats_mut().attribute_mut(attribute) += amount;
+        self.refresh_stats();
+        Ok(())
+    }
+
+    /// Take pending inventory and equipment change events
+    pub fn take_item_events(&mut self) -> Vec<ItemEvent> {
+        let mut events = self.inventory.take_events();
+        events.extend(self.equipment.take_events());
+        events
+    }
+
     /// Equip an item from inventory
     pub fn equip_item(
         &mut self,
@@ -176,53 +302,187 @@ impl Player {
 
     /// Recalculate stats including equipment bonuses
     fn recalculate_stats_with_equipment(&mut self, item_db: &ItemDatabase) {
-        // First recalculate base stats
-        self.stats.recalculate();
+        self.equipment_bonus = self
+            .equipment
+            .all_equipped()
+            .into_iter()
+            .filter_map(|(_slot, item_id)| item_db.get(item_id))
+            .fold(StatModifiers::default(), |total, item_def| total.combined(&item_def.stat_mods));
+
+        self.refresh_stats();
+    }
 
-        // Then apply equipment bonuses
-        for (_slot, item_id) in self.equipment.all_equipped() {
-            if let Some(item_def) = item_db.get(item_id) {
-                self.apply_stat_modifiers(&item_def.stat_mods);
+    /// Stats without equipment
+    pub fn base_stats(&self) -> Stats {
+        match &self.base_stats {
+            Some(base) => base.clone(),
+            None => {
+                // Older saves only stored the effective stats; take the equipped attributes back off
+                let bonus = &self.equipment_bonus;
+                let mut base = self.stats.clone();
+                base.strength -= bonus.strength;
+                base.dexterity -= bonus.dexterity;
+                base.intelligence -= bonus.intelligence;
+                base.vitality -= bonus.vitality;
+                base.luck -= bonus.luck;
+                base
             }
         }
     }
 
-    /// Apply stat modifiers from equipment
-    fn apply_stat_modifiers(&mut self, mods: &StatModifiers) {
-        self.stats.strength += mods.strength;
-        self.stats.dexterity += mods.dexterity;
-        self.stats.intelligence += mods.intelligence;
-        self.stats.vitality += mods.vitality;
-        self.stats.luck += mods.luck;
-        self.stats.max_health += mods.max_health;
-        self.stats.max_mana += mods.max_mana;
-        self.stats.physical_damage += mods.physical_damage;
-        self.stats.magic_damage += mods.magic_damage;
-        self.stats.defense += mods.defense;
-        self.stats.dodge_chance += mods.dodge_chance;
-        self.stats.crit_chance += mods.crit_chance;
-        self.stats.move_speed += mods.move_speed;
+    fn base_stats_mut(&mut self) -> &mut Stats {
+        let base = self.base_stats();
+        self.base_stats.get_or_insert(base)
+    }
+
+    /// Base stats with the equipment and status effect bonuses applied
+    /// (flat modifiers first, percentages last)
+    /// Current health and mana are left as they are
+    pub fn effective_stats(&self) -> Stats {
+        let bonus = self
+            .skill_buffs
+            .iter()
+            .fold(self.equipment_bonus.combined(&self.status_bonus), |total, buff| total.combined(&buff.mods));
+        let mut stats = self.base_stats();
+        stats.recalculate();
+        bonus.add_to(&mut stats);
+        bonus.percent.apply_to(&mut stats);
+        stats
+    }
+
+    /// Recompute stats if the active status effects' modifiers changed
+    /// (an effect was added, stacked or expired)
+    fn refresh_status_bonus(&mut self) {
+        let bonus = self
+            .status_effects
+            .iter()
+            .fold(StatModifiers::default(), |total, e| total.combined(&e.effect.stat_modifiers(e.power)));
+        if bonus != self.status_bonus {
+            self.status_bonus = bonus;
+            self.refresh_stats();
+        }
+    }
+
+    /// Rebuild the effective stats from the base stats and equipment bonus,
+    /// keeping current health and mana (capped at the new maximums)
+    fn refresh_stats(&mut self) {
+        let (health, mana) = (self.stats.current_health, self.stats.current_mana);
+        self.stats = self.effective_stats();
+        self.stats.current_health = health.min(self.stats.max_health);
+        self.stats.current_mana = mana.min(self.stats.max_mana);
+    }
+
+    /// Grant the exp for defeating an enemy
+    pub fn on_enemy_killed(&mut self, enemy_def: &EnemyDef) -> Option<i32> {
+        self.add_exp(enemy_def.exp_reward)
+    }
+
+    /// Apply a dialogue or quest reward action
+    /// Returns a signal when the caller has more to do (level up, battle, teleport)
+    pub fn apply_dialogue_action(
+        &mut self,
+        action: &DialogueAction,
+        item_db: &ItemDatabase,
+    ) -> Result<Option<DialogueSignal>, String> {
+        match action {
+            DialogueAction::GiveExp(exp) => Ok(self.add_exp(*exp).map(DialogueSignal::LevelUp)),
+            DialogueAction::GiveGold(amount) => {
+                self.inventory.add_gold(*amount);
+                Ok(None)
+            }
+            DialogueAction::TakeGold(amount) => {
+                if !self.inventory.spend_gold(*amount) {
+                    return Err(format!("Not enough gold (need {})", amount));
+                }
+                Ok(None)
+            }
+            DialogueAction::GiveItem(item_id, quantity) => {
+                let item_def = item_db.get(*item_id).ok_or("Item not found in database")?;
+                if self.inventory.add_item(item_def, *quantity) > 0 {
+                    return Err("Inventory is full".to_string());
+                }
+                Ok(None)
+            }
+            DialogueAction::TakeItem(item_id, quantity) => {
+                if !self.inventory.take_item(*item_id, *quantity) {
+                    return Err(format!("Not enough of item {} (need {})", item_id.0, quantity));
+                }
+                Ok(None)
+            }
+            DialogueAction::SetQuestFlag(flag, value) => {
+                self.set_quest_flag(flag, *value);
+                Ok(None)
+            }
+            DialogueAction::StartBattle(enemy_id) => Ok(Some(DialogueSignal::StartBattle(*enemy_id))),
+            DialogueAction::Teleport(checkpoint) => Ok(Some(DialogueSignal::Teleport(checkpoint.clone()))),
+            DialogueAction::EndDialogue => Ok(Some(DialogueSignal::EndDialogue)),
+        }
+    }
+
+    /// Add a stat buff on top of the current stats
+    /// A duration of 0 or less makes it permanent
+    pub fn add_skill_buff(&mut self, mods: StatModifiers, duration: f32) {
+        let remaining = (duration > 0.0).then_some(duration);
+        self.skill_buffs.push(SkillBuff { mods, remaining });
+        self.refresh_stats();
+    }
+
+    /// Cast a skill on the player themselves
+    ///
+    /// Mana, damage, heals and status effects work as in `Skill::apply`; stat buffs
+    /// go through `add_skill_buff` so rebuilding stats doesn't erase them. Skills
+    /// aimed at enemies are refused, since the player isn't a valid target for them.
+    pub fn cast_self_skill(&mut self, skill_def: &SkillDef) -> Result<(), String> {
+        if skill_def.target_type != TargetType::SelfTarget {
+            return Err(format!("{} needs an enemy target", skill_def.name));
+        }
+
+        let (buffs, effects): (Vec<_>, Vec<_>) = skill_def
+            .effects
+            .iter()
+            .cloned()
+            .partition(|effect| matches!(effect, SkillEffect::BuffStats { .. }));
+        let without_buffs = SkillDef { effects, ..skill_def.clone() };
+        let mut statuses = Vec::new();
+        Skill::apply(&without_buffs, &mut self.stats, &mut [], &mut statuses)?;
+
+        for buff in buffs {
+            if let SkillEffect::BuffStats { mods, duration } = buff {
+                self.add_skill_buff(mods, duration);
+            }
+        }
+        for status in statuses {
+            self.add_status_effect(status);
+        }
+        Ok(())
     }
 
     /// Use a consumable item
+    /// Fails with OnCooldown while another consumable of the same category is recovering
     pub fn use_consumable(
         &mut self,
         inventory_slot: usize,
         item_db: &ItemDatabase,
-    ) -> Result<String, String> {
+    ) -> Result<String, ConsumableError> {
         // Get item ID and validate first
         let item_id = self
             .inventory
             .get_item(inventory_slot)
-            .ok_or("No item in that slot")?
+            .ok_or(ConsumableError::Invalid("No item in that slot".to_string()))?
             .item_id;
 
         let item_def = item_db
             .get(item_id)
-            .ok_or("Item not found in database")?;
+            .ok_or(ConsumableError::Invalid("Item not found in database".to_string()))?;
 
         if item_def.item_type != ItemType::Consumable {
-            return Err("Item is not consumable".to_string());
+            return Err(ConsumableError::Invalid("Item is not consumable".to_string()));
+        }
+
+        let category = ConsumableCategory::of(item_id);
+        let remaining = self.consumable_cooldown(category);
+        if remaining > 0.0 {
+            return Err(ConsumableError::OnCooldown { category, remaining });
         }
 
         // Apply consumable effects based on item ID
@@ -243,12 +503,23 @@ impl Player {
         // Remove one from stack
         self.inventory.remove_item(inventory_slot, 1);
 
+        let cooldown = category.cooldown();
+        if cooldown > 0.0 {
+            self.consumable_cooldowns.insert(category, cooldown);
+        }
+
         Ok(result)
     }
 
-    /// Add a status effect
+    /// Seconds until another consumable of this category can be used
+    pub fn consumable_cooldown(&self, category: ConsumableCategory) -> f32 {
+        self.consumable_cooldowns.get(&category).copied().unwrap_or(0.0)
+    }
+
+    /// Add a status effect, following its `StackPolicy`
     pub fn add_status_effect(&mut self, effect: TimedStatusEffect) {
-        self.status_effects.push(effect);
+        apply_status_effect(&mut self.status_effects, effect);
+        self.refresh_status_bonus();
     }
 
     /// Check if player has a status effect
@@ -270,18 +541,131 @@ impl Player {
     pub fn save_checkpoint(&mut self, checkpoint_name: &str) {
         self.current_checkpoint = checkpoint_name.to_string();
     }
+
+    /// Save the player to a JSON file
+    pub fn save_to_json<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize JSON: {}", e))?;
+
+        fs::write(path, contents)
+            .map_err(|e| format!("Failed to write file: {}", e))
+    }
+
+    /// Load a player saved with `save_to_json`
+    pub fn load_from_json<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse JSON: {}", e))
+    }
+}
+
+/// Consumable cooldown groups - using one item blocks the rest of its group
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ConsumableCategory {
+    HealthPotion,
+    ManaPotion,
+    Other,
+}
+
+impl ConsumableCategory {
+    /// Category for a consumable item
+    pub fn of(item_id: ItemId) -> Self {
+        match item_id.0 {
+            100 => ConsumableCategory::HealthPotion,
+            101 => ConsumableCategory::ManaPotion,
+            _ => ConsumableCategory::Other,
+        }
+    }
+
+    /// Potion sickness duration in seconds after using an item of this category
+    pub fn cooldown(&self) -> f32 {
+        match self {
+            ConsumableCategory::HealthPotion => 10.0,
+            ConsumableCategory::ManaPotion => 10.0,
+            ConsumableCategory::Other => 0.0,
+        }
+    }
+}
+
+/// Why a consumable couldn't be used
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConsumableError {
+    /// Empty slot, unknown item or not a consumable
+    Invalid(String),
+    /// Another item of the same category was used too recently
+    OnCooldown {
+        category: ConsumableCategory,
+        remaining: f32,
+    },
+}
+
+impl std::fmt::Display for ConsumableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConsumableError::Invalid(reason) => write!(f, "{}", reason),
+            ConsumableError::OnCooldown { remaining, .. } => {
+                write!(f, "On cooldown ({:.1}s)", remaining)
+            }
+        }
+    }
 }
 
 /// Player class archetypes
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum PlayerClass {
     Warrior,
     Mage,
     Rogue,
+    #[default]
     Balanced,
 }
 
+/// A stat buff granted by a skill
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillBuff {
+    pub mods: StatModifiers,
+    /// Seconds left, or `None` for a permanent buff
+    pub remaining: Option<f32>,
+}
+
+/// Attribute growth applied on each level up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LevelUpGrowth {
+    /// Grant this many points to spend with `Player::allocate_stat`
+    StatPoints(i32),
+    /// Spread `PlayerClass::GROWTH_POOL` points by the class's growth weights
+    AutoDistribute,
+}
+
+impl Default for LevelUpGrowth {
+    fn default() -> Self {
+        LevelUpGrowth::StatPoints(5)
+    }
+}
+
 impl PlayerClass {
+    /// Attribute points gained per level with `LevelUpGrowth::AutoDistribute`
+    pub const GROWTH_POOL: i32 = 5;
+
+    /// Per-attribute gains on level up, summing to `GROWTH_POOL`
+    pub fn growth_weights(&self) -> [(Attribute, i32); 5] {
+        let [strength, dexterity, intelligence, vitality, luck] = match self {
+            PlayerClass::Warrior => [2, 1, 0, 2, 0],
+            PlayerClass::Mage => [0, 1, 3, 1, 0],
+            PlayerClass::Rogue => [1, 2, 0, 0, 2],
+            PlayerClass::Balanced => [1, 1, 1, 1, 1],
+        };
+        [
+            (Attribute::Strength, strength),
+            (Attribute::Dexterity, dexterity),
+            (Attribute::Intelligence, intelligence),
+            (Attribute::Vitality, vitality),
+            (Attribute::Luck, luck),
+        ]
+    }
+
     pub fn name(&self) -> &str {
         match self {
             PlayerClass::Warrior => "Warrior",
@@ -300,3 +684,373 @@ impl PlayerClass {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_potion_cooldown() {
+        let db = ItemDatabase::with_starter_items();
+        let mut player = Player::new("Tester", PlayerClass::Warrior);
+        player.inventory.add_item(db.get(ItemId(100)).unwrap(), 3);
+        player.inventory.add_item(db.get(ItemId(101)).unwrap(), 1);
+        let (health_slot, mana_slot) = (0, 1);
+
+        assert!(player.use_consumable(health_slot, &db).is_ok());
+        assert!(matches!(
+            player.use_consumable(health_slot, &db),
+            Err(ConsumableError::OnCooldown { category: ConsumableCategory::HealthPotion, .. })
+        ));
+        assert_eq!(player.inventory.count_item(ItemId(100)), 2);
+
+        // Other categories are unaffected
+        assert!(player.use_consumable(mana_slot, &db).is_ok());
+
+        player.update(ConsumableCategory::HealthPotion.cooldown() + 0.1);
+        assert_eq!(player.consumable_cooldown(ConsumableCategory::HealthPotion), 0.0);
+        assert!(player.use_consumable(health_slot, &db).is_ok());
+        assert_eq!(player.inventory.count_item(ItemId(100)), 1);
+    }
+
+    #[test]
+    fn test_equip_item_emits_one_equipment_event() {
+        let db = ItemDatabase::with_starter_items();
+        let mut player = Player::new("Tester", PlayerClass::Warrior);
+        player.inventory.add_item(db.get(ItemId(1)).unwrap(), 1);
+        player.inventory.take_events();
+
+        player.equip_item(0, &db).unwrap();
+        assert_eq!(
+            player.equipment.take_events(),
+            vec![ItemEvent::EquipmentChanged { slot: EquipSlot::Weapon }]
+        );
+        // Moving the sword out of the bag is reported separately
+        assert_eq!(player.inventory.take_events(), vec![ItemEvent::InventoryChanged]);
+
+        // A failed equip changes nothing
+        assert!(player.equip_item(0, &db).is_err());
+        assert!(player.equipment.take_events().is_empty());
+        assert!(player.inventory.take_events().is_empty());
+    }
+
+    fn percent_item(id: u32, slot: EquipSlot, flat: i32, percent: f32) -> ItemDef {
+        ItemDef::new(id, "Test Gear", ItemType::Armor)
+            .with_equip_slot(slot)
+            .with_stat_mods(StatModifiers {
+                physical_damage: flat,
+                percent: PercentModifiers { physical_damage: percent, ..Default::default() },
+                ..Default::default()
+            })
+    }
+
+    #[test]
+    fn test_percent_modifiers_apply_after_flat() {
+        let mut db = ItemDatabase::new();
+        db.register(percent_item(900, EquipSlot::Weapon, 10, 0.5));
+        db.register(percent_item(901, EquipSlot::Ring1, 0, 0.5));
+
+        let mut player = Player::new("Tester", PlayerClass::Warrior);
+        player.stats.recalculate();
+        let base = player.stats.physical_damage;
+
+        player.inventory.add_item(db.get(ItemId(900)).unwrap(), 1);
+        player.equip_item(0, &db).unwrap();
+        assert_eq!(player.stats.physical_damage, ((base + 10) as f32 * 1.5).round() as i32);
+
+        // A second +50% stacks additively to +100%
+        player.inventory.add_item(db.get(ItemId(901)).unwrap(), 1);
+        player.equip_item(0, &db).unwrap();
+        assert_eq!(player.stats.physical_damage, (base + 10) * 2);
+    }
+
+    #[test]
+    fn test_quest_exp_level_up_keeps_equipment_bonus() {
+        let db = ItemDatabase::with_starter_items();
+        let mut player = Player::new("Tester", PlayerClass::Warrior).with_level_up_growth(LevelUpGrowth::AutoDistribute);
+        player.inventory.add_item(db.get(ItemId(1)).unwrap(), 1);
+        player.equip_item(0, &db).unwrap();
+        let sword_damage = db.get(ItemId(1)).unwrap().stat_mods.physical_damage;
+        let strength = player.stats.strength;
+
+        let mut quest = Quest::new("test", "Test", "Level me up").with_reward(DialogueAction::GiveExp(1000));
+        quest.start();
+        quest.complete();
+        assert!(quest.claim_rewards(&mut player, &db).is_ok());
+
+        assert!(player.level.current_level > 1);
+        assert!(player.stats.strength > strength);
+        assert_eq!(player.stats.physical_damage, player.stats.strength * 2 + sword_damage);
+        assert_eq!(player.stats.current_health, player.stats.max_health);
+
+        // Rewards are only granted once
+        let level = player.level.current_level;
+        assert!(quest.claim_rewards(&mut player, &db).is_err());
+        assert_eq!(player.level.current_level, level);
+    }
+
+    #[test]
+    fn test_level_up_grants_stat_points_to_allocate() {
+        let db = ItemDatabase::with_starter_items();
+        let mut player = Player::new("Tester", PlayerClass::Mage).with_level_up_growth(LevelUpGrowth::StatPoints(3));
+        player.inventory.add_item(db.get(ItemId(1)).unwrap(), 1);
+        player.equip_item(0, &db).unwrap();
+        let sword_damage = db.get(ItemId(1)).unwrap().stat_mods.physical_damage;
+        let strength = player.stats.strength;
+
+        assert_eq!(player.add_exp(player.level.exp_to_next_level), Some(2));
+        assert_eq!(player.unspent_stat_points, 3);
+        assert_eq!(player.stats.strength, strength);
+
+        assert!(player.allocate_stat(Attribute::Strength, 4).is_err());
+        assert!(player.allocate_stat(Attribute::Strength, 0).is_err());
+        assert!(player.allocate_stat(Attribute::Strength, 2).is_ok());
+        assert_eq!(player.unspent_stat_points, 1);
+        assert_eq!(player.stats.strength, strength + 2);
+        assert_eq!(player.stats.physical_damage, (strength + 2) * 2 + sword_damage);
+
+        let max_health = player.stats.max_health;
+        assert!(player.allocate_stat(Attribute::Vitality, 1).is_ok());
+        assert_eq!(player.stats.max_health, max_health + 10);
+        assert_eq!(player.unspent_stat_points, 0);
+    }
+
+    #[test]
+    fn test_mage_grows_intelligence_over_strength() {
+        let mut player = Player::new("Tester", PlayerClass::Mage).with_level_up_growth(LevelUpGrowth::AutoDistribute);
+        let (strength, intelligence) = (player.stats.strength, player.stats.intelligence);
+
+        for _ in 0..5 {
+            player.add_exp(player.level.exp_to_next_level);
+        }
+
+        assert_eq!(player.level.current_level, 6);
+        let strength_gain = player.stats.strength - strength;
+        let intelligence_gain = player.stats.intelligence - intelligence;
+        assert!(intelligence_gain > strength_gain);
+        assert_eq!(intelligence_gain, 15);
+
+        for class in [PlayerClass::Warrior, PlayerClass::Mage, PlayerClass::Rogue, PlayerClass::Balanced] {
+            let total: i32 = class.growth_weights().iter().map(|(_, gain)| gain).sum();
+            assert_eq!(total, PlayerClass::GROWTH_POOL);
+        }
+    }
+
+    #[test]
+    fn test_haste_raises_move_speed_until_it_expires() {
+        let mut player = Player::new("Tester", PlayerClass::Rogue);
+        let baseline = player.stats.clone();
+
+        player.add_status_effect(TimedStatusEffect::new(StatusEffect::Hasted, 2.0, 25));
+        assert_eq!(player.stats.move_speed, baseline.move_speed * 1.25);
+
+        player.update(1.0);
+        assert!(player.stats.move_speed > baseline.move_speed);
+        player.update(1.5);
+        assert!(!player.has_status_effect(StatusEffect::Hasted));
+        assert_eq!(player.stats.move_speed, baseline.move_speed);
+
+        // Effects pushed directly are picked up on the next update
+        player.status_effects.push(TimedStatusEffect::new(StatusEffect::Cursed, 5.0, 4));
+        player.update(0.1);
+        assert_eq!(player.stats.defense, baseline.defense - 4);
+        player.update(5.0);
+        assert_eq!(player.stats, player.effective_stats());
+        assert_eq!(player.stats.defense, baseline.defense);
+    }
+
+    #[test]
+    fn test_equip_unequip_is_idempotent() {
+        let mut db = ItemDatabase::new();
+        db.register(
+            ItemDef::new(910, "Plate of Might", ItemType::Armor)
+                .with_equip_slot(EquipSlot::Chest)
+                .with_stat_mods(StatModifiers {
+                    strength: 5,
+                    vitality: 3,
+                    defense: 2,
+                    percent: PercentModifiers { max_health: 0.1, ..Default::default() },
+                    ..Default::default()
+                }),
+        );
+        let mut player = Player::new("Tester", PlayerClass::Warrior);
+        let start = player.stats.clone();
+
+        for _ in 0..10 {
+            player.inventory.add_item(db.get(ItemId(910)).unwrap(), 1);
+            player.equip_item(0, &db).unwrap();
+            assert_eq!(player.stats.strength, start.strength + 5);
+            player.unequip_item(EquipSlot::Chest, &db).unwrap();
+            player.inventory.remove_item(0, 1);
+        }
+
+        assert_eq!(player.stats, start);
+        assert_eq!(player.base_stats(), player.effective_stats());
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let db = ItemDatabase::with_starter_items();
+        let mut player = Player::new("Saver", PlayerClass::Rogue);
+        player.entity_id = Some(EntityId(42));
+        player.inventory.add_item(db.get(ItemId(1)).unwrap(), 1);
+        player.inventory.add_item(db.get(ItemId(100)).unwrap(), 5);
+        player.equip_item(0, &db).unwrap();
+        player.inventory.add_gold(75);
+        player.set_quest_flag("met_elder", true);
+        player.set_quest_flag("betrayed_guild", false);
+        player.add_status_effect(TimedStatusEffect::new(StatusEffect::Poisoned, 4.5, 3));
+        player.update(0.25);
+
+        let path = std::env::temp_dir().join(format!("glib_player_{}.json", std::process::id()));
+        player.save_to_json(&path).unwrap();
+        let loaded = Player::load_from_json(&path);
+        std::fs::remove_file(&path).ok();
+        let loaded = loaded.unwrap();
+
+        assert_eq!(loaded.entity_id, Some(EntityId(42)));
+        assert_eq!(loaded.quest_flags, player.quest_flags);
+        assert_eq!(loaded.status_effects, player.status_effects);
+        assert_eq!(loaded.equipment.slots, player.equipment.slots);
+        assert_eq!(loaded.inventory.items, player.inventory.items);
+        assert_eq!(loaded.inventory.gold(), 75);
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value(&player).unwrap()
+        );
+
+        assert!(Player::load_from_json(std::env::temp_dir().join("glib_missing_player.json")).is_err());
+    }
+
+    #[test]
+    fn test_regen_climbs_to_max_and_stops() {
+        let mut player = Player::new("Tester", PlayerClass::Warrior);
+        let max = player.stats.max_health;
+        player.stats.current_health = max - 10;
+        player.stats.health_regen_per_sec = 4.0;
+
+        // Fractions carry over between frames: 0.1s * 4/s = 0.4 HP per frame
+        player.update(0.1);
+        player.update(0.1);
+        assert_eq!(player.stats.current_health, max - 10);
+        player.update(0.1);
+        assert_eq!(player.stats.current_health, max - 9);
+
+        player.update(1.0);
+        assert_eq!(player.stats.current_health, max - 5);
+
+        for _ in 0..10 {
+            player.update(1.0);
+            assert!(player.stats.current_health <= max);
+        }
+        assert_eq!(player.stats.current_health, max);
+
+        // Poison pauses regen
+        player.stats.current_health = max - 10;
+        player.add_status_effect(TimedStatusEffect::new(StatusEffect::Poisoned, 100.0, 0));
+        player.update(0.5);
+        assert_eq!(player.stats.current_health, max - 10);
+    }
+
+    #[test]
+    fn test_claim_rewards_grants_gold_exp_and_item() {
+        let db = ItemDatabase::with_starter_items();
+        let mut player = Player::new("Tester", PlayerClass::Warrior);
+        player.inventory.add_item(db.get(ItemId(200)).unwrap(), 1);
+
+        let mut quest = Quest::new("delivery", "Delivery", "Hand over the key")
+            .with_reward(DialogueAction::TakeItem(ItemId(200), 1))
+            .with_reward(DialogueAction::GiveGold(50))
+            .with_reward(DialogueAction::GiveExp(40))
+            .with_reward(DialogueAction::GiveItem(ItemId(2), 1))
+            .with_reward(DialogueAction::SetQuestFlag("delivered".to_string(), true))
+            .with_reward(DialogueAction::Teleport("town".to_string()));
+        quest.start();
+        quest.complete();
+
+        let signals = quest.claim_rewards(&mut player, &db).unwrap();
+        assert_eq!(signals, vec![DialogueSignal::Teleport("town".to_string())]);
+        assert_eq!(player.inventory.gold(), 50);
+        assert_eq!(player.level.current_exp, 40);
+        assert_eq!(player.inventory.count_item(ItemId(2)), 1);
+        assert_eq!(player.inventory.count_item(ItemId(200)), 0);
+        assert!(player.has_quest_flag("delivered"));
+    }
+
+    #[test]
+    fn test_self_buff_skill_survives_stat_rebuilds_until_it_expires() {
+        let db = ItemDatabase::with_starter_items();
+        let mut player = Player::new("Tester", PlayerClass::Warrior);
+        let defense = player.stats.defense;
+        let guard = SkillDef::new(50, "Guard", SkillType::Active).with_effect(SkillEffect::BuffStats {
+            mods: StatModifiers { defense: 5, ..Default::default() },
+            duration: 10.0,
+        });
+
+        player.cast_self_skill(&guard).unwrap();
+        assert_eq!(player.stats.defense, defense + 5);
+
+        // Equipping rebuilds stats from the base; the buff is kept
+        player.inventory.add_item(db.get(ItemId(1)).unwrap(), 1);
+        player.equip_item(0, &db).unwrap();
+        assert_eq!(player.stats.defense, defense + 5);
+
+        player.update(11.0);
+        assert_eq!(player.stats.defense, defense);
+        assert!(player.skill_buffs.is_empty());
+    }
+
+    #[test]
+    fn test_enemy_target_skill_is_refused_without_spending_mana() {
+        let mut player = Player::new("Tester", PlayerClass::Mage);
+        let mana = player.stats.current_mana;
+        let bolt = SkillDef::new(51, "Bolt", SkillType::Active)
+            .with_target(TargetType::SingleEnemy)
+            .with_mana_cost(10)
+            .with_effect(SkillEffect::Damage { physical: 0, magical: 20 });
+
+        assert!(player.cast_self_skill(&bolt).is_err());
+        assert_eq!(player.stats.current_mana, mana);
+    }
+
+    #[test]
+    fn test_failed_reward_rolls_back_and_can_be_claimed_later() {
+        let db = ItemDatabase::with_starter_items();
+        let mut player = Player::new("Tester", PlayerClass::Warrior);
+
+        let mut quest = Quest::new("delivery", "Delivery", "Hand over the key")
+            .with_reward(DialogueAction::GiveGold(50))
+            .with_reward(DialogueAction::TakeItem(ItemId(200), 1));
+        quest.start();
+        quest.complete();
+
+        // No key yet: nothing is granted and the quest stays unclaimed
+        assert!(quest.claim_rewards(&mut player, &db).is_err());
+        assert_eq!(player.inventory.gold(), 0);
+        assert!(!quest.rewards_claimed);
+
+        player.inventory.add_item(db.get(ItemId(200)).unwrap(), 1);
+        assert!(quest.claim_rewards(&mut player, &db).is_ok());
+        assert_eq!(player.inventory.gold(), 50);
+        assert_eq!(player.inventory.count_item(ItemId(200)), 0);
+        assert!(quest.rewards_claimed);
+    }
+
+    #[test]
+    fn test_taking_missing_gold_or_items_fails() {
+        let db = ItemDatabase::with_starter_items();
+        let mut player = Player::new("Tester", PlayerClass::Warrior);
+        player.inventory.add_gold(5);
+        player.inventory.add_item(db.get(ItemId(100)).unwrap(), 2);
+
+        assert!(player.apply_dialogue_action(&DialogueAction::TakeGold(10), &db).is_err());
+        assert!(player.apply_dialogue_action(&DialogueAction::TakeItem(ItemId(100), 3), &db).is_err());
+        assert_eq!(player.inventory.gold(), 5);
+        assert_eq!(player.inventory.count_item(ItemId(100)), 2);
+
+        assert_eq!(
+            player.apply_dialogue_action(&DialogueAction::StartBattle(3), &db),
+            Ok(Some(DialogueSignal::StartBattle(3)))
+        );
+    }
+}
@@ -0,0 +1,56 @@
+/// Game Random Number Generator
+/// Seedable RNG handed to game systems so runs (and tests) can be reproduced
+
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+/// Seeded random number generator shared by gameplay systems
+#[derive(Debug, Clone)]
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    /// Create a generator that always produces the same sequence for a seed
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Create a generator with a random seed
+    pub fn from_entropy() -> Self {
+        Self::new(rand::random())
+    }
+
+    /// Seed this generator was created with (log it to reproduce a run)
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::from_entropy()
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
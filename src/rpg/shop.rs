@@ -0,0 +1,159 @@
+/// Shop System
+/// Selling items for gold, with previews and bulk-sell helpers
+
+use super::*;
+
+/// Shop helpers for trading with merchants
+pub struct Shop;
+
+impl Shop {
+    /// Preview how much gold selling the whole stack in a slot would give
+    pub fn sell_preview(inventory: &Inventory, slot: usize, item_db: &ItemDatabase) -> Option<i32> {
+        let stack = inventory.get_item(slot)?;
+        let item_def = item_db.get(stack.item_id)?;
+        Some(item_def.sell_value * stack.quantity as i32)
+    }
+
+    /// Compare an item against whatever is equipped in its slot
+    /// Returns the stat difference (positive = upgrade), or None if not equippable
+    pub fn compare_to_equipped(
+        player: &Player,
+        item_id: ItemId,
+        item_db: &ItemDatabase,
+    ) -> Option<StatModifiers> {
+        let item_def = item_db.get(item_id)?;
+        let slot = item_def.equip_slot?;
+
+        let equipped = player
+            .equipment
+            .get_equipped(slot)
+            .and_then(|id| item_db.get(id))
+            .map(|def| def.stat_mods.clone())
+            .unwrap_or_default();
+
+        let new = &item_def.stat_mods;
+        Some(StatModifiers {
+            strength: new.strength - equipped.strength,
+            dexterity: new.dexterity - equipped.dexterity,
+            intelligence: new.intelligence - equipped.intelligence,
+            vitality: new.vitality - equipped.vitality,
+            luck: new.luck - equipped.luck,
+            max_health: new.max_health - equipped.max_health,
+            max_mana: new.max_mana - equipped.max_mana,
+            physical_damage: new.physical_damage - equipped.physical_damage,
+            magic_damage: new.magic_damage - equipped.magic_damage,
+            defense: new.defense - equipped.defense,
+            dodge_chance: new.dodge_chance - equipped.dodge_chance,
+            crit_chance: new.crit_chance - equipped.crit_chance,
+            move_speed: new.move_speed - equipped.move_speed,
+            percent: PercentModifiers {
+                max_health: new.percent.max_health - equipped.percent.max_health,
+                max_mana: new.percent.max_mana - equipped.percent.max_mana,
+                physical_damage: new.percent.physical_damage - equipped.percent.physical_damage,
+                magic_damage: new.percent.magic_damage - equipped.percent.magic_damage,
+                defense: new.percent.defense - equipped.percent.defense,
+                move_speed: new.percent.move_speed - equipped.percent.move_speed,
+            },
+        })
+    }
+
+    /// Sell items from an inventory slot
+    /// Returns the gold gained
+    pub fn sell_item(
+        player: &mut Player,
+        slot: usize,
+        quantity: u32,
+        item_db: &ItemDatabase,
+    ) -> Result<i32, String> {
+        let item_id = player
+            .inventory
+            .get_item(slot)
+            .ok_or("No item in that slot")?
+            .item_id;
+
+        let item_def = item_db.get(item_id).ok_or("Item not found in database")?;
+
+        if item_def.item_type == ItemType::QuestItem {
+            return Err(format!("{} cannot be sold", item_def.name));
+        }
+
+        let sold = player
+            .inventory
+            .remove_item(slot, quantity)
+            .ok_or("No item in that slot")?;
+
+        let gold = item_def.sell_value * sold.quantity as i32;
+        player.inventory.add_gold(gold);
+
+        Ok(gold)
+    }
+
+    /// Sell every item below the given rarity (quest items are never sold)
+    /// Returns (gold gained, number of items sold)
+    pub fn sell_all_below_rarity(
+        player: &mut Player,
+        rarity: Rarity,
+        item_db: &ItemDatabase,
+    ) -> (i32, u32) {
+        let mut total_gold = 0;
+        let mut total_sold = 0;
+
+        for slot in 0..player.inventory.items.len() {
+            let Some(stack) = player.inventory.get_item(slot) else {
+                continue;
+            };
+            let Some(item_def) = item_db.get(stack.item_id) else {
+                continue;
+            };
+
+            if item_def.rarity >= rarity || item_def.item_type == ItemType::QuestItem {
+                continue;
+            }
+
+            let quantity = stack.quantity;
+            if let Ok(gold) = Self::sell_item(player, slot, quantity, item_db) {
+                total_gold += gold;
+                total_sold += quantity;
+            }
+        }
+
+        (total_gold, total_sold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sell_all_below_rarity() {
+        let db = ItemDatabase::with_starter_items();
+        let mut player = Player::new("Tester", PlayerClass::Balanced);
+
+        player.inventory.add_item(db.get(ItemId(1)).unwrap(), 1); // Rusty Sword (common, 10)
+        player.inventory.add_item(db.get(ItemId(2)).unwrap(), 1); // Iron Sword (uncommon, 50)
+        player.inventory.add_item(db.get(ItemId(100)).unwrap(), 3); // Health Potion (common, 10)
+        player.inventory.add_item(db.get(ItemId(3)).unwrap(), 1); // Magic Staff (rare, 150)
+        player.inventory.add_item(db.get(ItemId(200)).unwrap(), 1); // Mysterious Key (rare quest item)
+
+        let (gold, sold) = Shop::sell_all_below_rarity(&mut player, Rarity::Rare, &db);
+
+        assert_eq!(gold, 10 + 50 + 30);
+        assert_eq!(sold, 5);
+        assert_eq!(player.inventory.gold(), 90);
+        assert_eq!(player.inventory.count_item(ItemId(1)), 0);
+        assert_eq!(player.inventory.count_item(ItemId(100)), 0);
+        assert_eq!(player.inventory.count_item(ItemId(3)), 1);
+        assert_eq!(player.inventory.count_item(ItemId(200)), 1);
+    }
+
+    #[test]
+    fn test_sell_preview() {
+        let db = ItemDatabase::with_starter_items();
+        let mut inventory = Inventory::new(4);
+        inventory.add_item(db.get(ItemId(100)).unwrap(), 4);
+
+        assert_eq!(Shop::sell_preview(&inventory, 0, &db), Some(40));
+        assert_eq!(Shop::sell_preview(&inventory, 1, &db), None);
+    }
+}
@@ -107,6 +107,86 @@ impl SkillDef {
     }
 }
 
+/// Applies skill effects to character stats
+pub struct Skill;
+
+impl Skill {
+    /// Cast a skill: spend the caster's mana, then apply each effect
+    ///
+    /// `SelfTarget` skills affect the caster, `SingleEnemy` the first target and
+    /// `AllEnemies`/`AreaOfEffect` every target (the caller picks who is in range).
+    /// Damage adds the caster's physical/magic damage to the effect's base values,
+    /// reduced by the recipient's defense/magic resist. Status effects are pushed
+    /// to `status_sink` once per recipient, in order, for the caller to attach.
+    /// Buffs are added to the recipient's stats; reverting timed buffs is up to
+    /// the caller. Teleport is left to whoever moves the caster.
+    pub fn apply(
+        skill_def: &SkillDef,
+        caster: &mut Stats,
+        targets: &mut [&mut Stats],
+        status_sink: &mut Vec<TimedStatusEffect>,
+    ) -> Result<(), String> {
+        if !caster.use_mana(skill_def.mana_cost) {
+            return Err(format!("Not enough mana to cast {}", skill_def.name));
+        }
+
+        let physical_bonus = caster.physical_damage;
+        let magic_bonus = caster.magic_damage;
+        let apply_to = |recipient: &mut Stats, status_sink: &mut Vec<TimedStatusEffect>| {
+            for effect in &skill_def.effects {
+                Self::apply_effect(effect, physical_bonus, magic_bonus, recipient, status_sink);
+            }
+        };
+
+        match skill_def.target_type {
+            TargetType::SelfTarget => apply_to(caster, status_sink),
+            TargetType::SingleEnemy => {
+                if let Some(target) = targets.first_mut() {
+                    apply_to(target, status_sink);
+                }
+            }
+            TargetType::AllEnemies | TargetType::AreaOfEffect(_) => {
+                for target in targets.iter_mut() {
+                    apply_to(target, status_sink);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_effect(
+        effect: &SkillEffect,
+        physical_bonus: i32,
+        magic_bonus: i32,
+        recipient: &mut Stats,
+        status_sink: &mut Vec<TimedStatusEffect>,
+    ) {
+        match effect {
+            SkillEffect::Damage { physical, magical } => {
+                let mut damage = 0;
+                if *physical > 0 {
+                    damage += recipient.mitigate_damage(physical + physical_bonus, false);
+                }
+                if *magical > 0 {
+                    damage += recipient.mitigate_damage(magical + magic_bonus, true);
+                }
+                recipient.current_health = (recipient.current_health - damage).max(0);
+            }
+            SkillEffect::Heal(amount) => {
+                recipient.heal(*amount);
+            }
+            SkillEffect::ApplyStatusEffect { effect, duration, power } => {
+                status_sink.push(TimedStatusEffect::new(*effect, *duration, *power));
+            }
+            SkillEffect::BuffStats { mods, .. } => {
+                mods.add_to(recipient);
+                mods.percent.apply_to(recipient);
+            }
+            SkillEffect::Teleport { .. } => {}
+        }
+    }
+}
+
 /// Skill instance tracking cooldowns
 #[derive(Debug, Clone)]
 pub struct SkillInstance {
@@ -135,6 +215,14 @@ impl SkillInstance {
     pub fn trigger(&mut self, cooldown: f32) {
         self.current_cooldown = cooldown;
     }
+
+    /// Fraction of `cooldown` still to wait (1.0 just after use, 0.0 when ready)
+    pub fn cooldown_fraction(&self, cooldown: f32) -> f32 {
+        if cooldown <= 0.0 {
+            return 0.0;
+        }
+        (self.current_cooldown / cooldown).clamp(0.0, 1.0)
+    }
 }
 
 /// Skill database
@@ -338,19 +426,41 @@ impl Default for SkillDatabase {
 }
 
 /// Player's skill manager
+///
+/// Besides each skill's own cooldown, using any skill starts a shared global
+/// cooldown (GCD) during which no skill can be used.
 pub struct PlayerSkills {
     learned_skills: HashMap<SkillId, SkillInstance>,
+    /// Learned skills in the order they were learned, for hotkeys 1-5
+    hotbar: Vec<SkillId>,
+    /// Global cooldown started by every skill use, in seconds
+    pub global_cooldown: f32,
+    gcd_remaining: f32,
 }
 
 impl PlayerSkills {
+    /// Skills reachable from the number keys
+    pub const HOTBAR_SLOTS: usize = 5;
+
     pub fn new() -> Self {
         Self {
             learned_skills: HashMap::new(),
+            hotbar: Vec::new(),
+            global_cooldown: 1.0,
+            gcd_remaining: 0.0,
         }
     }
 
+    pub fn with_global_cooldown(mut self, seconds: f32) -> Self {
+        self.global_cooldown = seconds;
+        self
+    }
+
     /// Learn a new skill
     pub fn learn_skill(&mut self, skill_id: SkillId) {
+        if !self.hotbar.contains(&skill_id) {
+            self.hotbar.push(skill_id);
+        }
         self.learned_skills
             .insert(skill_id, SkillInstance::new(skill_id));
     }
@@ -360,26 +470,26 @@ impl PlayerSkills {
         self.learned_skills.contains_key(&skill_id)
     }
 
-    /// Check if a skill is ready to use
+    /// Check if a skill is ready to use (off its own and the global cooldown)
     pub fn is_skill_ready(&self, skill_id: SkillId) -> bool {
-        self.learned_skills
-            .get(&skill_id)
-            .map(|s| s.is_ready())
-            .unwrap_or(false)
+        self.gcd_remaining <= 0.0
+            && self
+                .learned_skills
+                .get(&skill_id)
+                .map(|s| s.is_ready())
+                .unwrap_or(false)
     }
 
-    /// Use a skill (trigger cooldown)
+    /// Use a skill (trigger its cooldown and the global cooldown)
     pub fn use_skill(&mut self, skill_id: SkillId, skill_def: &SkillDef) -> bool {
+        if !self.is_skill_ready(skill_id) {
+            return false;
+        }
         if let Some(instance) = self.learned_skills.get_mut(&skill_id) {
-            if instance.is_ready() {
-                instance.trigger(skill_def.cooldown);
-                true
-            } else {
-                false
-            }
-        } else {
-            false
+            instance.trigger(skill_def.cooldown);
         }
+        self.gcd_remaining = self.global_cooldown;
+        true
     }
 
     /// Update all skill cooldowns
@@ -387,12 +497,45 @@ impl PlayerSkills {
         for instance in self.learned_skills.values_mut() {
             instance.update(delta_time);
         }
+        self.gcd_remaining = (self.gcd_remaining - delta_time).max(0.0);
     }
 
     /// Get all learned skills
     pub fn get_all_skills(&self) -> Vec<SkillId> {
         self.learned_skills.keys().copied().collect()
     }
+
+    pub fn get_instance(&self, skill_id: SkillId) -> Option<&SkillInstance> {
+        self.learned_skills.get(&skill_id)
+    }
+
+    /// Skill on hotkey `n` (1-5): the nth learned skill
+    pub fn slot_for_hotkey(&self, n: usize) -> Option<SkillId> {
+        if n == 0 || n > Self::HOTBAR_SLOTS {
+            return None;
+        }
+        self.hotbar.get(n - 1).copied()
+    }
+
+    /// Seconds left on the global cooldown
+    pub fn global_cooldown_remaining(&self) -> f32 {
+        self.gcd_remaining
+    }
+
+    /// How much of a skill's wait is left, for drawing a cooldown sweep
+    /// The larger of its own cooldown and the global cooldown
+    pub fn cooldown_fraction(&self, skill_def: &SkillDef) -> f32 {
+        let own = self
+            .get_instance(skill_def.id)
+            .map(|instance| instance.cooldown_fraction(skill_def.cooldown))
+            .unwrap_or(0.0);
+        let global = if self.global_cooldown > 0.0 {
+            (self.gcd_remaining / self.global_cooldown).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        own.max(global)
+    }
 }
 
 impl Default for PlayerSkills {
@@ -400,3 +543,93 @@ impl Default for PlayerSkills {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dummy() -> Stats {
+        let mut stats = Stats::balanced(1);
+        stats.restore_full();
+        stats
+    }
+
+    #[test]
+    fn test_fireball_damages_and_burns_target() {
+        let db = SkillDatabase::with_starter_skills();
+        let fireball = db.get(SkillId(10)).unwrap();
+        let mut caster = Stats::mage(1);
+        caster.restore_full();
+        let mut target = dummy();
+        let mut other = dummy();
+        let mut statuses = Vec::new();
+
+        Skill::apply(fireball, &mut caster, &mut [&mut target, &mut other], &mut statuses).unwrap();
+
+        let expected = 30 + caster.magic_damage - target.magic_resist;
+        assert_eq!(target.current_health, target.max_health - expected);
+        // Single target: the second dummy is untouched
+        assert_eq!(other.current_health, other.max_health);
+        assert_eq!(caster.current_mana, caster.max_mana - fireball.mana_cost);
+
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].effect, StatusEffect::Burning);
+        assert_eq!((statuses[0].duration, statuses[0].power), (5.0, 5));
+    }
+
+    #[test]
+    fn test_cast_without_mana_fails() {
+        let db = SkillDatabase::with_starter_skills();
+        let fireball = db.get(SkillId(10)).unwrap();
+        let mut caster = Stats::mage(1);
+        caster.current_mana = fireball.mana_cost - 1;
+        let mut target = dummy();
+        let mut statuses = Vec::new();
+
+        assert!(Skill::apply(fireball, &mut caster, &mut [&mut target], &mut statuses).is_err());
+        assert_eq!(caster.current_mana, fireball.mana_cost - 1);
+        assert_eq!(target.current_health, target.max_health);
+        assert!(statuses.is_empty());
+    }
+
+    #[test]
+    fn test_cooldown_fraction_and_hotkeys() {
+        let db = SkillDatabase::with_starter_skills();
+        let fireball = db.get(SkillId(10)).unwrap();
+        let mut skills = PlayerSkills::new();
+        skills.learn_skill(SkillId(10));
+        skills.learn_skill(SkillId(100));
+        assert_eq!(skills.slot_for_hotkey(1), Some(SkillId(10)));
+        assert_eq!(skills.slot_for_hotkey(2), Some(SkillId(100)));
+        assert_eq!(skills.slot_for_hotkey(3), None);
+        assert_eq!(skills.slot_for_hotkey(0), None);
+
+        assert_eq!(skills.cooldown_fraction(fireball), 0.0);
+        assert!(skills.use_skill(SkillId(10), fireball));
+        // The global cooldown blocks other skills too
+        assert!(!skills.is_skill_ready(SkillId(100)));
+
+        skills.update(1.5);
+        assert!(skills.is_skill_ready(SkillId(100)));
+        let instance = skills.get_instance(SkillId(10)).unwrap();
+        assert_eq!(skills.cooldown_fraction(fireball), instance.current_cooldown / fireball.cooldown);
+        assert!(skills.cooldown_fraction(fireball) > 0.0);
+
+        skills.update(fireball.cooldown);
+        assert_eq!(skills.cooldown_fraction(fireball), 0.0);
+    }
+
+    #[test]
+    fn test_self_heal_ignores_targets() {
+        let db = SkillDatabase::with_starter_skills();
+        let heal = db.get(SkillId(100)).unwrap();
+        let mut caster = dummy();
+        caster.current_health = 10;
+        let mut enemy = dummy();
+        enemy.current_health = 10;
+
+        Skill::apply(heal, &mut caster, &mut [&mut enemy], &mut Vec::new()).unwrap();
+        assert_eq!(caster.current_health, 60);
+        assert_eq!(enemy.current_health, 10);
+    }
+}
@@ -1,10 +1,12 @@
 /// Character Stats System
 /// Defines stats for players and enemies
 
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use super::items::{PercentModifiers, StatModifiers};
 
 /// Core character statistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Stats {
     // Core attributes
     pub strength: i32,     // Physical damage and carrying capacity
@@ -21,10 +23,19 @@ pub struct Stats {
     pub physical_damage: i32,
     pub magic_damage: i32,
     pub defense: i32,
+    /// Flat reduction applied to magic damage
+    #[serde(default)]
+    pub magic_resist: i32,
     pub dodge_chance: f32,  // 0.0 to 1.0
     pub crit_chance: f32,   // 0.0 to 1.0
     pub crit_multiplier: f32,
     pub move_speed: f32,
+    /// Health regenerated per second out of poison/curse
+    #[serde(default)]
+    pub health_regen_per_sec: f32,
+    /// Mana regenerated per second out of poison/curse
+    #[serde(default)]
+    pub mana_regen_per_sec: f32,
 }
 
 impl Stats {
@@ -43,10 +54,13 @@ impl Stats {
             physical_damage: 0,
             magic_damage: 0,
             defense: 0,
+            magic_resist: 0,
             dodge_chance: 0.0,
             crit_chance: 0.0,
             crit_multiplier: 1.5,
             move_speed: 100.0,
+            health_regen_per_sec: 0.0,
+            mana_regen_per_sec: 0.0,
         };
         stats.recalculate();
         stats
@@ -76,6 +90,28 @@ impl Stats {
         Self::new(base, base * 2, base, base, base + 5)
     }
 
+    /// Current value of a core attribute
+    pub fn attribute(&self, attribute: Attribute) -> i32 {
+        match attribute {
+            Attribute::Strength => self.strength,
+            Attribute::Dexterity => self.dexterity,
+            Attribute::Intelligence => self.intelligence,
+            Attribute::Vitality => self.vitality,
+            Attribute::Luck => self.luck,
+        }
+    }
+
+    /// Mutable access to a core attribute (call `recalculate` afterwards)
+    pub fn attribute_mut(&mut self, attribute: Attribute) -> &mut i32 {
+        match attribute {
+            Attribute::Strength => &mut self.strength,
+            Attribute::Dexterity => &mut self.dexterity,
+            Attribute::Intelligence => &mut self.intelligence,
+            Attribute::Vitality => &mut self.vitality,
+            Attribute::Luck => &mut self.luck,
+        }
+    }
+
     /// Recalculate all derived stats based on attributes
     pub fn recalculate(&mut self) {
         // Health: 10 HP per vitality
@@ -93,6 +129,9 @@ impl Stats {
         // Defense: 1 defense per vitality
         self.defense = self.vitality;
 
+        // Magic resist: 1 per 2 intelligence
+        self.magic_resist = self.intelligence / 2;
+
         // Dodge chance: 1% per dexterity (max 75%)
         self.dodge_chance = (self.dexterity as f32 * 0.01).min(0.75);
 
@@ -102,6 +141,10 @@ impl Stats {
         // Move speed: 100 + (0.5 per dexterity)
         self.move_speed = 100.0 + (self.dexterity as f32 * 0.5);
 
+        // Regen: 0.5 HP/s + 0.1 per vitality, 0.5 MP/s + 0.1 per intelligence
+        self.health_regen_per_sec = 0.5 + self.vitality as f32 * 0.1;
+        self.mana_regen_per_sec = 0.5 + self.intelligence as f32 * 0.1;
+
         // Cap current health/mana at max if needed
         if self.current_health > self.max_health {
             self.current_health = self.max_health;
@@ -117,9 +160,67 @@ impl Stats {
         self.current_mana = self.max_mana;
     }
 
-    /// Take damage (returns true if still alive)
+    /// Damage left after defense (physical) or magic resist (magic)
+    /// Always at least 1
+    pub fn mitigate_damage(&self, damage: i32, is_magic: bool) -> i32 {
+        let reduction = if is_magic { self.magic_resist } else { self.defense };
+        (damage - reduction).max(1)
+    }
+
+    /// Work out how much of an attack lands on this character
+    ///
+    /// Rolls this character's dodge first (a dodged hit deals 0), then the
+    /// attacker's crit, then applies defense or magic resist.
+    pub fn compute_incoming_damage(
+        &self,
+        attacker: &Stats,
+        base_damage: i32,
+        is_magic: bool,
+        rng: &mut impl Rng,
+    ) -> DamageResult {
+        if rng.r#gen::<f32>() < self.dodge_chance {
+            return DamageResult { amount: 0, crit: false, dodged: true };
+        }
+
+        let crit = rng.r#gen::<f32>() < attacker.crit_chance;
+        let damage = if crit {
+            (base_damage as f32 * attacker.crit_multiplier).round() as i32
+        } else {
+            base_damage
+        };
+
+        DamageResult {
+            amount: self.mitigate_damage(damage, is_magic),
+            crit,
+            dodged: false,
+        }
+    }
+
+    /// Take an attack from `attacker`, rolling dodge and crit
+    /// Returns what landed so callers can show "Dodged!" or crit numbers
+    pub fn take_hit(
+        &mut self,
+        attacker: &Stats,
+        base_damage: i32,
+        is_magic: bool,
+        rng: &mut impl Rng,
+    ) -> DamageResult {
+        let result = self.compute_incoming_damage(attacker, base_damage, is_magic, rng);
+        self.current_health = (self.current_health - result.amount).max(0);
+        result
+    }
+
+    /// Take physical damage with no attacker behind it, which can't be dodged or
+    /// crit (returns true if still alive)
+    /// Hits from an attacker should go through `take_hit` instead
     pub fn take_damage(&mut self, damage: i32) -> bool {
-        let actual_damage = (damage - self.defense).max(1); // Always deal at least 1 damage
+        self.take_dot_damage(damage)
+    }
+
+    /// Take a poison or burn tick: defense still applies, but there's no dodge
+    /// roll (returns true if still alive)
+    pub fn take_dot_damage(&mut self, damage: i32) -> bool {
+        let actual_damage = self.mitigate_damage(damage, false);
         self.current_health = (self.current_health - actual_damage).max(0);
         self.current_health > 0
     }
@@ -177,6 +278,35 @@ impl Stats {
     }
 }
 
+/// Outcome of one attack against a character
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DamageResult {
+    /// Health removed (0 when dodged)
+    pub amount: i32,
+    pub crit: bool,
+    pub dodged: bool,
+}
+
+/// Core attributes that stat points can be spent on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Attribute {
+    Strength,
+    Dexterity,
+    Intelligence,
+    Vitality,
+    Luck,
+}
+
+impl Attribute {
+    pub const ALL: [Attribute; 5] = [
+        Attribute::Strength,
+        Attribute::Dexterity,
+        Attribute::Intelligence,
+        Attribute::Vitality,
+        Attribute::Luck,
+    ];
+}
+
 /// Character level and experience system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Level {
@@ -241,8 +371,82 @@ pub enum StatusEffect {
     Regenerating,
 }
 
+/// What happens when an effect is applied to a character that already has it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackPolicy {
+    /// Keep one instance, extending it to the longer duration (and stronger power)
+    RefreshDuration,
+    /// Keep one instance and add the new power to it
+    StackIntensity,
+    /// Keep the existing instance unchanged
+    IgnoreIfPresent,
+}
+
+impl StatusEffect {
+    pub fn stack_policy(&self) -> StackPolicy {
+        match self {
+            StatusEffect::Regenerating => StackPolicy::StackIntensity,
+            StatusEffect::Invulnerable => StackPolicy::IgnoreIfPresent,
+            StatusEffect::Poisoned
+            | StatusEffect::Burning
+            | StatusEffect::Frozen
+            | StatusEffect::Stunned
+            | StatusEffect::Blessed
+            | StatusEffect::Cursed
+            | StatusEffect::Hasted
+            | StatusEffect::Slowed => StackPolicy::RefreshDuration,
+        }
+    }
+
+    /// Stat changes while the effect is active
+    /// Hasted/Slowed read `power` as a percentage of move speed; Blessed/Cursed
+    /// add or remove `power` damage and defense
+    pub fn stat_modifiers(&self, power: i32) -> StatModifiers {
+        match self {
+            StatusEffect::Hasted | StatusEffect::Slowed => {
+                let sign = if *self == StatusEffect::Hasted { 1.0 } else { -1.0 };
+                StatModifiers {
+                    percent: PercentModifiers { move_speed: sign * power as f32 / 100.0, ..Default::default() },
+                    ..Default::default()
+                }
+            }
+            StatusEffect::Blessed | StatusEffect::Cursed => {
+                let amount = if *self == StatusEffect::Blessed { power } else { -power };
+                StatModifiers {
+                    physical_damage: amount,
+                    magic_damage: amount,
+                    defense: amount,
+                    ..Default::default()
+                }
+            }
+            _ => StatModifiers::default(),
+        }
+    }
+}
+
+/// Add an effect to a list of active effects, following its `StackPolicy`
+/// Shared by players, enemies and the ecs `StatusEffects` component
+pub fn apply_status_effect(effects: &mut Vec<TimedStatusEffect>, effect: TimedStatusEffect) {
+    let Some(existing) = effects.iter_mut().find(|e| e.effect == effect.effect) else {
+        effects.push(effect);
+        return;
+    };
+
+    match effect.effect.stack_policy() {
+        StackPolicy::RefreshDuration => {
+            existing.duration = existing.duration.max(effect.duration);
+            existing.power = existing.power.max(effect.power);
+        }
+        StackPolicy::StackIntensity => {
+            existing.duration = existing.duration.max(effect.duration);
+            existing.power += effect.power;
+        }
+        StackPolicy::IgnoreIfPresent => {}
+    }
+}
+
 /// Timed status effect with duration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TimedStatusEffect {
     pub effect: StatusEffect,
     pub duration: f32,     // Seconds remaining
@@ -292,6 +496,27 @@ impl TimedStatusEffect {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn test_status_effect_stacking() {
+        let mut effects = Vec::new();
+        apply_status_effect(&mut effects, TimedStatusEffect::new(StatusEffect::Poisoned, 5.0, 3));
+        effects[0].update(4.0);
+        apply_status_effect(&mut effects, TimedStatusEffect::new(StatusEffect::Poisoned, 5.0, 3));
+        assert_eq!(effects.len(), 1);
+        assert_eq!(effects[0].duration, 5.0);
+        assert_eq!(effects[0].power, 3);
+
+        apply_status_effect(&mut effects, TimedStatusEffect::new(StatusEffect::Regenerating, 5.0, 2));
+        apply_status_effect(&mut effects, TimedStatusEffect::new(StatusEffect::Regenerating, 3.0, 2));
+        apply_status_effect(&mut effects, TimedStatusEffect::new(StatusEffect::Invulnerable, 2.0, 0));
+        apply_status_effect(&mut effects, TimedStatusEffect::new(StatusEffect::Invulnerable, 9.0, 0));
+        assert_eq!(effects.len(), 3);
+        assert_eq!(effects[1].power, 4);
+        assert_eq!(effects[1].duration, 5.0);
+        assert_eq!(effects[2].duration, 2.0);
+    }
 
     #[test]
     fn test_stats_calculation() {
@@ -306,6 +531,66 @@ mod tests {
         assert!(stats.is_dead());
     }
 
+    /// Rolls always 0.0: every chance succeeds
+    fn always_rng() -> StepRng {
+        StepRng::new(0, 0)
+    }
+
+    /// Rolls always just under 1.0: every chance fails
+    fn never_rng() -> StepRng {
+        StepRng::new(u64::MAX, 0)
+    }
+
+    #[test]
+    fn test_dodged_hit_deals_nothing() {
+        let attacker = Stats::warrior(5);
+        let mut defender = Stats::rogue(5);
+        defender.restore_full();
+        let health = defender.current_health;
+
+        let result = defender.take_hit(&attacker, 100, false, &mut always_rng());
+        assert_eq!(result, DamageResult { amount: 0, crit: false, dodged: true });
+        assert_eq!(defender.current_health, health);
+    }
+
+    #[test]
+    fn test_crit_multiplies_before_defense() {
+        let attacker = Stats::balanced(1);
+        let mut defender = Stats::balanced(1);
+        defender.dodge_chance = 0.0;
+
+        let result = defender.compute_incoming_damage(&attacker, 40, false, &mut always_rng());
+        assert!(result.crit && !result.dodged);
+        assert_eq!(result.amount, 60 - defender.defense);
+    }
+
+    #[test]
+    fn test_normal_hit_uses_defense_or_magic_resist() {
+        let attacker = Stats::balanced(1);
+        let defender = Stats::warrior(1);
+
+        let physical = defender.compute_incoming_damage(&attacker, 40, false, &mut never_rng());
+        assert_eq!(physical, DamageResult { amount: 40 - defender.defense, crit: false, dodged: false });
+
+        let magic = defender.compute_incoming_damage(&attacker, 40, true, &mut never_rng());
+        assert_eq!(magic.amount, 40 - defender.magic_resist);
+        assert_ne!(defender.defense, defender.magic_resist);
+
+        // Armor never reduces a hit below 1
+        assert_eq!(defender.compute_incoming_damage(&attacker, 1, false, &mut never_rng()).amount, 1);
+    }
+
+    #[test]
+    fn test_dot_damage_is_mitigated_but_never_dodged() {
+        let mut defender = Stats::warrior(1);
+        defender.restore_full();
+        defender.dodge_chance = 1.0;
+        let health = defender.current_health;
+
+        defender.take_dot_damage(40);
+        assert_eq!(defender.current_health, health - (40 - defender.defense));
+    }
+
     #[test]
     fn test_level_up() {
         let mut level = Level::new();
@@ -1,6 +1,7 @@
 /// Story and Dialogue System
 /// Branching dialogue trees, quests, and narrative progression
 
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -36,7 +37,7 @@ impl DialogueCondition {
                 player.inventory.count_item(*item_id) >= *quantity
             }
             DialogueCondition::MinLevel(level) => player.level.current_level >= *level,
-            DialogueCondition::MinGold(gold) => player.inventory.gold >= *gold,
+            DialogueCondition::MinGold(gold) => player.inventory.gold() >= *gold,
             DialogueCondition::AlwaysTrue => true,
         }
     }
@@ -56,6 +57,18 @@ pub enum DialogueAction {
     EndDialogue,
 }
 
+/// Follow-up work a dialogue action leaves to the caller
+#[derive(Debug, Clone, PartialEq)]
+pub enum DialogueSignal {
+    /// Exp from the action levelled the player up to this level
+    LevelUp(i32),
+    /// Spawn this enemy and start a fight
+    StartBattle(u32),
+    /// Move the player to this checkpoint
+    Teleport(String),
+    EndDialogue,
+}
+
 /// A single dialogue choice
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DialogueChoice {
@@ -63,6 +76,13 @@ pub struct DialogueChoice {
     pub condition: DialogueCondition,
     pub next_node: Option<DialogueId>,
     pub actions: Vec<DialogueAction>,
+    /// Relative chance of being picked when the node auto-selects
+    #[serde(default = "default_choice_weight")]
+    pub weight: f32,
+}
+
+fn default_choice_weight() -> f32 {
+    1.0
 }
 
 impl DialogueChoice {
@@ -72,9 +92,15 @@ impl DialogueChoice {
             condition: DialogueCondition::AlwaysTrue,
             next_node: None,
             actions: Vec::new(),
+            weight: default_choice_weight(),
         }
     }
 
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
+    }
+
     pub fn with_condition(mut self, condition: DialogueCondition) -> Self {
         self.condition = condition;
         self
@@ -99,6 +125,9 @@ pub struct DialogueNode {
     pub text: String,
     pub choices: Vec<DialogueChoice>,
     pub auto_continue: Option<DialogueId>, // Auto-continue to next node
+    /// Pick a choice by weight instead of waiting for the player (NPC banter)
+    #[serde(default)]
+    pub auto_select: bool,
 }
 
 impl DialogueNode {
@@ -109,9 +138,15 @@ impl DialogueNode {
             text: text.to_string(),
             choices: Vec::new(),
             auto_continue: None,
+            auto_select: false,
         }
     }
 
+    pub fn with_auto_select(mut self) -> Self {
+        self.auto_select = true;
+        self
+    }
+
     pub fn with_choice(mut self, choice: DialogueChoice) -> Self {
         self.choices.push(choice);
         self
@@ -168,6 +203,177 @@ impl DialogueTree {
     }
 }
 
+/// Where a conversation stands after a step
+#[derive(Debug, Clone, Copy)]
+pub enum DialogueState<'a> {
+    Continue(&'a DialogueNode),
+    Ended,
+}
+
+/// A conversation in progress through a dialogue tree
+///
+/// Player-driven nodes advance with `choose`/`advance`, NPC banter with
+/// `auto_advance`. Actions are applied to the player as choices are made;
+/// anything left to the caller (battles, teleports, level-ups) is queued and
+/// drained with `take_signals`.
+#[derive(Debug, Clone)]
+pub struct DialogueSession<'a> {
+    tree: &'a DialogueTree,
+    current: Option<DialogueId>,
+    signals: Vec<DialogueSignal>,
+}
+
+/// The runtime that walks a dialogue tree; same type as `DialogueSession`
+pub type DialogueRunner<'a> = DialogueSession<'a>;
+
+impl<'a> DialogueSession<'a> {
+    /// Start at the tree's root node
+    pub fn new(tree: &'a DialogueTree) -> Self {
+        Self {
+            tree,
+            current: Some(tree.root_node),
+            signals: Vec::new(),
+        }
+    }
+
+    pub fn current_node(&self) -> Option<&'a DialogueNode> {
+        self.tree.get_node(self.current?)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current_node().is_none()
+    }
+
+    /// Current state of the conversation
+    pub fn state(&self) -> DialogueState<'a> {
+        match self.current_node() {
+            Some(node) => DialogueState::Continue(node),
+            None => DialogueState::Ended,
+        }
+    }
+
+    /// Take the signals raised by actions since the last call
+    pub fn take_signals(&mut self) -> Vec<DialogueSignal> {
+        std::mem::take(&mut self.signals)
+    }
+
+    /// Choices the player can pick at the current node
+    pub fn available_choices(
+        &self,
+        player: &super::Player,
+        item_db: &super::ItemDatabase,
+    ) -> Vec<&'a DialogueChoice> {
+        match self.current {
+            Some(id) => self.tree.get_available_choices(id, player, item_db),
+            None => Vec::new(),
+        }
+    }
+
+    /// Pick one of `available_choices` by index, apply its actions and move on
+    ///
+    /// A choice with no next node (or an `EndDialogue` action) ends the
+    /// conversation. If an action fails the conversation stays where it was.
+    pub fn choose(
+        &mut self,
+        index: usize,
+        player: &mut super::Player,
+        item_db: &super::ItemDatabase,
+    ) -> Result<DialogueState<'a>, String> {
+        let choice = *self
+            .available_choices(player, item_db)
+            .get(index)
+            .ok_or_else(|| format!("No dialogue choice {}", index))?;
+        self.run_choice(choice, player, item_db)?;
+        Ok(self.state())
+    }
+
+    /// Move past a node without choices, following `auto_continue`
+    /// or ending the conversation
+    pub fn advance(&mut self) -> DialogueState<'a> {
+        if let Some(node) = self.current_node()
+            && node.choices.is_empty()
+        {
+            self.current = node.auto_continue;
+        }
+        self.state()
+    }
+
+    /// Advance an auto-select node without player input
+    ///
+    /// Picks one of the available choices at random, weighted by `weight`, applies
+    /// its actions and moves to its next node. A node with no available choices
+    /// follows `auto_continue`, or ends the conversation. Returns the picked
+    /// choice; nodes that aren't flagged `auto_select` are left alone.
+    pub fn auto_advance(
+        &mut self,
+        rng: &mut super::GameRng,
+        player: &mut super::Player,
+        item_db: &super::ItemDatabase,
+    ) -> Result<Option<&'a DialogueChoice>, String> {
+        let Some(node) = self.current_node() else {
+            return Ok(None);
+        };
+        if !node.auto_select {
+            return Ok(None);
+        }
+
+        let available: Vec<&'a DialogueChoice> = node
+            .choices
+            .iter()
+            .filter(|choice| choice.weight > 0.0 && choice.condition.check(player, item_db))
+            .collect();
+        let Some(choice) = Self::pick_weighted(&available, rng) else {
+            self.current = node.auto_continue;
+            return Ok(None);
+        };
+
+        self.run_choice(choice, player, item_db)?;
+        Ok(Some(choice))
+    }
+
+    /// Apply a choice's actions, then move to its next node
+    fn run_choice(
+        &mut self,
+        choice: &'a DialogueChoice,
+        player: &mut super::Player,
+        item_db: &super::ItemDatabase,
+    ) -> Result<(), String> {
+        // Apply to a copy so a failing action rolls back the ones before it
+        let mut updated = player.clone();
+        let mut signals = Vec::new();
+        let mut next = choice.next_node;
+        for action in &choice.actions {
+            match updated.apply_dialogue_action(action, item_db)? {
+                Some(DialogueSignal::EndDialogue) => next = None,
+                Some(signal) => signals.push(signal),
+                None => {}
+            }
+        }
+
+        *player = updated;
+        self.signals.extend(signals);
+        self.current = next;
+        Ok(())
+    }
+
+    fn pick_weighted(choices: &[&'a DialogueChoice], rng: &mut super::GameRng) -> Option<&'a DialogueChoice> {
+        let total: f32 = choices.iter().map(|choice| choice.weight).sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut roll = rng.gen_range(0.0..total);
+        for choice in choices {
+            if roll < choice.weight {
+                return Some(choice);
+            }
+            roll -= choice.weight;
+        }
+        // Float rounding can leave the roll just past the last weight
+        choices.last().copied()
+    }
+}
+
 /// Quest status
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum QuestStatus {
@@ -224,6 +430,8 @@ pub struct Quest {
     pub objectives: Vec<QuestObjective>,
     pub rewards: Vec<DialogueAction>,
     pub required_level: i32,
+    #[serde(default)]
+    pub rewards_claimed: bool,
 }
 
 impl Quest {
@@ -236,6 +444,7 @@ impl Quest {
             objectives: Vec::new(),
             rewards: Vec::new(),
             required_level: 1,
+            rewards_claimed: false,
         }
     }
 
@@ -268,6 +477,101 @@ impl Quest {
     pub fn complete(&mut self) {
         self.status = QuestStatus::Completed;
     }
+
+    /// Record a kill for matching objectives, returns true if any advanced
+    pub fn on_enemy_killed(&mut self, enemy_id: u32) -> bool {
+        self.advance(|objective| match objective {
+            QuestObjective::KillEnemies { enemy_id: target, current, required } if *target == enemy_id => {
+                Self::bump(current, *required, 1)
+            }
+            _ => false,
+        })
+    }
+
+    /// Record picked up items for matching objectives
+    pub fn on_item_collected(&mut self, item_id: super::ItemId, quantity: u32) -> bool {
+        self.advance(|objective| match objective {
+            QuestObjective::CollectItems { item_id: target, current, required } if *target == item_id => {
+                Self::bump(current, *required, quantity)
+            }
+            _ => false,
+        })
+    }
+
+    /// Record arriving at a named location
+    pub fn on_location_reached(&mut self, name: &str) -> bool {
+        self.advance(|objective| match objective {
+            QuestObjective::ReachLocation { location, reached } if location == name && !*reached => {
+                *reached = true;
+                true
+            }
+            _ => false,
+        })
+    }
+
+    /// Record talking to an NPC
+    pub fn on_npc_talked(&mut self, npc_id: &str) -> bool {
+        self.advance(|objective| match objective {
+            QuestObjective::TalkToNpc { npc_id: target, talked } if target == npc_id && !*talked => {
+                *talked = true;
+                true
+            }
+            _ => false,
+        })
+    }
+
+    /// Raise a counter towards `required`, returns true if it moved
+    fn bump(current: &mut u32, required: u32, amount: u32) -> bool {
+        let next = current.saturating_add(amount).min(required);
+        let changed = next != *current;
+        *current = next;
+        changed
+    }
+
+    /// Update objectives of an active quest, completing it once all are done
+    fn advance(&mut self, mut update: impl FnMut(&mut QuestObjective) -> bool) -> bool {
+        if self.status != QuestStatus::Active {
+            return false;
+        }
+
+        let mut changed = false;
+        for objective in &mut self.objectives {
+            changed |= update(objective);
+        }
+        if changed && self.is_complete() {
+            self.complete();
+        }
+        changed
+    }
+
+    /// Grant the rewards of a completed quest to the player, once
+    /// Exp goes through `Player::add_exp`, so level-ups behave like any other.
+    /// Rewards are all-or-nothing: if any fails (e.g. a `TakeItem` the player can't
+    /// pay) the player is left untouched and the rewards can be claimed again later.
+    /// Returns the signals raised by the rewards, in order
+    pub fn claim_rewards(
+        &mut self,
+        player: &mut super::Player,
+        item_db: &super::ItemDatabase,
+    ) -> Result<Vec<DialogueSignal>, String> {
+        if self.status != QuestStatus::Completed {
+            return Err(format!("Quest '{}' is not complete", self.name));
+        }
+        if self.rewards_claimed {
+            return Err(format!("Rewards for '{}' were already claimed", self.name));
+        }
+
+        // Apply to a copy so a failing reward rolls back the ones before it
+        let mut rewarded = player.clone();
+        let mut signals = Vec::new();
+        for reward in &self.rewards {
+            signals.extend(rewarded.apply_dialogue_action(reward, item_db)?);
+        }
+
+        *player = rewarded;
+        self.rewards_claimed = true;
+        Ok(signals)
+    }
 }
 
 /// Story database
@@ -304,6 +608,43 @@ impl StoryDatabase {
         self.quests.get_mut(id)
     }
 
+    /// Every quest, in no particular order
+    pub fn quests(&self) -> impl Iterator<Item = &Quest> {
+        self.quests.values()
+    }
+
+    /// Tell every active quest about a kill
+    /// Returns the ids of quests that completed because of it
+    pub fn notify_enemy_killed(&mut self, enemy_id: u32) -> Vec<String> {
+        self.notify(|quest| quest.on_enemy_killed(enemy_id))
+    }
+
+    /// Tell every active quest about picked up items
+    pub fn notify_item_collected(&mut self, item_id: super::ItemId, quantity: u32) -> Vec<String> {
+        self.notify(|quest| quest.on_item_collected(item_id, quantity))
+    }
+
+    /// Tell every active quest the player reached a location
+    pub fn notify_location_reached(&mut self, name: &str) -> Vec<String> {
+        self.notify(|quest| quest.on_location_reached(name))
+    }
+
+    /// Tell every active quest the player talked to an NPC
+    pub fn notify_npc_talked(&mut self, npc_id: &str) -> Vec<String> {
+        self.notify(|quest| quest.on_npc_talked(npc_id))
+    }
+
+    fn notify(&mut self, mut event: impl FnMut(&mut Quest) -> bool) -> Vec<String> {
+        let mut completed: Vec<String> = self
+            .quests
+            .values_mut()
+            .filter(|quest| quest.status == QuestStatus::Active)
+            .filter_map(|quest| (event(quest) && quest.status == QuestStatus::Completed).then(|| quest.id.clone()))
+            .collect();
+        completed.sort();
+        completed
+    }
+
     /// Create a database with starter content
     pub fn with_starter_content() -> Self {
         let mut db = Self::new();
@@ -394,3 +735,172 @@ impl Default for StoryDatabase {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpg::{GameRng, ItemDatabase, ItemId, ItemStack, Player, PlayerClass};
+
+    fn banter() -> DialogueTree {
+        let mut tree = DialogueTree::new("guards", "Guard Banter", DialogueId(0));
+        tree.add_node(
+            DialogueNode::new(0, Speaker::Npc("Guard".to_string()), "Quiet night.")
+                .with_auto_select()
+                .with_choice(DialogueChoice::new("Too quiet.").with_weight(3.0).with_next(DialogueId(1)))
+                .with_choice(DialogueChoice::new("Sure is.").with_weight(1.0).with_next(DialogueId(2)))
+                .with_choice(
+                    DialogueChoice::new("Heard about the dragon?")
+                        .with_weight(100.0)
+                        .with_condition(DialogueCondition::HasQuestFlag("dragon_seen".to_string()))
+                        .with_next(DialogueId(3)),
+                ),
+        );
+        for id in 1..=3 {
+            tree.add_node(DialogueNode::new(id, Speaker::Npc("Guard".to_string()), "..."));
+        }
+        tree
+    }
+
+    fn picks(seed: u64, tree: &DialogueTree, player: &mut Player) -> Vec<String> {
+        let db = ItemDatabase::with_starter_items();
+        let mut rng = GameRng::new(seed);
+        (0..200)
+            .map(|_| {
+                let mut session = DialogueSession::new(tree);
+                session.auto_advance(&mut rng, player, &db).unwrap().unwrap().text.clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_auto_advance_is_weighted_and_deterministic() {
+        let tree = banter();
+        let mut player = Player::new("Tester", PlayerClass::Balanced);
+
+        let first = picks(7, &tree, &mut player);
+        assert_eq!(first, picks(7, &tree, &mut player));
+
+        // The flag-gated choice never shows up, the rest roughly follow 3:1
+        assert!(first.iter().all(|text| text != "Heard about the dragon?"));
+        let too_quiet = first.iter().filter(|text| *text == "Too quiet.").count();
+        assert!((120..=180).contains(&too_quiet), "got {}", too_quiet);
+
+        player.set_quest_flag("dragon_seen", true);
+        let flagged = picks(7, &tree, &mut player);
+        let dragon = flagged.iter().filter(|text| *text == "Heard about the dragon?").count();
+        assert!(dragon > 180, "got {}", dragon);
+    }
+
+    #[test]
+    fn test_auto_advance_moves_to_next_node_and_ignores_manual_nodes() {
+        let tree = banter();
+        let db = ItemDatabase::with_starter_items();
+        let mut player = Player::new("Tester", PlayerClass::Balanced);
+        let mut rng = GameRng::new(1);
+
+        let mut session = DialogueSession::new(&tree);
+        let choice = session.auto_advance(&mut rng, &mut player, &db).unwrap().unwrap();
+        assert_eq!(session.current_node().map(|node| node.id), choice.next_node);
+
+        // Follow-up nodes aren't auto-select, so the session waits
+        assert!(session.auto_advance(&mut rng, &mut player, &db).unwrap().is_none());
+        assert!(!session.is_finished());
+    }
+
+    #[test]
+    fn test_killing_rats_completes_rat_problem() {
+        let mut story = StoryDatabase::with_starter_content();
+        let rat = 1;
+
+        // Kills before the quest is accepted don't count
+        assert!(story.notify_enemy_killed(rat).is_empty());
+        story.get_quest_mut("rat_problem").unwrap().start();
+
+        story.notify_enemy_killed(2);
+        for _ in 0..4 {
+            assert!(story.notify_enemy_killed(rat).is_empty());
+        }
+        let quest = story.get_quest("rat_problem").unwrap();
+        assert_eq!(quest.status, QuestStatus::Active);
+        assert_eq!(quest.objectives[0].description(), "Defeat enemies: 4/5");
+
+        assert_eq!(story.notify_enemy_killed(rat), vec!["rat_problem".to_string()]);
+        let quest = story.get_quest("rat_problem").unwrap();
+        assert_eq!(quest.status, QuestStatus::Completed);
+
+        // Extra kills don't overflow the counter
+        story.notify_enemy_killed(rat);
+        let quest = story.get_quest("rat_problem").unwrap();
+        assert_eq!(quest.objectives[0].description(), "Defeat enemies: 5/5");
+    }
+
+    #[test]
+    fn test_quest_completes_when_all_objectives_done() {
+        let mut quest = Quest::new("errand", "Errand", "")
+            .with_objective(QuestObjective::CollectItems { item_id: ItemId(100), current: 0, required: 3 })
+            .with_objective(QuestObjective::ReachLocation { location: "Mill".to_string(), reached: false })
+            .with_objective(QuestObjective::TalkToNpc { npc_id: "miller".to_string(), talked: false });
+        quest.start();
+
+        assert!(quest.on_item_collected(ItemId(100), 5));
+        assert!(quest.on_location_reached("Mill"));
+        assert!(!quest.on_location_reached("Mill"));
+        assert_eq!(quest.status, QuestStatus::Active);
+        assert!(quest.on_npc_talked("miller"));
+        assert_eq!(quest.status, QuestStatus::Completed);
+    }
+
+    #[test]
+    fn test_runner_buys_potion_and_loops_back() {
+        let story = StoryDatabase::with_starter_content();
+        let tree = story.get_dialogue("merchant_1").unwrap();
+        let db = ItemDatabase::with_starter_items();
+        let mut player = Player::new("Tester", PlayerClass::Balanced);
+        player.inventory.add_gold(25);
+
+        let mut runner = DialogueRunner::new(tree);
+        let texts: Vec<&str> = runner.available_choices(&player, &db).iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, vec!["What do you have for sale?", "I need a health potion.", "Goodbye."]);
+
+        let DialogueState::Continue(node) = runner.choose(1, &mut player, &db).unwrap() else {
+            panic!("dialogue ended early");
+        };
+        assert_eq!(node.id, DialogueId(1002));
+        assert_eq!(player.inventory.gold(), 15);
+        assert_eq!(player.inventory.count_item(ItemId(100)), 1);
+
+        // "Here you go!" has no choices and loops back to the greeting
+        assert!(runner.available_choices(&player, &db).is_empty());
+        assert!(matches!(runner.advance(), DialogueState::Continue(node) if node.id == DialogueId(1000)));
+
+        // Buy again, then leave
+        runner.choose(1, &mut player, &db).unwrap();
+        runner.advance();
+        assert_eq!(player.inventory.gold(), 5);
+
+        // Too poor for a third potion: the choice is hidden
+        assert_eq!(runner.available_choices(&player, &db).len(), 2);
+        assert!(runner.choose(2, &mut player, &db).is_err());
+        assert!(matches!(runner.choose(1, &mut player, &db).unwrap(), DialogueState::Ended));
+        assert!(runner.is_finished());
+        assert!(runner.take_signals().is_empty());
+    }
+
+    #[test]
+    fn test_buying_with_a_full_bag_keeps_the_gold() {
+        let story = StoryDatabase::with_starter_content();
+        let tree = story.get_dialogue("merchant_1").unwrap();
+        let db = ItemDatabase::with_starter_items();
+        let mut player = Player::new("Tester", PlayerClass::Balanced);
+        player.inventory.add_gold(25);
+        for slot in &mut player.inventory.items {
+            *slot = Some(ItemStack::single(ItemId(2)));
+        }
+
+        let mut runner = DialogueRunner::new(tree);
+        assert_eq!(runner.choose(1, &mut player, &db).unwrap_err(), "Inventory is full");
+        assert_eq!(player.inventory.gold(), 25);
+        assert_eq!(player.inventory.count_item(ItemId(100)), 0);
+        assert!(matches!(runner.state(), DialogueState::Continue(node) if node.id == DialogueId(1000)));
+    }
+}
@@ -3,6 +3,8 @@
 
 use raylib::prelude::*;
 use super::*;
+use crate::ecs::World;
+use crate::terrain::Terrain;
 
 /// UI panel positions
 #[derive(Debug, Clone, Copy)]
@@ -14,11 +16,100 @@ pub enum UIPanelPosition {
     Center,
 }
 
+/// Colors and thresholds used by the UI
+#[derive(Debug, Clone, Copy)]
+pub struct UiTheme {
+    /// Health above this fraction uses `health_high_color`
+    pub health_high_threshold: f32,
+    /// Health above this fraction (up to the high threshold) uses `health_mid_color`
+    pub health_low_threshold: f32,
+    pub health_high_color: Color,
+    pub health_mid_color: Color,
+    pub health_low_color: Color,
+}
+
+impl Default for UiTheme {
+    fn default() -> Self {
+        Self {
+            health_high_threshold: 0.6,
+            health_low_threshold: 0.3,
+            health_high_color: Color::GREEN,
+            health_mid_color: Color::YELLOW,
+            health_low_color: Color::RED,
+        }
+    }
+}
+
+/// Screen rectangle a resource bar is drawn into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BarRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl BarRect {
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self { x, y, width, height }
+    }
+}
+
+/// Square screen area the minimap is drawn into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapView {
+    pub x: i32,
+    pub y: i32,
+    /// Side length in pixels
+    pub size: i32,
+}
+
+impl MapView {
+    pub fn new(x: i32, y: i32, size: i32) -> Self {
+        Self { x, y, size }
+    }
+}
+
+/// Inventory panel position and size, shared by drawing and mouse hit tests
+const INVENTORY_PANEL: (i32, i32, i32, i32) = (400, 100, 500, 500);
+const INVENTORY_SLOT_SIZE: i32 = 60;
+const INVENTORY_SLOT_GAP: i32 = 10;
+const INVENTORY_SLOTS_PER_ROW: usize = 6;
+
+/// Side of one terrain shading cell on the minimap, in pixels
+const MINIMAP_CELL_PIXELS: i32 = 8;
+
+/// Tooltip text sizes and padding
+const TOOLTIP_TITLE_SIZE: i32 = 18;
+const TOOLTIP_LINE_SIZE: i32 = 14;
+const TOOLTIP_PADDING: i32 = 8;
+/// Gap between the cursor and the tooltip
+const TOOLTIP_CURSOR_OFFSET: i32 = 16;
+
+/// What a click or drag in the inventory panel asks the game to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InventoryAction {
+    /// Equip the item in this slot
+    Equip(usize),
+    /// Use the consumable in this slot
+    Use(usize),
+    /// Swap the contents of two slots (`Inventory::swap_slots`)
+    Move { from: usize, to: usize },
+}
+
 /// RPG UI Renderer
 pub struct RpgUI {
     pub show_inventory: bool,
     pub show_character_sheet: bool,
     pub show_status_effects: bool,
+    pub show_map: bool,
+    pub theme: UiTheme,
+    /// Font for all UI text; raylib's default font when None
+    pub font: Option<Font>,
+    /// Minimap zoom in pixels per world unit
+    pub minimap_scale: f32,
+    /// Inventory slot being dragged
+    inventory_drag: Option<usize>,
 }
 
 impl Default for RpgUI {
@@ -27,6 +118,11 @@ impl Default for RpgUI {
             show_inventory: false,
             show_character_sheet: false,
             show_status_effects: true,
+            show_map: true,
+            theme: UiTheme::default(),
+            font: None,
+            minimap_scale: 2.0,
+            inventory_drag: None,
         }
     }
 }
@@ -36,17 +132,52 @@ impl RpgUI {
         Self::default()
     }
 
+    /// Load a TTF/OTF (or any raylib-supported) font for all UI text
+    pub fn load_font(&mut self, rl: &mut RaylibHandle, thread: &RaylibThread, path: &str) -> Result<(), String> {
+        let font = rl.load_font(thread, path).map_err(|e| format!("Failed to load font {}: {}", path, e))?;
+        self.font = Some(font);
+        Ok(())
+    }
+
+    /// Draw text with the loaded font, or raylib's default font
+    fn draw_text(&self, d: &mut RaylibDrawHandle, text: &str, x: i32, y: i32, font_size: i32, color: Color) {
+        match &self.font {
+            Some(font) => {
+                // Same letter spacing raylib's draw_text uses for its default font
+                let spacing = font_size as f32 / 10.0;
+                d.draw_text_ex(font, text, Vector2::new(x as f32, y as f32), font_size as f32, spacing, color);
+            }
+            None => d.draw_text(text, x, y, font_size, color),
+        }
+    }
+
+    /// Width of text in the loaded font, or raylib's default font
+    fn measure_text(&self, d: &RaylibDrawHandle, text: &str, font_size: i32) -> i32 {
+        match &self.font {
+            Some(font) => measure_text_ex(font, text, font_size as f32, font_size as f32 / 10.0).x.ceil() as i32,
+            None => d.measure_text(text, font_size),
+        }
+    }
+
+    /// Whether any panel that needs the mouse is open
+    pub fn any_panel_open(&self) -> bool {
+        self.show_inventory || self.show_character_sheet
+    }
+
+    /// Fill color for a health fraction (0.0 to 1.0)
+    pub fn health_color(percent: f32, theme: &UiTheme) -> Color {
+        if percent > theme.health_high_threshold {
+            theme.health_high_color
+        } else if percent > theme.health_low_threshold {
+            theme.health_mid_color
+        } else {
+            theme.health_low_color
+        }
+    }
+
     /// Draw health bar
-    pub fn draw_health_bar(
-        d: &mut RaylibDrawHandle,
-        x: i32,
-        y: i32,
-        width: i32,
-        height: i32,
-        current: i32,
-        max: i32,
-        label: &str,
-    ) {
+    pub fn render_health_bar(&self, d: &mut RaylibDrawHandle, bar: BarRect, current: i32, max: i32, label: &str) {
+        let BarRect { x, y, width, height } = bar;
         let percent = (current as f32 / max as f32).clamp(0.0, 1.0);
         let filled_width = (width as f32 * percent) as i32;
 
@@ -54,13 +185,7 @@ impl RpgUI {
         d.draw_rectangle(x, y, width, height, Color::DARKGRAY);
 
         // Health fill
-        let health_color = if percent > 0.6 {
-            Color::GREEN
-        } else if percent > 0.3 {
-            Color::YELLOW
-        } else {
-            Color::RED
-        };
+        let health_color = Self::health_color(percent, &self.theme);
         d.draw_rectangle(x, y, filled_width, height, health_color);
 
         // Border
@@ -68,19 +193,12 @@ impl RpgUI {
 
         // Text
         let text = format!("{}: {}/{}", label, current, max);
-        d.draw_text(&text, x + 5, y + (height / 2 - 8), 16, Color::WHITE);
+        self.draw_text(d, &text, x + 5, y + (height / 2 - 8), 16, Color::WHITE);
     }
 
     /// Draw mana bar
-    pub fn draw_mana_bar(
-        d: &mut RaylibDrawHandle,
-        x: i32,
-        y: i32,
-        width: i32,
-        height: i32,
-        current: i32,
-        max: i32,
-    ) {
+    pub fn render_mana_bar(&self, d: &mut RaylibDrawHandle, bar: BarRect, current: i32, max: i32) {
+        let BarRect { x, y, width, height } = bar;
         let percent = (current as f32 / max as f32).clamp(0.0, 1.0);
         let filled_width = (width as f32 * percent) as i32;
 
@@ -95,20 +213,12 @@ impl RpgUI {
 
         // Text
         let text = format!("Mana: {}/{}", current, max);
-        d.draw_text(&text, x + 5, y + (height / 2 - 8), 16, Color::WHITE);
+        self.draw_text(d, &text, x + 5, y + (height / 2 - 8), 16, Color::WHITE);
     }
 
     /// Draw experience bar
-    pub fn draw_exp_bar(
-        d: &mut RaylibDrawHandle,
-        x: i32,
-        y: i32,
-        width: i32,
-        height: i32,
-        current: i32,
-        required: i32,
-        level: i32,
-    ) {
+    pub fn render_exp_bar(&self, d: &mut RaylibDrawHandle, bar: BarRect, current: i32, required: i32, level: i32) {
+        let BarRect { x, y, width, height } = bar;
         let percent = (current as f32 / required as f32).clamp(0.0, 1.0);
         let filled_width = (width as f32 * percent) as i32;
 
@@ -123,11 +233,11 @@ impl RpgUI {
 
         // Text
         let text = format!("Level {} - EXP: {}/{}", level, current, required);
-        d.draw_text(&text, x + 5, y + (height / 2 - 8), 16, Color::BLACK);
+        self.draw_text(d, &text, x + 5, y + (height / 2 - 8), 16, Color::BLACK);
     }
 
     /// Draw player HUD (health, mana, exp)
-    pub fn draw_player_hud(d: &mut RaylibDrawHandle, player: &Player) {
+    pub fn render_player_hud(&self, d: &mut RaylibDrawHandle, player: &Player) {
         let margin = 10;
         let bar_width = 300;
         let bar_height = 30;
@@ -136,7 +246,8 @@ impl RpgUI {
         let mut y = margin;
 
         // Player name and level
-        d.draw_text(
+        self.draw_text(
+            d,
             &format!("{} - Level {}", player.name, player.level.current_level),
             margin,
             y,
@@ -146,12 +257,9 @@ impl RpgUI {
         y += 30;
 
         // Health bar
-        Self::draw_health_bar(
+        self.render_health_bar(
             d,
-            margin,
-            y,
-            bar_width,
-            bar_height,
+            BarRect::new(margin, y, bar_width, bar_height),
             player.stats.current_health,
             player.stats.max_health,
             "HP",
@@ -159,24 +267,18 @@ impl RpgUI {
         y += bar_height + spacing;
 
         // Mana bar
-        Self::draw_mana_bar(
+        self.render_mana_bar(
             d,
-            margin,
-            y,
-            bar_width,
-            bar_height,
+            BarRect::new(margin, y, bar_width, bar_height),
             player.stats.current_mana,
             player.stats.max_mana,
         );
         y += bar_height + spacing;
 
         // Experience bar
-        Self::draw_exp_bar(
+        self.render_exp_bar(
             d,
-            margin,
-            y,
-            bar_width,
-            bar_height,
+            BarRect::new(margin, y, bar_width, bar_height),
             player.level.current_exp,
             player.level.exp_to_next_level,
             player.level.current_level,
@@ -184,12 +286,12 @@ impl RpgUI {
     }
 
     /// Draw status effects
-    pub fn draw_status_effects(d: &mut RaylibDrawHandle, player: &Player, x: i32, mut y: i32) {
+    pub fn render_status_effects(&self, d: &mut RaylibDrawHandle, player: &Player, x: i32, mut y: i32) {
         if player.status_effects.is_empty() {
             return;
         }
 
-        d.draw_text("Status Effects:", x, y, 18, Color::WHITE);
+        self.draw_text(d, "Status Effects:", x, y, 18, Color::WHITE);
         y += 22;
 
         for effect in &player.status_effects {
@@ -207,46 +309,97 @@ impl RpgUI {
             };
 
             let text = format!("[{}] {:.1}s", icon, effect.duration);
-            d.draw_text(&text, x, y, 16, color);
+            self.draw_text(d, &text, x, y, 16, color);
             y += 20;
         }
     }
 
-    /// Draw inventory panel
-    pub fn draw_inventory(d: &mut RaylibDrawHandle, inventory: &Inventory, db: &ItemDatabase) {
-        let panel_x = 400;
-        let panel_y = 100;
-        let panel_width = 500;
-        let panel_height = 500;
+    /// Screen rectangle of an inventory slot
+    pub fn inventory_slot_rect(index: usize) -> Rectangle {
+        let (panel_x, panel_y, _, _) = INVENTORY_PANEL;
+        let row = (index / INVENTORY_SLOTS_PER_ROW) as i32;
+        let col = (index % INVENTORY_SLOTS_PER_ROW) as i32;
+        Rectangle::new(
+            (panel_x + 20 + col * (INVENTORY_SLOT_SIZE + INVENTORY_SLOT_GAP)) as f32,
+            (panel_y + 50 + row * (INVENTORY_SLOT_SIZE + INVENTORY_SLOT_GAP)) as f32,
+            INVENTORY_SLOT_SIZE as f32,
+            INVENTORY_SLOT_SIZE as f32,
+        )
+    }
+
+    /// Inventory slot under the mouse, out of the first `slot_count`
+    pub fn inventory_slot_at(mouse: Vector2, slot_count: usize) -> Option<usize> {
+        (0..slot_count).find(|&index| {
+            let slot = Self::inventory_slot_rect(index);
+            mouse.x >= slot.x && mouse.x < slot.x + slot.width && mouse.y >= slot.y && mouse.y < slot.y + slot.height
+        })
+    }
+
+    /// Turn mouse input over the inventory panel into an action
+    ///
+    /// Pressing on a filled slot picks it up. Releasing over another slot moves it
+    /// there; releasing over the same slot is a click, which equips equippable
+    /// items and uses consumables. Releasing outside the grid cancels the drag.
+    pub fn handle_inventory_input(
+        &mut self,
+        inventory: &Inventory,
+        db: &ItemDatabase,
+        mouse: Vector2,
+        pressed: bool,
+        released: bool,
+    ) -> Option<InventoryAction> {
+        let hovered = Self::inventory_slot_at(mouse, inventory.items.len());
+        if pressed {
+            self.inventory_drag = hovered.filter(|&slot| inventory.get_item(slot).is_some());
+        }
+        if !released {
+            return None;
+        }
+
+        let from = self.inventory_drag.take()?;
+        let to = hovered?;
+        if to != from {
+            return Some(InventoryAction::Move { from, to });
+        }
+
+        let item = db.get(inventory.get_item(from)?.item_id)?;
+        if item.equip_slot.is_some() {
+            Some(InventoryAction::Equip(from))
+        } else if item.item_type == ItemType::Consumable {
+            Some(InventoryAction::Use(from))
+        } else {
+            None
+        }
+    }
+
+    /// Draw inventory panel, with a tooltip for the item under the mouse
+    pub fn render_inventory(&self, d: &mut RaylibDrawHandle, inventory: &Inventory, db: &ItemDatabase, mouse: Vector2) {
+        let (panel_x, panel_y, panel_width, panel_height) = INVENTORY_PANEL;
 
         // Panel background
         d.draw_rectangle(panel_x, panel_y, panel_width, panel_height, Color::new(40, 40, 40, 240));
         d.draw_rectangle_lines(panel_x, panel_y, panel_width, panel_height, Color::WHITE);
 
         // Title
-        d.draw_text("Inventory", panel_x + 10, panel_y + 10, 24, Color::WHITE);
+        self.draw_text(d, "Inventory", panel_x + 10, panel_y + 10, 24, Color::WHITE);
 
         // Gold display
-        let gold_text = format!("Gold: {}", inventory.gold);
-        d.draw_text(&gold_text, panel_x + 350, panel_y + 15, 18, Color::GOLD);
+        let gold_text = format!("Gold: {}", inventory.gold());
+        self.draw_text(d, &gold_text, panel_x + 350, panel_y + 15, 18, Color::GOLD);
 
         // Draw items grid
-        let item_size = 60;
-        let items_per_row = 6;
-        let start_x = panel_x + 20;
-        let start_y = panel_y + 50;
+        let item_size = INVENTORY_SLOT_SIZE;
 
         for (idx, maybe_stack) in inventory.items.iter().enumerate() {
-            let row = (idx / items_per_row) as i32;
-            let col = (idx % items_per_row) as i32;
-            let x = start_x + col * (item_size + 10);
-            let y = start_y + row * (item_size + 10);
+            let slot = Self::inventory_slot_rect(idx);
+            let (x, y) = (slot.x as i32, slot.y as i32);
 
             if let Some(stack) = maybe_stack {
                 if let Some(item) = db.get(stack.item_id) {
-                    // Item slot background
+                    // Item slot background, outlined in yellow while being dragged
                     d.draw_rectangle(x, y, item_size, item_size, Color::new(60, 60, 60, 255));
-                    d.draw_rectangle_lines(x, y, item_size, item_size, item.rarity.color());
+                    let outline = if self.inventory_drag == Some(idx) { Color::YELLOW } else { item.rarity.color() };
+                    d.draw_rectangle_lines(x, y, item_size, item_size, outline);
 
                     // Item name (abbreviated)
                     let name_short = if item.name.len() > 8 {
@@ -254,12 +407,12 @@ impl RpgUI {
                     } else {
                         item.name.clone()
                     };
-                    d.draw_text(&name_short, x + 5, y + 5, 12, Color::WHITE);
+                    self.draw_text(d, &name_short, x + 5, y + 5, 12, Color::WHITE);
 
                     // Stack size if > 1
                     if stack.quantity > 1 {
                         let stack_text = format!("x{}", stack.quantity);
-                        d.draw_text(&stack_text, x + item_size - 25, y + item_size - 18, 14, Color::YELLOW);
+                        self.draw_text(d, &stack_text, x + item_size - 25, y + item_size - 18, 14, Color::YELLOW);
                     }
 
                     // Item type icon
@@ -271,7 +424,7 @@ impl RpgUI {
                         ItemType::QuestItem => "Q",
                         ItemType::Material => "M",
                     };
-                    d.draw_text(type_icon, x + 5, y + item_size - 18, 14, Color::LIGHTGRAY);
+                    self.draw_text(d, type_icon, x + 5, y + item_size - 18, 14, Color::LIGHTGRAY);
                 }
             } else {
                 // Empty slot
@@ -281,11 +434,113 @@ impl RpgUI {
         }
 
         // Instructions
-        d.draw_text("Press I to close", panel_x + 10, panel_y + panel_height - 30, 16, Color::LIGHTGRAY);
+        self.draw_text(d, "Press I to close", panel_x + 10, panel_y + panel_height - 30, 16, Color::LIGHTGRAY);
+
+        // Tooltip last so it sits on top of the grid
+        let hovered = Self::inventory_slot_at(mouse, inventory.items.len())
+            .and_then(|idx| inventory.items[idx].as_ref())
+            .and_then(|stack| db.get(stack.item_id));
+        if let Some(item) = hovered {
+            self.render_item_tooltip(d, item, mouse.x as i32, mouse.y as i32);
+        }
+    }
+
+    /// Lines of an item tooltip with their colors: name (in rarity color), type,
+    /// stat modifiers, required level, sell value and description
+    pub fn item_tooltip_lines(item: &ItemDef) -> Vec<(String, Color)> {
+        let mut lines = vec![(item.name.clone(), item.rarity.color())];
+        let kind = match item.equip_slot {
+            Some(slot) => format!("{:?} {:?} ({:?})", item.rarity, item.item_type, slot),
+            None => format!("{:?} {:?}", item.rarity, item.item_type),
+        };
+        lines.push((kind, Color::LIGHTGRAY));
+
+        for line in Self::stat_mod_lines(&item.stat_mods) {
+            lines.push((line, Color::GREEN));
+        }
+        if item.required_level > 1 {
+            lines.push((format!("Requires level {}", item.required_level), Color::LIGHTGRAY));
+        }
+        lines.push((format!("Sells for {} gold", item.sell_value), Color::GOLD));
+        if !item.description.is_empty() {
+            lines.push((item.description.clone(), Color::GRAY));
+        }
+        lines
+    }
+
+    /// One line per non-zero modifier, e.g. "+15 Physical Damage" or "+50% Max Health"
+    fn stat_mod_lines(mods: &StatModifiers) -> Vec<String> {
+        let flat = [
+            ("Strength", mods.strength),
+            ("Dexterity", mods.dexterity),
+            ("Intelligence", mods.intelligence),
+            ("Vitality", mods.vitality),
+            ("Luck", mods.luck),
+            ("Max Health", mods.max_health),
+            ("Max Mana", mods.max_mana),
+            ("Physical Damage", mods.physical_damage),
+            ("Magic Damage", mods.magic_damage),
+            ("Defense", mods.defense),
+            ("Move Speed", mods.move_speed.round() as i32),
+        ];
+        let percent = [
+            ("Dodge Chance", mods.dodge_chance),
+            ("Crit Chance", mods.crit_chance),
+            ("Max Health", mods.percent.max_health),
+            ("Max Mana", mods.percent.max_mana),
+            ("Physical Damage", mods.percent.physical_damage),
+            ("Magic Damage", mods.percent.magic_damage),
+            ("Defense", mods.percent.defense),
+            ("Move Speed", mods.percent.move_speed),
+        ];
+
+        let flat = flat.into_iter().filter(|(_, value)| *value != 0).map(|(name, value)| format!("{:+} {}", value, name));
+        let percent = percent
+            .into_iter()
+            .filter(|(_, value)| *value != 0.0)
+            .map(|(name, value)| format!("{:+}% {}", (value * 100.0).round() as i32, name));
+        flat.chain(percent).collect()
+    }
+
+    /// Top-left corner for a `size` tooltip beside the cursor, flipped to the
+    /// other side of the cursor near the right/bottom edge and kept on screen
+    pub fn tooltip_position(cursor: (i32, i32), size: (i32, i32), screen: (i32, i32)) -> (i32, i32) {
+        let place = |cursor: i32, size: i32, screen: i32| {
+            let after = cursor + TOOLTIP_CURSOR_OFFSET;
+            let position = if after + size <= screen { after } else { cursor - TOOLTIP_CURSOR_OFFSET - size };
+            position.min(screen - size).max(0)
+        };
+        (place(cursor.0, size.0, screen.0), place(cursor.1, size.1, screen.1))
+    }
+
+    /// Draw an item's details in a panel next to the cursor at (`x`, `y`)
+    pub fn render_item_tooltip(&self, d: &mut RaylibDrawHandle, item_def: &ItemDef, x: i32, y: i32) {
+        let lines = Self::item_tooltip_lines(item_def);
+        let line_size = |i: usize| if i == 0 { TOOLTIP_TITLE_SIZE } else { TOOLTIP_LINE_SIZE };
+
+        let width = lines
+            .iter()
+            .enumerate()
+            .map(|(i, (text, _))| self.measure_text(d, text, line_size(i)))
+            .max()
+            .unwrap_or(0)
+            + TOOLTIP_PADDING * 2;
+        let height = (0..lines.len()).map(|i| line_size(i) + 4).sum::<i32>() + TOOLTIP_PADDING * 2;
+        let screen = (d.get_screen_width(), d.get_screen_height());
+        let (panel_x, panel_y) = Self::tooltip_position((x, y), (width, height), screen);
+
+        d.draw_rectangle(panel_x, panel_y, width, height, Color::new(20, 20, 20, 235));
+        d.draw_rectangle_lines(panel_x, panel_y, width, height, item_def.rarity.color());
+
+        let mut line_y = panel_y + TOOLTIP_PADDING;
+        for (i, (text, color)) in lines.iter().enumerate() {
+            self.draw_text(d, text, panel_x + TOOLTIP_PADDING, line_y, line_size(i), *color);
+            line_y += line_size(i) + 4;
+        }
     }
 
     /// Draw character sheet
-    pub fn draw_character_sheet(d: &mut RaylibDrawHandle, player: &Player) {
+    pub fn render_character_sheet(&self, d: &mut RaylibDrawHandle, player: &Player) {
         let panel_x = 300;
         let panel_y = 80;
         let panel_width = 700;
@@ -296,7 +551,8 @@ impl RpgUI {
         d.draw_rectangle_lines(panel_x, panel_y, panel_width, panel_height, Color::WHITE);
 
         // Title
-        d.draw_text(
+        self.draw_text(
+            d,
             &format!("{} - Character Sheet", player.name),
             panel_x + 20,
             panel_y + 20,
@@ -309,7 +565,7 @@ impl RpgUI {
         let right_col = panel_x + 380;
 
         // Core attributes
-        d.draw_text("Attributes", left_col, y, 20, Color::GOLD);
+        self.draw_text(d, "Attributes", left_col, y, 20, Color::GOLD);
         y += 25;
 
         let attrs = [
@@ -321,13 +577,13 @@ impl RpgUI {
         ];
 
         for (name, value) in attrs {
-            d.draw_text(&format!("{}: {}", name, value), left_col, y, 18, Color::WHITE);
+            self.draw_text(d, &format!("{}: {}", name, value), left_col, y, 18, Color::WHITE);
             y += 22;
         }
 
         // Combat stats
         y = panel_y + 60;
-        d.draw_text("Combat Stats", right_col, y, 20, Color::GOLD);
+        self.draw_text(d, "Combat Stats", right_col, y, 20, Color::GOLD);
         y += 25;
 
         let combat_stats = [
@@ -341,13 +597,13 @@ impl RpgUI {
         ];
 
         for stat in combat_stats {
-            d.draw_text(&stat, right_col, y, 18, Color::WHITE);
+            self.draw_text(d, &stat, right_col, y, 18, Color::WHITE);
             y += 22;
         }
 
         // Equipment section
         y = panel_y + 280;
-        d.draw_text("Equipment", left_col, y, 20, Color::GOLD);
+        self.draw_text(d, "Equipment", left_col, y, 20, Color::GOLD);
         y += 25;
 
         let slot_names = [
@@ -368,12 +624,13 @@ impl RpgUI {
             } else {
                 format!("{}: [Empty]", slot_name)
             };
-            d.draw_text(&item_text, left_col, y, 16, Color::LIGHTGRAY);
+            self.draw_text(d, &item_text, left_col, y, 16, Color::LIGHTGRAY);
             y += 20;
         }
 
         // Instructions
-        d.draw_text(
+        self.draw_text(
+            d,
             "Press C to close",
             panel_x + 20,
             panel_y + panel_height - 30,
@@ -383,11 +640,12 @@ impl RpgUI {
     }
 
     /// Draw quick stats overlay (minimal HUD)
-    pub fn draw_quick_stats(d: &mut RaylibDrawHandle, player: &Player, x: i32, y: i32) {
+    pub fn render_quick_stats(&self, d: &mut RaylibDrawHandle, player: &Player, x: i32, y: i32) {
         let mut current_y = y;
 
         // Compact stats display
-        d.draw_text(
+        self.draw_text(
+            d,
             &format!("STR:{} DEX:{} INT:{} VIT:{} LCK:{}",
                 player.stats.strength,
                 player.stats.dexterity,
@@ -402,7 +660,8 @@ impl RpgUI {
         );
         current_y += 20;
 
-        d.draw_text(
+        self.draw_text(
+            d,
             &format!("ATK:{} DEF:{} SPD:{:.0}",
                 player.stats.physical_damage + player.stats.magic_damage,
                 player.stats.defense,
@@ -414,4 +673,355 @@ impl RpgUI {
             Color::LIGHTGRAY,
         );
     }
+
+    /// Where a world position lands on a `size` pixel minimap centered on `center`
+    ///
+    /// Returns minimap-local pixels (+X right, +Z down). Points further than the
+    /// minimap radius are pulled in to its edge along the same direction.
+    pub fn world_to_minimap(point: Vector3, center: Vector3, scale: f32, size: i32) -> Vector2 {
+        let radius = size as f32 / 2.0;
+        let mut offset = Vector2::new(point.x - center.x, point.z - center.z) * scale;
+        if offset.length() > radius {
+            offset = offset.normalized() * radius;
+        }
+        Vector2::new(radius + offset.x, radius + offset.y)
+    }
+
+    /// Draw a top-down map around the player: terrain height in grayscale, the
+    /// player as a triangle pointing where the camera looks, enemies in red and
+    /// chests in gold
+    pub fn render_minimap(
+        &self,
+        d: &mut RaylibDrawHandle,
+        world: &World,
+        player_pos: Vector3,
+        terrain: &Terrain,
+        view: MapView,
+    ) {
+        let MapView { x, y, size } = view;
+        // Terrain, sampled at the center of each cell
+        let height_scale = terrain.config.height_scale.max(f32::EPSILON);
+        let cells = (size / MINIMAP_CELL_PIXELS).max(1);
+        for cx in 0..cells {
+            for cz in 0..cells {
+                let pixel_x = (cx * MINIMAP_CELL_PIXELS) as f32 + MINIMAP_CELL_PIXELS as f32 / 2.0;
+                let pixel_z = (cz * MINIMAP_CELL_PIXELS) as f32 + MINIMAP_CELL_PIXELS as f32 / 2.0;
+                let world_x = player_pos.x + (pixel_x - size as f32 / 2.0) / self.minimap_scale;
+                let world_z = player_pos.z + (pixel_z - size as f32 / 2.0) / self.minimap_scale;
+                let height = terrain.get_height_at(world_x, world_z);
+                let shade = ((height / height_scale + 1.0) / 2.0).clamp(0.0, 1.0);
+                let gray = (40.0 + shade * 200.0) as u8;
+                d.draw_rectangle(
+                    x + cx * MINIMAP_CELL_PIXELS,
+                    y + cz * MINIMAP_CELL_PIXELS,
+                    MINIMAP_CELL_PIXELS,
+                    MINIMAP_CELL_PIXELS,
+                    Color::new(gray, gray, gray, 230),
+                );
+            }
+        }
+
+        let origin = Vector2::new(x as f32, y as f32);
+        let mut player_yaw = 0.0;
+        for entity in world.entities().filter(|e| e.enabled) {
+            if entity.is_player {
+                if let Some(camera) = &entity.camera {
+                    player_yaw = camera.yaw;
+                }
+                continue;
+            }
+            let color = if entity.is_enemy {
+                Color::RED
+            } else if entity.container.is_some() {
+                Color::GOLD
+            } else {
+                continue;
+            };
+            if let Some(transform) = &entity.transform {
+                let dot = Self::world_to_minimap(transform.position, player_pos, self.minimap_scale, size);
+                d.draw_circle_v(origin + dot, 3.0, color);
+            }
+        }
+
+        // Player triangle: yaw 0 looks down +X, which is right on the map
+        let center = origin + Vector2::new(size as f32 / 2.0, size as f32 / 2.0);
+        d.draw_poly(center, 3, 6.0, player_yaw, Color::SKYBLUE);
+
+        d.draw_rectangle_lines(x, y, size, size, Color::BLACK);
+    }
+
+    /// Draw the hotbar: one numbered slot per hotkey, with a top-down cooldown
+    /// sweep; skills that can't be used yet (cooldown or not enough mana) are greyed out
+    pub fn render_skill_bar(
+        &self,
+        d: &mut RaylibDrawHandle,
+        player_skills: &PlayerSkills,
+        skill_db: &SkillDatabase,
+        current_mana: i32,
+        x: i32,
+        y: i32,
+    ) {
+        let slot_size = 48;
+        let gap = 6;
+
+        for n in 1..=PlayerSkills::HOTBAR_SLOTS {
+            let slot_x = x + (n as i32 - 1) * (slot_size + gap);
+            d.draw_rectangle(slot_x, y, slot_size, slot_size, Color::new(40, 40, 40, 220));
+
+            let skill = player_skills.slot_for_hotkey(n).and_then(|id| skill_db.get(id));
+            if let Some(skill) = skill {
+                let usable = player_skills.is_skill_ready(skill.id) && current_mana >= skill.mana_cost;
+                let color = if usable { Color::WHITE } else { Color::GRAY };
+                let label: String = skill.name.chars().take(4).collect();
+                self.draw_text(d, &label, slot_x + 4, y + 18, 14, color);
+                if current_mana < skill.mana_cost {
+                    self.draw_text(d, &skill.mana_cost.to_string(), slot_x + 4, y + slot_size - 14, 12, Color::SKYBLUE);
+                }
+
+                let fill = player_skills.cooldown_fraction(skill);
+                if fill > 0.0 {
+                    let height = (slot_size as f32 * fill).round() as i32;
+                    d.draw_rectangle(slot_x, y, slot_size, height, Color::new(0, 0, 0, 160));
+                    if let Some(instance) = player_skills.get_instance(skill.id).filter(|i| !i.is_ready()) {
+                        let seconds = format!("{:.1}", instance.current_cooldown);
+                        self.draw_text(d, &seconds, slot_x + slot_size - 26, y + slot_size - 14, 12, Color::YELLOW);
+                    }
+                }
+            }
+
+            d.draw_rectangle_lines(slot_x, y, slot_size, slot_size, Color::LIGHTGRAY);
+            self.draw_text(d, &n.to_string(), slot_x + 3, y + 2, 12, Color::LIGHTGRAY);
+        }
+    }
+
+    /// Draw the explored-area map (unexplored cells are dimmed)
+    pub fn render_fog_map(
+        &self,
+        d: &mut RaylibDrawHandle,
+        fog: &FogOfWar,
+        player_pos: Vector3,
+        x: i32,
+        y: i32,
+        size: i32,
+    ) {
+        let cell_w = size as f32 / fog.width as f32;
+        let cell_h = size as f32 / fog.depth as f32;
+
+        // Background (unexplored)
+        d.draw_rectangle(x, y, size, size, Color::new(20, 20, 25, 220));
+
+        // Explored cells
+        for cx in 0..fog.width {
+            for cz in 0..fog.depth {
+                if fog.is_explored(cx, cz) {
+                    d.draw_rectangle(
+                        x + (cx as f32 * cell_w) as i32,
+                        y + (cz as f32 * cell_h) as i32,
+                        cell_w.ceil() as i32,
+                        cell_h.ceil() as i32,
+                        Color::new(110, 140, 100, 220),
+                    );
+                }
+            }
+        }
+
+        // Player marker
+        let px = (player_pos.x - fog.origin.x) / fog.cell_size * cell_w;
+        let pz = (player_pos.z - fog.origin.y) / fog.cell_size * cell_h;
+        d.draw_circle(
+            x + (px as i32).clamp(0, size),
+            y + (pz as i32).clamp(0, size),
+            3.0,
+            Color::YELLOW,
+        );
+
+        // Border
+        d.draw_rectangle_lines(x, y, size, size, Color::BLACK);
+
+        let explored = format!("Explored: {:.0}%", fog.explored_fraction() * 100.0);
+        self.draw_text(d, &explored, x, y + size + 4, 14, Color::LIGHTGRAY);
+    }
+}
+
+/// Stateless entry points, drawing through a default `RpgUI` (default font)
+impl RpgUI {
+    pub fn draw_health_bar(d: &mut RaylibDrawHandle, bar: BarRect, current: i32, max: i32, label: &str, theme: &UiTheme) {
+        Self::themed(theme).render_health_bar(d, bar, current, max, label);
+    }
+
+    pub fn draw_mana_bar(d: &mut RaylibDrawHandle, bar: BarRect, current: i32, max: i32) {
+        Self::default().render_mana_bar(d, bar, current, max);
+    }
+
+    pub fn draw_exp_bar(d: &mut RaylibDrawHandle, bar: BarRect, current: i32, required: i32, level: i32) {
+        Self::default().render_exp_bar(d, bar, current, required, level);
+    }
+
+    pub fn draw_player_hud(d: &mut RaylibDrawHandle, player: &Player, theme: &UiTheme) {
+        Self::themed(theme).render_player_hud(d, player);
+    }
+
+    pub fn draw_status_effects(d: &mut RaylibDrawHandle, player: &Player, x: i32, y: i32) {
+        Self::default().render_status_effects(d, player, x, y);
+    }
+
+    pub fn draw_inventory(d: &mut RaylibDrawHandle, inventory: &Inventory, db: &ItemDatabase, mouse: Vector2) {
+        Self::default().render_inventory(d, inventory, db, mouse);
+    }
+
+    pub fn draw_item_tooltip(d: &mut RaylibDrawHandle, item_def: &ItemDef, x: i32, y: i32) {
+        Self::default().render_item_tooltip(d, item_def, x, y);
+    }
+
+    pub fn draw_character_sheet(d: &mut RaylibDrawHandle, player: &Player) {
+        Self::default().render_character_sheet(d, player);
+    }
+
+    pub fn draw_quick_stats(d: &mut RaylibDrawHandle, player: &Player, x: i32, y: i32) {
+        Self::default().render_quick_stats(d, player, x, y);
+    }
+
+    pub fn draw_skill_bar(
+        d: &mut RaylibDrawHandle,
+        player_skills: &PlayerSkills,
+        skill_db: &SkillDatabase,
+        current_mana: i32,
+        x: i32,
+        y: i32,
+    ) {
+        Self::default().render_skill_bar(d, player_skills, skill_db, current_mana, x, y);
+    }
+
+    pub fn draw_minimap(d: &mut RaylibDrawHandle, world: &World, player_pos: Vector3, terrain: &Terrain, view: MapView) {
+        Self::default().render_minimap(d, world, player_pos, terrain, view);
+    }
+
+    pub fn draw_fog_map(d: &mut RaylibDrawHandle, fog: &FogOfWar, player_pos: Vector3, x: i32, y: i32, size: i32) {
+        Self::default().render_fog_map(d, fog, player_pos, x, y, size);
+    }
+
+    fn themed(theme: &UiTheme) -> Self {
+        Self {
+            theme: *theme,
+            ..Self::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_color_thresholds() {
+        let theme = UiTheme::default();
+
+        assert_eq!(RpgUI::health_color(1.0, &theme), Color::GREEN);
+        assert_eq!(RpgUI::health_color(0.61, &theme), Color::GREEN);
+        assert_eq!(RpgUI::health_color(0.6, &theme), Color::YELLOW);
+        assert_eq!(RpgUI::health_color(0.31, &theme), Color::YELLOW);
+        assert_eq!(RpgUI::health_color(0.3, &theme), Color::RED);
+        assert_eq!(RpgUI::health_color(0.0, &theme), Color::RED);
+    }
+
+    #[test]
+    fn test_iron_sword_tooltip_lists_stats() {
+        let db = ItemDatabase::with_starter_items();
+        let sword = db.get(ItemId(2)).unwrap();
+        let lines: Vec<String> = RpgUI::item_tooltip_lines(sword).into_iter().map(|(text, _)| text).collect();
+
+        assert_eq!(lines[0], "Iron Sword");
+        assert_eq!(RpgUI::item_tooltip_lines(sword)[0].1, Rarity::Uncommon.color());
+        assert!(lines.contains(&"+15 Physical Damage".to_string()));
+        assert!(lines.contains(&"+2 Strength".to_string()));
+        assert!(lines.contains(&format!("Sells for {} gold", sword.sell_value)));
+
+        // Hovering the first slot finds it; the gap between slots finds nothing
+        assert_eq!(RpgUI::inventory_slot_at(Vector2::new(425.0, 155.0), 20), Some(0));
+        assert_eq!(RpgUI::inventory_slot_at(Vector2::new(500.0, 230.0), 20), Some(7));
+        let slot = RpgUI::inventory_slot_rect(0);
+        assert_eq!(RpgUI::inventory_slot_at(Vector2::new(slot.x + 5.0, slot.y + 5.0), 20), Some(0));
+        let second = RpgUI::inventory_slot_rect(1);
+        assert_eq!(RpgUI::inventory_slot_at(Vector2::new(second.x + 1.0, second.y + 1.0), 20), Some(1));
+        assert_eq!(RpgUI::inventory_slot_at(Vector2::new(slot.x + slot.width + 5.0, slot.y), 20), None);
+    }
+
+    #[test]
+    fn test_click_and_drag_inventory_actions() {
+        let db = ItemDatabase::with_starter_items();
+        let mut inventory = Inventory::new(8);
+        inventory.add_item(db.get(ItemId(2)).unwrap(), 1); // Iron Sword
+        inventory.add_item(db.get(ItemId(100)).unwrap(), 2); // Health Potion
+        let center = |slot: usize| {
+            let rect = RpgUI::inventory_slot_rect(slot);
+            Vector2::new(rect.x + rect.width / 2.0, rect.y + rect.height / 2.0)
+        };
+        let mut ui = RpgUI::new();
+
+        // Clicks
+        assert_eq!(ui.handle_inventory_input(&inventory, &db, center(0), true, false), None);
+        assert_eq!(ui.handle_inventory_input(&inventory, &db, center(0), false, true), Some(InventoryAction::Equip(0)));
+        ui.handle_inventory_input(&inventory, &db, center(1), true, false);
+        assert_eq!(ui.handle_inventory_input(&inventory, &db, center(1), false, true), Some(InventoryAction::Use(1)));
+
+        // Drag the sword onto the empty slot 5
+        ui.handle_inventory_input(&inventory, &db, center(0), true, false);
+        let action = ui.handle_inventory_input(&inventory, &db, center(5), false, true);
+        assert_eq!(action, Some(InventoryAction::Move { from: 0, to: 5 }));
+        if let Some(InventoryAction::Move { from, to }) = action {
+            inventory.swap_slots(from, to);
+        }
+        assert_eq!(inventory.items[0], None);
+        assert_eq!(inventory.items[5].as_ref().map(|s| s.item_id), Some(ItemId(2)));
+
+        // Empty slots can't be picked up, and dropping outside the grid cancels
+        ui.handle_inventory_input(&inventory, &db, center(0), true, false);
+        assert_eq!(ui.handle_inventory_input(&inventory, &db, center(1), false, true), None);
+        ui.handle_inventory_input(&inventory, &db, center(1), true, false);
+        assert_eq!(ui.handle_inventory_input(&inventory, &db, Vector2::zero(), false, true), None);
+    }
+
+    #[test]
+    fn test_world_to_minimap() {
+        let center = Vector3::new(10.0, 5.0, -20.0);
+        let at = |x: f32, z: f32| RpgUI::world_to_minimap(Vector3::new(x, 0.0, z), center, 2.0, 160);
+
+        assert_eq!(at(10.0, -20.0), Vector2::new(80.0, 80.0));
+        // +X is right and +Z is down, at 2 pixels per unit
+        assert_eq!(at(30.0, -20.0), Vector2::new(120.0, 80.0));
+        assert_eq!(at(10.0, -50.0), Vector2::new(80.0, 20.0));
+        assert_eq!(at(-5.0, -10.0), Vector2::new(50.0, 100.0));
+
+        // Out of range: clamped to the edge in the same direction
+        assert_eq!(at(1000.0, -20.0), Vector2::new(160.0, 80.0));
+        let far = at(10.0 + 300.0, -20.0 + 300.0);
+        assert!(((far - Vector2::new(80.0, 80.0)).length() - 80.0).abs() < 1e-3);
+        assert!((far.x - far.y).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_tooltip_stays_on_screen() {
+        let screen = (1280, 720);
+        assert_eq!(RpgUI::tooltip_position((100, 100), (200, 150), screen), (116, 116));
+        // Near the bottom right it flips to the other side of the cursor
+        assert_eq!(RpgUI::tooltip_position((1270, 710), (200, 150), screen), (1054, 544));
+        // Too big to fit either side: pinned to the edge
+        assert_eq!(RpgUI::tooltip_position((10, 10), (1300, 150), screen), (0, 26));
+    }
+
+    #[test]
+    fn test_health_color_custom_theme() {
+        let theme = UiTheme {
+            health_high_threshold: 0.8,
+            health_low_threshold: 0.5,
+            health_high_color: Color::BLUE,
+            health_mid_color: Color::PURPLE,
+            health_low_color: Color::BLACK,
+        };
+
+        assert_eq!(RpgUI::health_color(0.81, &theme), Color::BLUE);
+        assert_eq!(RpgUI::health_color(0.8, &theme), Color::PURPLE);
+        assert_eq!(RpgUI::health_color(0.51, &theme), Color::PURPLE);
+        assert_eq!(RpgUI::health_color(0.5, &theme), Color::BLACK);
+    }
 }
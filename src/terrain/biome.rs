@@ -0,0 +1,184 @@
+/// Terrain Biomes
+/// Classifies terrain into biomes and groups its triangles into per-biome material batches
+
+use raylib::prelude::Color;
+use super::generator::Terrain;
+
+/// Terrain biome, used to pick a material for each part of the mesh
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Biome {
+    Water,
+    Desert,
+    Grassland,
+    Forest,
+    Rock,
+    Tundra,
+}
+
+impl Biome {
+    pub const ALL: [Biome; 6] = [
+        Biome::Water,
+        Biome::Desert,
+        Biome::Grassland,
+        Biome::Forest,
+        Biome::Rock,
+        Biome::Tundra,
+    ];
+
+    /// Biome for a height and a moisture in [0, 1], matching the terrain vertex colors
+    ///
+    /// Water and the sandy shore are decided by height alone, as are the tundra and
+    /// rock peaks; in between moisture picks desert, grassland or forest.
+    pub fn from_height_and_moisture(height: f32, moisture: f32, height_scale: f32) -> Self {
+        let normalized = (height / height_scale + 1.0) / 2.0; // 0.0 to 1.0
+
+        if normalized < 0.4 {
+            Biome::Water
+        } else if normalized < 0.45 {
+            Biome::Desert
+        } else if normalized >= 0.85 {
+            Biome::Tundra
+        } else if normalized >= 0.75 {
+            if moisture > 0.6 { Biome::Tundra } else { Biome::Rock }
+        } else if moisture < 0.4 {
+            Biome::Desert
+        } else if moisture < 0.6 {
+            Biome::Grassland
+        } else {
+            Biome::Forest
+        }
+    }
+
+    /// Vertex color used for this biome
+    pub fn color(self) -> Color {
+        match self {
+            Biome::Water => Color::new(50, 100, 200, 255),
+            Biome::Desert => Color::new(220, 190, 120, 255),
+            Biome::Grassland => Color::new(50, 150, 50, 255),
+            Biome::Forest => Color::new(30, 95, 40, 255),
+            Biome::Rock => Color::new(120, 120, 120, 255),
+            Biome::Tundra => Color::new(240, 240, 250, 255),
+        }
+    }
+}
+
+/// Triangles of one biome, drawn together with that biome's material
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerrainMaterialGroup {
+    pub biome: Biome,
+    /// Three indices into `Terrain::vertices` per triangle
+    pub indices: Vec<u32>,
+}
+
+impl TerrainMaterialGroup {
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+}
+
+impl Terrain {
+    /// Split the terrain mesh into per-biome index buffers
+    ///
+    /// Each grid quad becomes two triangles, and each triangle goes to the biome of
+    /// its average vertex height and moisture. Empty groups are left out; groups are
+    /// ordered by biome.
+    pub fn build_material_groups(&self) -> Vec<TerrainMaterialGroup> {
+        let (width, depth) = (self.config.width, self.config.depth);
+        let mut groups: Vec<TerrainMaterialGroup> = Biome::ALL
+            .iter()
+            .map(|&biome| TerrainMaterialGroup { biome, indices: Vec::new() })
+            .collect();
+
+        if self.vertices.len() < width * depth {
+            return Vec::new();
+        }
+
+        for x in 0..width.saturating_sub(1) {
+            for z in 0..depth.saturating_sub(1) {
+                let idx00 = x * depth + z;
+                let idx10 = (x + 1) * depth + z;
+                let idx01 = x * depth + (z + 1);
+                let idx11 = (x + 1) * depth + (z + 1);
+
+                // Same winding as Terrain::render
+                for triangle in [[idx00, idx10, idx01], [idx10, idx11, idx01]] {
+                    let height = triangle.iter().map(|&i| self.vertices[i].y).sum::<f32>() / 3.0;
+                    let moisture = triangle.iter().map(|&i| self.moisture[i / depth][i % depth]).sum::<f32>() / 3.0;
+                    let biome = Biome::from_height_and_moisture(height, moisture, self.config.height_scale);
+                    let group = &mut groups[biome as usize];
+                    group.indices.extend(triangle.iter().map(|&i| i as u32));
+                }
+            }
+        }
+
+        groups.retain(|group| !group.indices.is_empty());
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terrain::TerrainConfig;
+
+    #[test]
+    fn test_material_groups_split_by_biome() {
+        let mut terrain = Terrain::generate(TerrainConfig {
+            width: 20,
+            depth: 10,
+            height_scale: 10.0,
+            ..Default::default()
+        });
+
+        // Left half is sand-height desert, right half grassland
+        let desert_height = -0.15 * terrain.config.height_scale;
+        let grass_height = 0.0;
+        for (x, column) in terrain.heightmap.iter_mut().enumerate() {
+            column.fill(if x < 10 { desert_height } else { grass_height });
+        }
+        for column in terrain.moisture.iter_mut() {
+            column.fill(0.5);
+        }
+        terrain.rebuild_vertices();
+
+        let groups = &terrain.material_groups;
+        let biomes: Vec<_> = groups.iter().map(|g| g.biome).collect();
+        assert_eq!(biomes, vec![Biome::Desert, Biome::Grassland]);
+
+        let desert = &groups[0];
+        for triangle in desert.indices.chunks(3) {
+            assert!(triangle.iter().all(|&i| terrain.vertices[i as usize].x < 0.0));
+        }
+
+        let total: usize = groups.iter().map(|g| g.triangle_count()).sum();
+        assert_eq!(total, (20 - 1) * (10 - 1) * 2);
+    }
+
+    #[test]
+    fn test_material_groups_agree_with_vertex_colors() {
+        let mut terrain = Terrain::generate(TerrainConfig { width: 16, depth: 16, ..Default::default() });
+
+        // Lowland that is dry in the first rows and wet in the rest, with a snowy ridge
+        let ridge_height = 0.6 * terrain.config.height_scale;
+        for (x, column) in terrain.heightmap.iter_mut().enumerate() {
+            column.fill(if x >= 12 { ridge_height } else { 0.0 });
+        }
+        for (x, column) in terrain.moisture.iter_mut().enumerate() {
+            column.fill(if x < 6 { 0.2 } else { 0.8 });
+        }
+        terrain.rebuild_vertices();
+
+        let biomes: Vec<_> = terrain.material_groups.iter().map(|g| g.biome).collect();
+        assert!(biomes.contains(&Biome::Desert) && biomes.contains(&Biome::Forest) && biomes.contains(&Biome::Tundra));
+
+        // Wherever a triangle's vertices share a color, its group has that color too
+        for group in &terrain.material_groups {
+            for triangle in group.indices.chunks(3) {
+                let colors: Vec<_> = triangle.iter().map(|&i| terrain.colors[i as usize]).collect();
+                if colors.iter().all(|&color| color == colors[0]) {
+                    assert_eq!(group.biome.color(), colors[0], "{:?} triangle {:?}", group.biome, triangle);
+                }
+            }
+        }
+    }
+}
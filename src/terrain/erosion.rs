@@ -0,0 +1,260 @@
+/// Hydraulic Erosion
+/// Droplet-based erosion pass that carves valleys and deposits sediment on a heightmap
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use super::generator::Terrain;
+
+/// Erosion simulation parameters
+#[derive(Debug, Clone)]
+pub struct ErosionParams {
+    pub seed: u64,               // Random seed for droplet spawn positions
+    pub inertia: f32,            // How much a droplet keeps its direction (0.0 - 1.0)
+    pub capacity: f32,           // Sediment carry capacity multiplier
+    pub min_capacity: f32,       // Capacity floor so flat ground still erodes a little
+    pub erosion_rate: f32,       // Fraction of free capacity taken from the ground per step
+    pub deposition_rate: f32,    // Fraction of excess sediment dropped per step
+    pub evaporation_rate: f32,   // Water lost per step
+    pub gravity: f32,            // Acceleration from height differences
+    pub max_lifetime: u32,       // Maximum steps per droplet
+}
+
+impl Default for ErosionParams {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            inertia: 0.05,
+            capacity: 4.0,
+            min_capacity: 0.01,
+            erosion_rate: 0.3,
+            deposition_rate: 0.3,
+            evaporation_rate: 0.01,
+            gravity: 4.0,
+            max_lifetime: 30,
+        }
+    }
+}
+
+impl Terrain {
+    /// Simulate water droplets eroding the heightmap
+    /// This is expensive and never runs as part of generate() - call it explicitly
+    pub fn apply_hydraulic_erosion(&mut self, droplets: u32, params: &ErosionParams) {
+        let width = self.config.width;
+        let depth = self.config.depth;
+        if width < 2 || depth < 2 {
+            return;
+        }
+
+        let mut rng = StdRng::seed_from_u64(params.seed);
+
+        for _ in 0..droplets {
+            let mut pos_x = rng.gen_range(0.0..(width - 1) as f32);
+            let mut pos_z = rng.gen_range(0.0..(depth - 1) as f32);
+            let mut dir_x = 0.0;
+            let mut dir_z = 0.0;
+            let mut speed = 1.0;
+            let mut water = 1.0;
+            let mut sediment = 0.0;
+            let mut left_map = false;
+
+            for _ in 0..params.max_lifetime {
+                let cell_x = pos_x as usize;
+                let cell_z = pos_z as usize;
+                let offset_x = pos_x - cell_x as f32;
+                let offset_z = pos_z - cell_z as f32;
+
+                let (height, grad_x, grad_z) = self.height_and_gradient(pos_x, pos_z);
+
+                // Blend previous direction with the downhill direction
+                dir_x = dir_x * params.inertia - grad_x * (1.0 - params.inertia);
+                dir_z = dir_z * params.inertia - grad_z * (1.0 - params.inertia);
+
+                let len = (dir_x * dir_x + dir_z * dir_z).sqrt();
+                if len < 1e-6 {
+                    break;
+                }
+                dir_x /= len;
+                dir_z /= len;
+
+                pos_x += dir_x;
+                pos_z += dir_z;
+
+                if pos_x < 0.0
+                    || pos_z < 0.0
+                    || pos_x >= (width - 1) as f32
+                    || pos_z >= (depth - 1) as f32
+                {
+                    // Sediment carried off the edge is lost
+                    left_map = true;
+                    break;
+                }
+
+                let (new_height, _, _) = self.height_and_gradient(pos_x, pos_z);
+                let delta_height = new_height - height;
+
+                let capacity = (-delta_height * speed * water * params.capacity)
+                    .max(params.min_capacity);
+
+                if sediment > capacity || delta_height > 0.0 {
+                    // Moving uphill fills the pit behind us, otherwise drop the excess
+                    let amount = if delta_height > 0.0 {
+                        delta_height.min(sediment)
+                    } else {
+                        (sediment - capacity) * params.deposition_rate
+                    };
+                    sediment -= amount;
+                    self.distribute(cell_x, cell_z, offset_x, offset_z, amount);
+                } else {
+                    // Never dig deeper than the height difference, or we create spikes
+                    let amount = ((capacity - sediment) * params.erosion_rate).min(-delta_height);
+                    sediment += amount;
+                    self.distribute(cell_x, cell_z, offset_x, offset_z, -amount);
+                }
+
+                speed = (speed * speed - delta_height * params.gravity).max(0.0).sqrt();
+                water *= 1.0 - params.evaporation_rate;
+            }
+
+            // A droplet that dries up or stalls drops whatever it still carries
+            if !left_map && sediment > 0.0 {
+                let (cell_x, cell_z) = (pos_x as usize, pos_z as usize);
+                self.distribute(cell_x, cell_z, pos_x - cell_x as f32, pos_z - cell_z as f32, sediment);
+            }
+        }
+
+        self.rebuild_vertices();
+    }
+
+    /// Bilinear height and gradient at a grid-space position
+    fn height_and_gradient(&self, grid_x: f32, grid_z: f32) -> (f32, f32, f32) {
+        let x = grid_x as usize;
+        let z = grid_z as usize;
+        let fx = grid_x - x as f32;
+        let fz = grid_z - z as f32;
+
+        let h00 = self.heightmap[x][z];
+        let h10 = self.heightmap[x + 1][z];
+        let h01 = self.heightmap[x][z + 1];
+        let h11 = self.heightmap[x + 1][z + 1];
+
+        let grad_x = (h10 - h00) * (1.0 - fz) + (h11 - h01) * fz;
+        let grad_z = (h01 - h00) * (1.0 - fx) + (h11 - h10) * fx;
+        let height = h00 * (1.0 - fx) * (1.0 - fz)
+            + h10 * fx * (1.0 - fz)
+            + h01 * (1.0 - fx) * fz
+            + h11 * fx * fz;
+
+        (height, grad_x, grad_z)
+    }
+
+    /// Add height to the four corners of a cell weighted by offset
+    fn distribute(&mut self, x: usize, z: usize, fx: f32, fz: f32, amount: f32) {
+        self.heightmap[x][z] += amount * (1.0 - fx) * (1.0 - fz);
+        self.heightmap[x + 1][z] += amount * fx * (1.0 - fz);
+        self.heightmap[x][z + 1] += amount * (1.0 - fx) * fz;
+        self.heightmap[x + 1][z + 1] += amount * fx * fz;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terrain::TerrainConfig;
+
+    fn test_terrain() -> Terrain {
+        Terrain::generate(TerrainConfig {
+            width: 64,
+            depth: 64,
+            noise_scale: 20.0,
+            seed: 11,
+            ..Default::default()
+        })
+    }
+
+    fn max_height(terrain: &Terrain) -> f32 {
+        terrain.heightmap.iter().flatten().cloned().fold(f32::MIN, f32::max)
+    }
+
+    fn variance(terrain: &Terrain) -> f32 {
+        let values: Vec<f32> = terrain.heightmap.iter().flatten().cloned().collect();
+        let mean = values.iter().sum::<f32>() / values.len() as f32;
+        values.iter().map(|h| (h - mean) * (h - mean)).sum::<f32>() / values.len() as f32
+    }
+
+    #[test]
+    fn test_erosion_lowers_peaks_and_flattens() {
+        let mut terrain = test_terrain();
+        let peak_before = max_height(&terrain);
+        let variance_before = variance(&terrain);
+
+        terrain.apply_hydraulic_erosion(5000, &ErosionParams::default());
+
+        assert!(max_height(&terrain) < peak_before);
+        // Material moves from high ground into valleys
+        assert!(variance(&terrain) < variance_before);
+        assert_eq!(terrain.vertices.len(), 64 * 64);
+    }
+
+    #[test]
+    fn test_erosion_deterministic() {
+        let params = ErosionParams { seed: 3, ..Default::default() };
+
+        let mut a = test_terrain();
+        let mut b = test_terrain();
+        a.apply_hydraulic_erosion(1000, &params);
+        b.apply_hydraulic_erosion(1000, &params);
+
+        assert_eq!(a.heightmap, b.heightmap);
+    }
+
+    /// Smooth cone peaking in the middle of a flat 64x64 grid
+    fn cone_terrain() -> Terrain {
+        let mut terrain = test_terrain();
+        for (x, column) in terrain.heightmap.iter_mut().enumerate() {
+            for (z, height) in column.iter_mut().enumerate() {
+                let distance = ((x as f32 - 32.0).powi(2) + (z as f32 - 32.0).powi(2)).sqrt();
+                *height = (20.0 - distance).max(0.0) * 0.5;
+            }
+        }
+        terrain.rebuild_vertices();
+        terrain
+    }
+
+    /// Mean distance of each interior height from the average of its 4 neighbours
+    fn roughness(terrain: &Terrain) -> f32 {
+        let h = &terrain.heightmap;
+        let mut total = 0.0;
+        let mut count = 0;
+        for x in 1..h.len() - 1 {
+            for z in 1..h[x].len() - 1 {
+                let neighbours = (h[x - 1][z] + h[x + 1][z] + h[x][z - 1] + h[x][z + 1]) / 4.0;
+                total += (h[x][z] - neighbours).abs();
+                count += 1;
+            }
+        }
+        total / count as f32
+    }
+
+    #[test]
+    fn test_erosion_on_cone_conserves_material() {
+        let mut terrain = cone_terrain();
+        let total_before: f32 = terrain.heightmap.iter().flatten().sum();
+        let roughness_before = roughness(&terrain);
+
+        terrain.apply_hydraulic_erosion(3000, &ErosionParams { seed: 5, ..Default::default() });
+
+        // Material is moved rather than created or destroyed
+        let total_after: f32 = terrain.heightmap.iter().flatten().sum();
+        let drift = (total_after - total_before).abs() / total_before;
+        assert!(drift < 0.05, "total height drifted by {:.1}%", drift * 100.0);
+
+        // Gullies and sediment fans change the smooth cone's surface
+        let roughness_after = roughness(&terrain);
+        assert!(
+            (roughness_after - roughness_before).abs() > roughness_before * 0.1,
+            "roughness {} -> {}",
+            roughness_before,
+            roughness_after
+        );
+    }
+}
@@ -2,10 +2,27 @@
 /// Generates large terrain meshes using Perlin noise
 
 use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
 use super::noise::*;
+use super::biome::{Biome, TerrainMaterialGroup};
+
+/// How noise octaves are combined into heights
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NoiseMode {
+    /// Plain fractal noise in [-1, 1]: rolling hills and valleys
+    #[default]
+    Fractal,
+    /// Ridged noise in [0, 1]: sharp alpine ridges
+    Ridged,
+    /// Billow noise in [0, 1]: rounded, puffy hills
+    Billow,
+}
 
 /// Terrain configuration
-#[derive(Debug, Clone)]
+///
+/// Stored in level files; fields left out take their default value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TerrainConfig {
     pub width: usize,           // Number of vertices in X direction
     pub depth: usize,           // Number of vertices in Z direction
@@ -16,6 +33,9 @@ pub struct TerrainConfig {
     pub lacunarity: f32,        // Frequency multiplier per octave
     pub noise_scale: f32,       // Overall noise frequency scale
     pub seed: u32,              // Random seed
+    pub noise_mode: NoiseMode,  // How octaves are combined
+    pub moisture_scale: f32,    // Noise frequency scale of the moisture map
+    pub moisture_seed: u32,     // Random seed of the moisture map
 }
 
 impl Default for TerrainConfig {
@@ -30,7 +50,32 @@ impl Default for TerrainConfig {
             lacunarity: 2.0,
             noise_scale: 50.0,
             seed: 42,
+            noise_mode: NoiseMode::Fractal,
+            moisture_scale: 80.0,
+            moisture_seed: 7,
+        }
+    }
+}
+
+impl TerrainConfig {
+    /// Check the settings can generate a terrain
+    /// The grid needs at least 2x2 vertices and every scale must be positive
+    pub fn validate(&self) -> Result<(), String> {
+        if self.width < 2 || self.depth < 2 {
+            return Err(format!("Terrain must be at least 2x2 vertices, got {}x{}", self.width, self.depth));
+        }
+        let scales = [
+            ("cell_size", self.cell_size),
+            ("height_scale", self.height_scale),
+            ("noise_scale", self.noise_scale),
+            ("moisture_scale", self.moisture_scale),
+        ];
+        for (name, value) in scales {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(format!("Terrain {} must be positive, got {}", name, value));
+            }
         }
+        Ok(())
     }
 }
 
@@ -38,8 +83,14 @@ impl Default for TerrainConfig {
 pub struct Terrain {
     pub config: TerrainConfig,
     pub heightmap: Vec<Vec<f32>>,
+    /// Moisture per heightmap cell in [0, 1], picks the biome along with height
+    pub moisture: Vec<Vec<f32>>,
     pub vertices: Vec<Vector3>,
     pub colors: Vec<Color>,
+    /// Triangles grouped by biome, rebuilt with the vertices
+    pub material_groups: Vec<TerrainMaterialGroup>,
+    /// GPU model built by `upload`, drawn instead of per-triangle calls
+    model: Option<ffi::Model>,
 }
 
 impl Terrain {
@@ -54,47 +105,67 @@ impl Terrain {
                 let nx = x as f32 / config.noise_scale;
                 let nz = z as f32 / config.noise_scale;
 
-                let height = noise.fractal_noise2d(
-                    nx,
-                    nz,
-                    config.octaves,
-                    config.persistence,
-                    config.lacunarity,
-                );
+                let (octaves, persistence, lacunarity) = (config.octaves, config.persistence, config.lacunarity);
+                let height = match config.noise_mode {
+                    NoiseMode::Fractal => noise.fractal_noise2d(nx, nz, octaves, persistence, lacunarity),
+                    NoiseMode::Ridged => noise.ridged_noise2d(nx, nz, octaves, persistence, lacunarity),
+                    NoiseMode::Billow => noise.billow_noise2d(nx, nz, octaves, persistence, lacunarity),
+                };
 
                 // Normalize and scale
                 heightmap[x][z] = height * config.height_scale;
             }
         }
 
-        // Generate vertices and colors
-        let mut vertices = Vec::new();
-        let mut colors = Vec::new();
-
-        for x in 0..config.width {
-            for z in 0..config.depth {
-                let world_x = (x as f32 - config.width as f32 / 2.0) * config.cell_size;
-                let world_z = (z as f32 - config.depth as f32 / 2.0) * config.cell_size;
-                let world_y = heightmap[x][z];
-
-                vertices.push(Vector3::new(world_x, world_y, world_z));
-
-                // Color based on height
-                let color = Self::height_to_color(world_y, config.height_scale);
-                colors.push(color);
+        // Generate moisture map from its own noise field
+        let moisture_noise = PerlinNoise::new(config.moisture_seed);
+        let mut moisture = vec![vec![0.0; config.depth]; config.width];
+        for (x, column) in moisture.iter_mut().enumerate() {
+            for (z, cell) in column.iter_mut().enumerate() {
+                let nx = x as f32 / config.moisture_scale;
+                let nz = z as f32 / config.moisture_scale;
+                let value = moisture_noise.fractal_noise2d(nx, nz, 4, 0.5, 2.0);
+                *cell = ((value + 1.0) / 2.0).clamp(0.0, 1.0);
             }
         }
 
-        Self {
+        let mut terrain = Self {
             config,
             heightmap,
-            vertices,
-            colors,
+            moisture,
+            vertices: Vec::new(),
+            colors: Vec::new(),
+            material_groups: Vec::new(),
+            model: None,
+        };
+        terrain.rebuild_vertices();
+        terrain
+    }
+
+    /// Regenerate vertices and colors from the current heightmap
+    pub fn rebuild_vertices(&mut self) {
+        self.vertices.clear();
+        self.colors.clear();
+
+        let (width, depth, height_scale) = (self.config.width, self.config.depth, self.config.height_scale);
+        for x in 0..width {
+            for z in 0..depth {
+                let (world_x, world_z) = self.grid_to_world(x as f32, z as f32);
+                let world_y = self.heightmap[x][z];
+
+                self.vertices.push(Vector3::new(world_x, world_y, world_z));
+
+                // Color based on height and moisture
+                let color = Self::height_to_biome_color(world_y, self.moisture[x][z], height_scale);
+                self.colors.push(color);
+            }
         }
+
+        self.material_groups = self.build_material_groups();
     }
 
-    /// Convert height to color (terrain coloring)
-    fn height_to_color(height: f32, max_height: f32) -> Color {
+    /// Convert height and moisture to color (terrain coloring)
+    pub fn height_to_biome_color(height: f32, moisture: f32, max_height: f32) -> Color {
         let normalized = (height / max_height + 1.0) / 2.0; // 0.0 to 1.0
 
         if normalized < 0.3 {
@@ -106,26 +177,41 @@ impl Terrain {
         } else if normalized < 0.45 {
             // Beach/sand
             Color::new(210, 180, 140, 255)
-        } else if normalized < 0.6 {
-            // Grass
-            Color::new(50, 150, 50, 255)
-        } else if normalized < 0.75 {
-            // Dark grass/dirt
-            Color::new(100, 120, 60, 255)
-        } else if normalized < 0.85 {
-            // Rock
-            Color::new(120, 120, 120, 255)
         } else {
-            // Snow
-            Color::new(240, 240, 250, 255)
+            Biome::from_height_and_moisture(height, moisture, max_height).color()
         }
     }
 
+    /// Convert a world XZ position to (fractional) heightmap grid coordinates
+    /// The grid is centered on the world origin and spaced `cell_size` apart
+    pub fn world_to_grid(&self, world_x: f32, world_z: f32) -> (f32, f32) {
+        (
+            world_x / self.config.cell_size + self.config.width as f32 / 2.0,
+            world_z / self.config.cell_size + self.config.depth as f32 / 2.0,
+        )
+    }
+
+    /// Convert heightmap grid coordinates to a world XZ position
+    pub fn grid_to_world(&self, grid_x: f32, grid_z: f32) -> (f32, f32) {
+        (
+            (grid_x - self.config.width as f32 / 2.0) * self.config.cell_size,
+            (grid_z - self.config.depth as f32 / 2.0) * self.config.cell_size,
+        )
+    }
+
+    /// World XZ extent of the heightmap as (min, max) corners
+    pub fn world_bounds(&self) -> (Vector2, Vector2) {
+        let (min_x, min_z) = self.grid_to_world(0.0, 0.0);
+        let (max_x, max_z) = self.grid_to_world(
+            self.config.width.saturating_sub(1) as f32,
+            self.config.depth.saturating_sub(1) as f32,
+        );
+        (Vector2::new(min_x, min_z), Vector2::new(max_x, max_z))
+    }
+
     /// Get height at world position (bilinear interpolation)
     pub fn get_height_at(&self, world_x: f32, world_z: f32) -> f32 {
-        // Convert world coordinates to grid coordinates
-        let grid_x = (world_x / self.config.cell_size) + (self.config.width as f32 / 2.0);
-        let grid_z = (world_z / self.config.cell_size) + (self.config.depth as f32 / 2.0);
+        let (grid_x, grid_z) = self.world_to_grid(world_x, world_z);
 
         // Clamp to valid range
         if grid_x < 0.0
@@ -157,35 +243,167 @@ impl Terrain {
         h0 * (1.0 - fz) + h1 * fz
     }
 
-    /// Render terrain using grid of triangles
-    pub fn render(&self, d: &mut RaylibMode3D<RaylibDrawHandle>) {
-        // Draw terrain as a grid of quads (2 triangles each)
-        for x in 0..(self.config.width - 1) {
-            for z in 0..(self.config.depth - 1) {
-                let idx00 = x * self.config.depth + z;
-                let idx10 = (x + 1) * self.config.depth + z;
-                let idx01 = x * self.config.depth + (z + 1);
-                let idx11 = (x + 1) * self.config.depth + (z + 1);
+    /// Get slope angle (in degrees) at world position using central differences
+    pub fn get_slope_at(&self, world_x: f32, world_z: f32) -> f32 {
+        let step = self.config.cell_size * 0.5;
 
-                let v00 = self.vertices[idx00];
-                let v10 = self.vertices[idx10];
-                let v01 = self.vertices[idx01];
-                let v11 = self.vertices[idx11];
+        let dx = (self.get_height_at(world_x + step, world_z)
+            - self.get_height_at(world_x - step, world_z))
+            / (2.0 * step);
+        let dz = (self.get_height_at(world_x, world_z + step)
+            - self.get_height_at(world_x, world_z - step))
+            / (2.0 * step);
 
-                let c00 = self.colors[idx00];
-                let c10 = self.colors[idx10];
-                let c01 = self.colors[idx01];
-                let c11 = self.colors[idx11];
+        (dx * dx + dz * dz).sqrt().atan().to_degrees()
+    }
 
-                // Average color for each triangle
-                let color1 = Self::average_color(&[c00, c10, c01]);
-                let color2 = Self::average_color(&[c10, c11, c01]);
+    /// Surface normal at world position using central differences
+    pub fn get_normal_at(&self, world_x: f32, world_z: f32) -> Vector3 {
+        let step = self.config.cell_size * 0.5;
 
-                // First triangle
-                d.draw_triangle3D(v00, v10, v01, color1);
+        let dx = (self.get_height_at(world_x + step, world_z)
+            - self.get_height_at(world_x - step, world_z))
+            / (2.0 * step);
+        let dz = (self.get_height_at(world_x, world_z + step)
+            - self.get_height_at(world_x, world_z - step))
+            / (2.0 * step);
 
-                // Second triangle
-                d.draw_triangle3D(v10, v11, v01, color2);
+        Vector3::new(-dx, 1.0, -dz).normalized()
+    }
+
+    /// Height below which terrain is rendered as water
+    pub fn water_level(&self) -> f32 {
+        // Matches the shallow water band in height_to_biome_color
+        -0.2 * self.config.height_scale
+    }
+
+    /// Normal at a heightmap vertex from the neighbouring heights
+    /// (central differences, one-sided at the edges)
+    pub fn normal_at_vertex(&self, x: usize, z: usize) -> Vector3 {
+        let (width, depth) = (self.config.width, self.config.depth);
+        let (x0, x1) = (x.saturating_sub(1), (x + 1).min(width - 1));
+        let (z0, z1) = (z.saturating_sub(1), (z + 1).min(depth - 1));
+        let cell = self.config.cell_size;
+
+        let dx = (self.heightmap[x1][z] - self.heightmap[x0][z]) / ((x1 - x0).max(1) as f32 * cell);
+        let dz = (self.heightmap[x][z1] - self.heightmap[x][z0]) / ((z1 - z0).max(1) as f32 * cell);
+        Vector3::new(-dx, 1.0, -dz).normalized()
+    }
+
+    /// Build a raylib mesh of the whole heightmap with normals and vertex colors
+    ///
+    /// Buffers are allocated with raylib's allocator so `UnloadMesh`/`UnloadModel`
+    /// can free them. Grids with more vertices than 16-bit indices can address
+    /// are emitted unindexed, three vertices per triangle.
+    pub fn build_mesh(&self) -> ffi::Mesh {
+        let (width, depth) = (self.config.width, self.config.depth);
+        let mut triangles: Vec<[usize; 3]> = Vec::with_capacity(width.saturating_sub(1) * depth.saturating_sub(1) * 2);
+        for x in 0..width.saturating_sub(1) {
+            for z in 0..depth.saturating_sub(1) {
+                let idx00 = x * depth + z;
+                let idx10 = (x + 1) * depth + z;
+                let idx01 = x * depth + (z + 1);
+                let idx11 = (x + 1) * depth + (z + 1);
+
+                // Counter-clockwise seen from above so the faces survive backface culling
+                triangles.push([idx00, idx01, idx10]);
+                triangles.push([idx10, idx01, idx11]);
+            }
+        }
+
+        let normals: Vec<Vector3> = (0..width * depth)
+            .map(|i| self.normal_at_vertex(i / depth, i % depth))
+            .collect();
+        let indexed = width * depth <= u16::MAX as usize + 1;
+        let order: Vec<usize> = if indexed {
+            (0..width * depth).collect()
+        } else {
+            triangles.iter().flatten().copied().collect()
+        };
+
+        // SAFETY: zeroed pointers and ids are raylib's "not set" values; every
+        // buffer written below is allocated with the length it is written with
+        unsafe {
+            let mut mesh: ffi::Mesh = std::mem::zeroed();
+            mesh.vertexCount = order.len() as i32;
+            mesh.triangleCount = triangles.len() as i32;
+            let vertices: Vec<f32> = order.iter().flat_map(|&i| {
+                let v = self.vertices[i];
+                [v.x, v.y, v.z]
+            }).collect();
+            let normals: Vec<f32> = order.iter().flat_map(|&i| {
+                let n = normals[i];
+                [n.x, n.y, n.z]
+            }).collect();
+            let colors: Vec<u8> = order.iter().flat_map(|&i| {
+                let c = self.colors[i];
+                [c.r, c.g, c.b, c.a]
+            }).collect();
+            mesh.vertices = Self::alloc_buffer(&vertices);
+            mesh.normals = Self::alloc_buffer(&normals);
+            mesh.colors = Self::alloc_buffer(&colors);
+            if indexed {
+                let indices: Vec<u16> = triangles.iter().flatten().map(|&i| i as u16).collect();
+                mesh.indices = Self::alloc_buffer(&indices);
+            }
+            mesh
+        }
+    }
+
+    /// Copy values into a buffer from raylib's allocator
+    unsafe fn alloc_buffer<T: Copy>(values: &[T]) -> *mut T {
+        // SAFETY: MemAlloc returns memory of the requested size (or null)
+        unsafe {
+            let buffer = ffi::MemAlloc(std::mem::size_of_val(values) as u32) as *mut T;
+            if !buffer.is_null() {
+                std::ptr::copy_nonoverlapping(values.as_ptr(), buffer, values.len());
+            }
+            buffer
+        }
+    }
+
+    /// Upload the terrain mesh to the GPU once; `render` then draws it as a model
+    /// Call again after editing the heightmap to replace the old model
+    pub fn upload(&mut self, _thread: &RaylibThread) {
+        self.cleanup();
+        let mut mesh = self.build_mesh();
+        unsafe {
+            ffi::UploadMesh(&mut mesh, false);
+            self.model = Some(ffi::LoadModelFromMesh(mesh));
+        }
+    }
+
+    /// Whether `upload` has built a GPU model
+    pub fn is_uploaded(&self) -> bool {
+        self.model.is_some()
+    }
+
+    /// Free the uploaded GPU model, if any
+    pub fn cleanup(&mut self) {
+        if let Some(model) = self.model.take() {
+            unsafe {
+                ffi::UnloadModel(model);
+            }
+        }
+    }
+
+    /// Render the uploaded model, or fall back to drawing each biome group's
+    /// triangles when the terrain hasn't been uploaded
+    pub fn render(&self, d: &mut RaylibMode3D<RaylibDrawHandle>) {
+        if let Some(model) = self.model {
+            unsafe {
+                ffi::DrawModel(model, Vector3::zero().into(), 1.0, Color::WHITE.into());
+            }
+            return;
+        }
+
+        for group in &self.material_groups {
+            for triangle in group.indices.chunks_exact(3) {
+                let [a, b, c] = [triangle[0] as usize, triangle[1] as usize, triangle[2] as usize];
+
+                // Average color for each triangle
+                let color = Self::average_color(&[self.colors[a], self.colors[b], self.colors[c]]);
+                d.draw_triangle3D(self.vertices[a], self.vertices[b], self.vertices[c], color);
             }
         }
     }
@@ -225,3 +443,131 @@ impl Terrain {
         )
     }
 }
+
+impl Drop for Terrain {
+    fn drop(&mut self) {
+        self.cleanup();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small_terrain(cell_size: f32) -> Terrain {
+        Terrain::generate(TerrainConfig {
+            width: 32,
+            depth: 16,
+            cell_size,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_same_seed_generates_identical_heightmaps() {
+        let bits = |terrain: &Terrain| -> Vec<u32> {
+            terrain.heightmap.iter().flatten().chain(terrain.moisture.iter().flatten()).map(|h| h.to_bits()).collect()
+        };
+        let first = small_terrain(1.0);
+        let second = small_terrain(1.0);
+        assert_eq!(bits(&first), bits(&second));
+
+        let reseeded = Terrain::generate(TerrainConfig { seed: first.config.seed + 1, ..first.config.clone() });
+        assert_ne!(bits(&first), bits(&reseeded));
+    }
+
+    #[test]
+    fn test_world_grid_round_trip() {
+        let terrain = small_terrain(2.5);
+        for &(x, z) in &[(0.0, 0.0), (-12.3, 7.9), (31.0, -19.5)] {
+            let (gx, gz) = terrain.world_to_grid(x, z);
+            let (wx, wz) = terrain.grid_to_world(gx, gz);
+            assert!((wx - x).abs() < 1e-4 && (wz - z).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_cell_size_maps_vertices_and_heights() {
+        let terrain = small_terrain(2.5);
+
+        // Origin is the grid center; each cell is 2.5 world units wide
+        assert_eq!(terrain.world_to_grid(0.0, 0.0), (16.0, 8.0));
+        assert_eq!(terrain.world_to_grid(5.0, -2.5), (18.0, 7.0));
+        assert_eq!(terrain.grid_to_world(0.0, 0.0), (-40.0, -20.0));
+
+        // Vertices sit where grid_to_world says and heights sample back exactly
+        let (x, z) = (20, 5);
+        let vertex = terrain.vertices[x * terrain.config.depth + z];
+        assert_eq!((vertex.x, vertex.z), terrain.grid_to_world(x as f32, z as f32));
+        assert!((terrain.get_height_at(vertex.x, vertex.z) - terrain.heightmap[x][z]).abs() < 1e-4);
+
+        let (min, max) = terrain.world_bounds();
+        assert_eq!(min, Vector2::new(-40.0, -20.0));
+        assert_eq!(max, Vector2::new(37.5, 17.5));
+    }
+
+    #[test]
+    fn test_build_mesh_counts_and_normals() {
+        let mut terrain = small_terrain(1.0);
+        let (width, depth) = (terrain.config.width, terrain.config.depth);
+
+        let mesh = terrain.build_mesh();
+        assert_eq!(mesh.triangleCount as usize, (width - 1) * (depth - 1) * 2);
+        assert_eq!(mesh.vertexCount as usize, width * depth);
+
+        unsafe {
+            let indices = std::slice::from_raw_parts(mesh.indices, mesh.triangleCount as usize * 3);
+            assert!(indices.iter().all(|&i| (i as usize) < width * depth));
+            let vertices = std::slice::from_raw_parts(mesh.vertices, mesh.vertexCount as usize * 3);
+            assert_eq!(vertices[3 * 5 + 1], terrain.vertices[5].y);
+            for buffer in [mesh.vertices as *mut std::ffi::c_void, mesh.normals as _, mesh.colors as _, mesh.indices as _] {
+                ffi::MemFree(buffer);
+            }
+        }
+
+        // Flat ground points straight up, a slope rising in +x leans towards -x
+        for column in terrain.heightmap.iter_mut() {
+            column.fill(0.0);
+        }
+        assert_eq!(terrain.normal_at_vertex(4, 4), Vector3::new(0.0, 1.0, 0.0));
+        for (x, column) in terrain.heightmap.iter_mut().enumerate() {
+            column.fill(x as f32);
+        }
+        let normal = terrain.normal_at_vertex(4, 4);
+        assert!((normal.x + std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-5);
+        assert!((normal.y - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-5);
+        assert!(!terrain.is_uploaded());
+    }
+
+    #[test]
+    fn test_noise_mode_changes_heightmap() {
+        let config = TerrainConfig { width: 24, depth: 24, ..Default::default() };
+        let fractal = Terrain::generate(config.clone());
+        let ridged = Terrain::generate(TerrainConfig { noise_mode: NoiseMode::Ridged, ..config });
+
+        assert!(ridged.heightmap.iter().flatten().all(|&h| h >= 0.0));
+        assert_ne!(fractal.heightmap, ridged.heightmap);
+    }
+
+    #[test]
+    fn test_moisture_picks_biome_color() {
+        let mut terrain = Terrain::generate(TerrainConfig { width: 8, depth: 8, ..Default::default() });
+        assert!(terrain.moisture.iter().flatten().all(|m| (0.0..=1.0).contains(m)));
+
+        // Low-lying land, wet on the left half and dry on the right
+        for column in terrain.heightmap.iter_mut() {
+            column.fill(0.0);
+        }
+        for (x, column) in terrain.moisture.iter_mut().enumerate() {
+            column.fill(if x < 4 { 0.9 } else { 0.1 });
+        }
+        terrain.rebuild_vertices();
+
+        let forest = Biome::Forest.color();
+        let desert = Biome::Desert.color();
+        assert_eq!(terrain.colors[0], forest);
+        assert_eq!(terrain.colors[7 * 8 + 7], desert);
+        assert_eq!(Terrain::height_to_biome_color(0.0, 0.9, 10.0), forest);
+        assert_eq!(Terrain::height_to_biome_color(0.0, 0.1, 10.0), desert);
+    }
+}
@@ -1,5 +1,13 @@
 pub mod noise;
 pub mod generator;
+pub mod scatter;
+pub mod erosion;
+pub mod biome;
+pub mod pathfinding;
 
 pub use noise::*;
 pub use generator::*;
+pub use scatter::*;
+pub use erosion::*;
+pub use biome::*;
+pub use pathfinding::*;
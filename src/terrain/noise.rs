@@ -94,6 +94,145 @@ impl PerlinNoise {
         total / max_value
     }
 
+    /// Ridged multifractal noise in [0, 1]: octaves of `1 - |noise|`, squared to
+    /// sharpen the creases into mountain ridges
+    pub fn ridged_noise2d(
+        &self,
+        x: f32,
+        y: f32,
+        octaves: u32,
+        persistence: f32,
+        lacunarity: f32,
+    ) -> f32 {
+        self.accumulate_octaves2d(x, y, octaves, persistence, lacunarity, |n| {
+            let ridge = 1.0 - n.abs();
+            ridge * ridge
+        })
+    }
+
+    /// Billow noise in [0, 1]: octaves of `|noise|`, giving rounded, puffy hills
+    pub fn billow_noise2d(
+        &self,
+        x: f32,
+        y: f32,
+        octaves: u32,
+        persistence: f32,
+        lacunarity: f32,
+    ) -> f32 {
+        self.accumulate_octaves2d(x, y, octaves, persistence, lacunarity, f32::abs)
+    }
+
+    /// Sum `shape(noise)` over octaves, normalized by the total amplitude
+    fn accumulate_octaves2d(
+        &self,
+        x: f32,
+        y: f32,
+        octaves: u32,
+        persistence: f32,
+        lacunarity: f32,
+        shape: impl Fn(f32) -> f32,
+    ) -> f32 {
+        let mut total = 0.0;
+        let mut frequency = 1.0;
+        let mut amplitude = 1.0;
+        let mut max_value = 0.0;
+
+        for _ in 0..octaves {
+            total += shape(self.noise2d(x * frequency, y * frequency).clamp(-1.0, 1.0)) * amplitude;
+            max_value += amplitude;
+            amplitude *= persistence;
+            frequency *= lacunarity;
+        }
+
+        if max_value > 0.0 { total / max_value } else { 0.0 }
+    }
+
+    /// Generate 3D Perlin noise at given coordinates, in [-1, 1]
+    pub fn noise3d(&self, x: f32, y: f32, z: f32) -> f32 {
+        // Find unit cube containing point
+        let xi = (x.floor() as i32 & 255) as usize;
+        let yi = (y.floor() as i32 & 255) as usize;
+        let zi = (z.floor() as i32 & 255) as usize;
+
+        // Relative position within cube
+        let xf = x - x.floor();
+        let yf = y - y.floor();
+        let zf = z - z.floor();
+
+        let u = Self::fade(xf);
+        let v = Self::fade(yf);
+        let w = Self::fade(zf);
+
+        // Hash coordinates of the 8 corners
+        let p = &self.permutation;
+        let a = p[xi] as usize + yi;
+        let aa = p[a] as usize + zi;
+        let ab = p[a + 1] as usize + zi;
+        let b = p[xi + 1] as usize + yi;
+        let ba = p[b] as usize + zi;
+        let bb = p[b + 1] as usize + zi;
+
+        // Trilinear interpolation of the corner gradients
+        let x1 = Self::lerp(Self::grad3d(p[aa] as usize, xf, yf, zf), Self::grad3d(p[ba] as usize, xf - 1.0, yf, zf), u);
+        let x2 = Self::lerp(
+            Self::grad3d(p[ab] as usize, xf, yf - 1.0, zf),
+            Self::grad3d(p[bb] as usize, xf - 1.0, yf - 1.0, zf),
+            u,
+        );
+        let y1 = Self::lerp(x1, x2, v);
+
+        let x1 = Self::lerp(
+            Self::grad3d(p[aa + 1] as usize, xf, yf, zf - 1.0),
+            Self::grad3d(p[ba + 1] as usize, xf - 1.0, yf, zf - 1.0),
+            u,
+        );
+        let x2 = Self::lerp(
+            Self::grad3d(p[ab + 1] as usize, xf, yf - 1.0, zf - 1.0),
+            Self::grad3d(p[bb + 1] as usize, xf - 1.0, yf - 1.0, zf - 1.0),
+            u,
+        );
+        let y2 = Self::lerp(x1, x2, v);
+
+        // The 12-edge gradient set can slightly overshoot 1.0 in rare spots
+        Self::lerp(y1, y2, w).clamp(-1.0, 1.0)
+    }
+
+    /// Octave-based fractal 3D noise, same octave scheme as `fractal_noise2d`
+    pub fn fractal_noise3d(
+        &self,
+        x: f32,
+        y: f32,
+        z: f32,
+        octaves: u32,
+        persistence: f32,
+        lacunarity: f32,
+    ) -> f32 {
+        let mut total = 0.0;
+        let mut frequency = 1.0;
+        let mut amplitude = 1.0;
+        let mut max_value = 0.0;
+
+        for _ in 0..octaves {
+            total += self.noise3d(x * frequency, y * frequency, z * frequency) * amplitude;
+            max_value += amplitude;
+            amplitude *= persistence;
+            frequency *= lacunarity;
+        }
+
+        total / max_value
+    }
+
+    /// 2D noise sampled at coordinates pushed around by two other noise lookups
+    ///
+    /// `strength` is how far (in noise units) a point can be displaced; 0 gives
+    /// plain `noise2d`. Useful for twisted coastlines and less grid-like terrain.
+    pub fn domain_warp2d(&self, x: f32, y: f32, strength: f32) -> f32 {
+        // Offsets decorrelate the two warp lookups from each other and the sample
+        let warp_x = self.noise2d(x + 5.2, y + 1.3);
+        let warp_y = self.noise2d(x + 1.7, y + 9.2);
+        self.noise2d(x + warp_x * strength, y + warp_y * strength)
+    }
+
     /// Fade function (6t^5 - 15t^4 + 10t^3)
     fn fade(t: f32) -> f32 {
         t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
@@ -116,6 +255,24 @@ impl PerlinNoise {
             _ => 0.0,
         }
     }
+
+    /// Compute gradient dot product against one of the 12 cube edge directions
+    fn grad3d(hash: usize, x: f32, y: f32, z: f32) -> f32 {
+        match hash & 15 {
+            0 | 12 => x + y,
+            1 | 14 => -x + y,
+            2 => x - y,
+            3 => -x - y,
+            4 => x + z,
+            5 => -x + z,
+            6 => x - z,
+            7 => -x - z,
+            8 => y + z,
+            9 | 13 => -y + z,
+            10 => y - z,
+            _ => -y - z,
+        }
+    }
 }
 
 impl Default for PerlinNoise {
@@ -169,4 +326,53 @@ mod tests {
         let value = noise.fractal_noise2d(1.5, 2.3, 4, 0.5, 2.0);
         assert!(value >= -1.0 && value <= 1.0);
     }
+
+    #[test]
+    fn test_noise3d_bounds() {
+        let noise = PerlinNoise::new(7);
+        for i in 0..2000 {
+            let t = i as f32 * 0.137;
+            let value = noise.noise3d(t, t * 0.61 + 3.3, -t * 1.27);
+            assert!((-1.0..=1.0).contains(&value), "noise3d out of range: {}", value);
+
+            let fractal = noise.fractal_noise3d(t, 2.0 - t, t * 0.5, 5, 0.5, 2.0);
+            assert!((-1.0..=1.0).contains(&fractal), "fractal_noise3d out of range: {}", fractal);
+        }
+
+        // Zero on integer lattice points, but not everywhere
+        assert_eq!(noise.noise3d(3.0, 4.0, 5.0), 0.0);
+        assert!((0..50).any(|i| noise.noise3d(i as f32 * 0.31, 0.5, 0.25).abs() > 0.05));
+    }
+
+    #[test]
+    fn test_domain_warp_changes_samples() {
+        let noise = PerlinNoise::new(42);
+        let points: Vec<(f32, f32)> = (0..50).map(|i| (i as f32 * 0.29, i as f32 * 0.17 + 0.5)).collect();
+
+        assert!(points.iter().all(|&(x, y)| noise.domain_warp2d(x, y, 0.0) == noise.noise2d(x, y)));
+        let differing = points
+            .iter()
+            .filter(|&&(x, y)| (noise.domain_warp2d(x, y, 2.0) - noise.noise2d(x, y)).abs() > 1e-3)
+            .count();
+        assert!(differing > 40, "only {} samples changed", differing);
+        assert!(points.iter().all(|&(x, y)| (-1.0..=1.0).contains(&noise.domain_warp2d(x, y, 4.0))));
+    }
+
+    #[test]
+    fn test_ridged_and_billow_noise() {
+        let noise = PerlinNoise::new(42);
+        let mut differs = 0;
+        for i in 0..500 {
+            let (x, y) = (i as f32 * 0.173, i as f32 * 0.091 + 0.4);
+            let ridged = noise.ridged_noise2d(x, y, 5, 0.5, 2.0);
+            let billow = noise.billow_noise2d(x, y, 5, 0.5, 2.0);
+            assert!((0.0..=1.0).contains(&ridged), "ridged out of range: {}", ridged);
+            assert!((0.0..=1.0).contains(&billow), "billow out of range: {}", billow);
+
+            if (ridged - noise.fractal_noise2d(x, y, 5, 0.5, 2.0)).abs() > 1e-3 {
+                differs += 1;
+            }
+        }
+        assert!(differs > 450, "ridged matched fractal too often: {}", differs);
+    }
 }
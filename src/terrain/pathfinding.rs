@@ -0,0 +1,198 @@
+/// Terrain Pathfinding
+/// A* over the heightmap grid, avoiding slopes too steep to walk
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use raylib::prelude::*;
+use super::generator::Terrain;
+
+/// Grid steps to the 8 neighbours of a node
+const NEIGHBOURS: [(isize, isize); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+/// Open-set entry, ordered so the heap pops the lowest estimated cost first
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct OpenNode {
+    estimate: f32,
+    index: usize,
+}
+
+impl Eq for OpenNode {}
+
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate).then(other.index.cmp(&self.index))
+    }
+}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Find a walkable path across the terrain between two world XZ positions
+///
+/// The heightmap grid is the navigation graph (8-connected, no cutting corners
+/// past blocked nodes). A node is impassable when `Terrain::get_slope_at` there
+/// exceeds `max_slope` degrees. Returns the node positions from the one nearest
+/// `start` to the one nearest `goal`, or `None` if either end is blocked or
+/// unreachable.
+pub fn astar_path(terrain: &Terrain, start: Vector2, goal: Vector2, max_slope: f32) -> Option<Vec<Vector2>> {
+    let (width, depth) = (terrain.config.width, terrain.config.depth);
+    if width == 0 || depth == 0 {
+        return None;
+    }
+
+    let to_node = |point: Vector2| {
+        let (grid_x, grid_z) = terrain.world_to_grid(point.x, point.y);
+        let x = grid_x.round().clamp(0.0, (width - 1) as f32) as usize;
+        let z = grid_z.round().clamp(0.0, (depth - 1) as f32) as usize;
+        x * depth + z
+    };
+    let position = |index: usize| {
+        let (x, z) = terrain.grid_to_world((index / depth) as f32, (index % depth) as f32);
+        Vector2::new(x, z)
+    };
+
+    let mut walkable: Vec<Option<bool>> = vec![None; width * depth];
+    let mut is_walkable = |index: usize| {
+        *walkable[index].get_or_insert_with(|| {
+            let point = position(index);
+            terrain.get_slope_at(point.x, point.y) <= max_slope
+        })
+    };
+
+    let (start_index, goal_index) = (to_node(start), to_node(goal));
+    if !is_walkable(start_index) || !is_walkable(goal_index) {
+        return None;
+    }
+
+    let goal_position = position(goal_index);
+    let mut cost = vec![f32::INFINITY; width * depth];
+    let mut came_from = vec![usize::MAX; width * depth];
+    let mut open = BinaryHeap::new();
+    cost[start_index] = 0.0;
+    open.push(OpenNode {
+        estimate: position(start_index).distance_to(goal_position),
+        index: start_index,
+    });
+
+    while let Some(OpenNode { estimate, index }) = open.pop() {
+        if index == goal_index {
+            let mut path = vec![position(index)];
+            let mut current = index;
+            while current != start_index {
+                current = came_from[current];
+                path.push(position(current));
+            }
+            path.reverse();
+            return Some(path);
+        }
+        // Stale entry left behind after a cheaper route was found
+        if estimate > cost[index] + position(index).distance_to(goal_position) + f32::EPSILON {
+            continue;
+        }
+
+        let (x, z) = ((index / depth) as isize, (index % depth) as isize);
+        for (dx, dz) in NEIGHBOURS {
+            let (nx, nz) = (x + dx, z + dz);
+            if nx < 0 || nz < 0 || nx >= width as isize || nz >= depth as isize {
+                continue;
+            }
+            let neighbour = nx as usize * depth + nz as usize;
+            if !is_walkable(neighbour) {
+                continue;
+            }
+            if dx != 0
+                && dz != 0
+                && (!is_walkable(nx as usize * depth + z as usize) || !is_walkable(x as usize * depth + nz as usize))
+            {
+                continue;
+            }
+
+            let step = position(index).distance_to(position(neighbour));
+            let next_cost = cost[index] + step;
+            if next_cost < cost[neighbour] {
+                cost[neighbour] = next_cost;
+                came_from[neighbour] = index;
+                open.push(OpenNode {
+                    estimate: next_cost + position(neighbour).distance_to(goal_position),
+                    index: neighbour,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::generator::TerrainConfig;
+
+    /// Flat 32x32 terrain with a tall ridge along grid x = 16, open for z >= 26
+    fn ridge_terrain() -> Terrain {
+        let mut terrain = Terrain::generate(TerrainConfig {
+            width: 32,
+            depth: 32,
+            cell_size: 1.0,
+            ..Default::default()
+        });
+        for column in terrain.heightmap.iter_mut() {
+            column.fill(0.0);
+        }
+        for z in 0..26 {
+            terrain.heightmap[16][z] = 10.0;
+        }
+        terrain
+    }
+
+    #[test]
+    fn test_path_routes_around_ridge() {
+        let terrain = ridge_terrain();
+        let (start_x, start_z) = terrain.grid_to_world(8.0, 8.0);
+        let (goal_x, goal_z) = terrain.grid_to_world(24.0, 8.0);
+        let start = Vector2::new(start_x, start_z);
+        let goal = Vector2::new(goal_x, goal_z);
+
+        let path = astar_path(&terrain, start, goal, 30.0).expect("a path around the ridge");
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+
+        for point in &path {
+            assert!(terrain.get_slope_at(point.x, point.y) <= 30.0);
+        }
+        // It has to detour through the gap at the far end of the ridge
+        let furthest = path.iter().map(|p| terrain.world_to_grid(p.x, p.y).1).fold(f32::MIN, f32::max);
+        assert!(furthest >= 26.0, "path never reached the gap: {:?}", path);
+        assert!(path.len() > 17);
+
+        // Without the gap there is no way across
+        let mut walled = terrain;
+        for z in 0..32 {
+            walled.heightmap[16][z] = 10.0;
+        }
+        assert!(astar_path(&walled, start, goal, 30.0).is_none());
+    }
+
+    #[test]
+    fn test_flat_path_is_straight() {
+        let terrain = ridge_terrain();
+        let (start_x, start_z) = terrain.grid_to_world(2.0, 28.0);
+        let (goal_x, goal_z) = terrain.grid_to_world(12.0, 28.0);
+
+        let path = astar_path(&terrain, Vector2::new(start_x, start_z), Vector2::new(goal_x, goal_z), 30.0).unwrap();
+        assert_eq!(path.len(), 11);
+        assert!(path.iter().all(|p| p.y == start_z));
+    }
+}
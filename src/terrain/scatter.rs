@@ -0,0 +1,130 @@
+/// Terrain Object Scatter
+/// Deterministic noise-thresholded placement of props (trees, rocks) on terrain
+
+use raylib::prelude::*;
+use super::generator::Terrain;
+use super::noise::PerlinNoise;
+
+/// Frequency of the clustering noise (in grid cells)
+const CLUSTER_SCALE: f32 = 12.0;
+
+/// Scatter positions across the terrain surface
+///
+/// `density` is the average fraction of grid cells that receive an object (0.0 - 1.0).
+/// Noise clusters the placement so objects form groves rather than uniform coverage.
+/// Cells steeper than `slope_max` degrees or below the water level are skipped.
+/// The same seed always produces the same positions.
+pub fn scatter_positions(terrain: &Terrain, density: f32, seed: u32, slope_max: f32) -> Vec<Vector3> {
+    let config = &terrain.config;
+    let noise = PerlinNoise::new(seed);
+    let density = density.clamp(0.0, 1.0);
+    let water_level = terrain.water_level();
+
+    let mut positions = Vec::new();
+
+    // Skip the outer ring of cells so samples never fall off the heightmap edge
+    for x in 1..config.width.saturating_sub(2) {
+        for z in 1..config.depth.saturating_sub(2) {
+            // Clustering factor in 0.0 - 2.0 (averages to ~1.0)
+            let cluster = noise.fractal_noise2d(
+                x as f32 / CLUSTER_SCALE,
+                z as f32 / CLUSTER_SCALE,
+                3,
+                0.5,
+                2.0,
+            ) + 1.0;
+
+            if hash01(seed, x as u32, z as u32, 0) >= density * cluster {
+                continue;
+            }
+
+            // Jitter within the cell so objects don't line up on the grid
+            let jitter_x = hash01(seed, x as u32, z as u32, 1);
+            let jitter_z = hash01(seed, x as u32, z as u32, 2);
+
+            let (world_x, world_z) = terrain.grid_to_world(x as f32 + jitter_x, z as f32 + jitter_z);
+            let world_y = terrain.get_height_at(world_x, world_z);
+
+            if world_y < water_level {
+                continue;
+            }
+
+            if terrain.get_slope_at(world_x, world_z) > slope_max {
+                continue;
+            }
+
+            positions.push(Vector3::new(world_x, world_y, world_z));
+        }
+    }
+
+    positions
+}
+
+/// Hash a grid cell into a pseudo-random value in 0.0 - 1.0
+fn hash01(seed: u32, x: u32, z: u32, channel: u32) -> f32 {
+    let mut h = seed
+        .wrapping_mul(0x9E37_79B9)
+        ^ x.wrapping_mul(0x85EB_CA6B)
+        ^ z.wrapping_mul(0xC2B2_AE35)
+        ^ channel.wrapping_mul(0x27D4_EB2F);
+
+    // Final avalanche (murmur3 fmix32)
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85EB_CA6B);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xC2B2_AE35);
+    h ^= h >> 16;
+
+    (h >> 8) as f32 / (1u32 << 24) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terrain::TerrainConfig;
+
+    fn test_terrain() -> Terrain {
+        Terrain::generate(TerrainConfig {
+            width: 64,
+            depth: 64,
+            cell_size: 2.0,
+            height_scale: 15.0,
+            noise_scale: 20.0,
+            seed: 7,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_scatter_deterministic() {
+        let terrain = test_terrain();
+        let a = scatter_positions(&terrain, 0.2, 99, 45.0);
+        let b = scatter_positions(&terrain, 0.2, 99, 45.0);
+        let c = scatter_positions(&terrain, 0.2, 100, 45.0);
+
+        assert!(!a.is_empty());
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_scatter_respects_slope_max() {
+        let terrain = test_terrain();
+        let steep = scatter_positions(&terrain, 0.5, 3, 90.0);
+        let gentle = scatter_positions(&terrain, 0.5, 3, 10.0);
+
+        assert!(gentle.len() < steep.len());
+        for p in &gentle {
+            assert!(terrain.get_slope_at(p.x, p.z) <= 10.0);
+        }
+    }
+
+    #[test]
+    fn test_scatter_on_surface() {
+        let terrain = test_terrain();
+        for p in scatter_positions(&terrain, 0.3, 5, 60.0) {
+            assert!((p.y - terrain.get_height_at(p.x, p.z)).abs() < 1e-4);
+            assert!(p.y >= terrain.water_level());
+        }
+    }
+}